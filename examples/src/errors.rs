@@ -1,4 +1,7 @@
 use diesel;
+use rustiful::JsonApiError;
+use rustiful::JsonApiErrorDetails;
+use rustiful::ConversionError;
 use std::error::Error;
 use std::fmt::Display;
 use std::fmt::Formatter;
@@ -7,18 +10,19 @@ use std::fmt::Result;
 #[derive(Debug)]
 /// This error wraps any error returned from the database, along with any errors returned from
 /// attempting to convert a `Todo::Resource` (aka the JSONAPI representation of a `Todo`) to a
-/// `Todo`. Any error used in `JsonGet`, `JsonIndex` et cetera has to implement `std::error::Error`.
+/// `Todo`. Any error used in `JsonGet`, `JsonIndex` et cetera has to implement `std::error::Error`
+/// and `JsonApiErrorDetails`.
 pub enum MyErr {
     Diesel(diesel::result::Error),
     TooManySortColumns(String),
-    UpdateError(String)
+    Invalid(ConversionError)
 }
 
 impl Error for MyErr {
     fn description(&self) -> &str {
         match *self {
             MyErr::Diesel(ref err) => err.description(),
-            MyErr::UpdateError(ref err) => err,
+            MyErr::Invalid(ref err) => err.description(),
             MyErr::TooManySortColumns(ref err) => err
         }
     }
@@ -26,7 +30,7 @@ impl Error for MyErr {
     fn cause(&self) -> Option<&Error> {
         match *self {
             MyErr::Diesel(ref err) => err.cause(),
-            MyErr::UpdateError(_) | MyErr::TooManySortColumns(_) => None
+            MyErr::Invalid(_) | MyErr::TooManySortColumns(_) => None
         }
     }
 }
@@ -35,8 +39,17 @@ impl Display for MyErr {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match *self {
             MyErr::Diesel(ref err) => err.fmt(f),
-            MyErr::UpdateError(ref err) => err.fmt(f),
+            MyErr::Invalid(ref err) => err.fmt(f),
             MyErr::TooManySortColumns(ref err) => err.fmt(f)
         }
     }
 }
+
+impl JsonApiErrorDetails for MyErr {
+    fn error_objects(&self, status: u16) -> Vec<JsonApiError> {
+        match *self {
+            MyErr::Invalid(ref err) => err.error_objects(status),
+            _ => vec![JsonApiError::from_error(self, status)]
+        }
+    }
+}
@@ -18,9 +18,11 @@ infer_schema!("dotenv:POSTGRES_URL");
 #[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi, Queryable,
          Insertable, AsChangeset)]
 #[table_name = "todos"]
+#[jsonapi(status = "Status")]
 #[changeset_options(treat_none_as_null = "true")]
 pub struct Todo {
     id: Uuid,
+    #[jsonapi(validate(length(min = 1, max = 200)))]
     title: String,
     body: Option<String>,
     published: bool
@@ -43,11 +45,11 @@ impl JsonGet for Todo {
         id: Self::JsonApiIdType,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Option<JsonApiData<Self>>, (Self::Error, Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Status)> {
         table
             .find(id)
             .first::<Todo>(ctx.conn())
-            .map(|r| r.into_json(params))
+            .map(|r| r.into_json(params).into())
             .optional()
             .map_err(|e| (MyErr::Diesel(e), Status::InternalServerError))
     }
@@ -56,15 +58,23 @@ impl JsonGet for Todo {
 impl JsonIndex for Todo {
     type Error = MyErr;
     type Context = DB;
+    const DEFAULT_PAGE_SIZE: Option<u64> = Some(20);
+    const MAX_PAGE_SIZE: Option<u64> = Some(100);
 
-    /// Gets all records from the database
+    /// Gets a page of records from the database
     fn find_all(
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Vec<JsonApiData<Self>>, (Self::Error, Status)> {
+    ) -> Result<JsonApiPage<Self>, (Self::Error, Status)> {
 
         let mut query = table.into_boxed();
 
+        if let Some(page) = params.page.as_ref() {
+            if let (Some(offset), Some(size)) = (page.offset(), page.size()) {
+                query = query.offset(offset as i64).limit(size as i64);
+            }
+        }
+
         {
             use self::todo::sort::*;
             use self::todos as column;
@@ -126,7 +136,7 @@ impl JsonIndex for Todo {
 
         query
             .load::<Todo>(ctx.conn())
-            .map(|r| r.into_json(params))
+            .map(|r| JsonApiPage::new(r.into_json(params)))
             .map_err(|e| (MyErr::Diesel(e), Status::InternalServerError))
     }
 }
@@ -143,19 +153,19 @@ impl JsonPatch for Todo {
         json: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Status)> {
+    ) -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Status)> {
         let record = table
             .find(&id)
             .first(ctx.conn())
             .map_err(|e| (MyErr::Diesel(e), Status::InternalServerError))?;
         let patch = (record, json)
             .try_into()
-            .map_err(|e| (MyErr::UpdateError(e), Status::ImATeapot))?;
+            .map_err(|e| (MyErr::Invalid(e), Status::ImATeapot))?;
         diesel::update(table.find(&id))
             .set(&patch)
             .execute(ctx.conn())
             .map_err(|e| (MyErr::Diesel(e), Status::InternalServerError))?;
-        Ok(patch.into_json(params))
+        Ok(patch.into_json(params).into())
     }
 }
 
@@ -184,15 +194,15 @@ impl JsonPost for Todo {
         record: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Status)> {
         let todo: Todo = record
             .try_into()
-            .map_err(|e| (MyErr::UpdateError(e), Status::ImATeapot))?;
+            .map_err(|e| (MyErr::Invalid(e), Status::ImATeapot))?;
         let result: NewTodo = todo.into();
         diesel::insert(&result)
             .into(table)
             .get_result::<Todo>(ctx.conn())
-            .map(|r| r.into_json(params))
+            .map(|r| Some(r.into_json(params).into()))
             .map_err(|e| (MyErr::Diesel(e), Status::InternalServerError))
     }
 }
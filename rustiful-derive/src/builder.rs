@@ -4,14 +4,34 @@ use quote::Tokens;
 use syn::Ident;
 use util;
 use util::JsonApiField;
+use util::JsonApiRelationship;
 extern crate inflector;
 
 use self::inflector::Inflector;
 
 pub fn expand_json_api_builders(
     name: &Ident,
-    &(ref id, ref fields): &(JsonApiField, Vec<JsonApiField>)
+    &(ref id, ref attrs, ref relationships): &(
+        JsonApiField,
+        Vec<JsonApiField>,
+        Vec<JsonApiRelationship>
+    )
 ) -> Tokens {
+    // Relationships are plain fields on the model, so they need to be settable on the builder
+    // as well. A missing relationship is reported with the name of the relationship rather than
+    // with the name of the field.
+    let fields: Vec<_> = attrs
+        .iter()
+        .map(|f| {
+            let name = &f.name;
+            (&f.ident, &f.field, quote!(MissingAttribute(#name.to_string())))
+        })
+        .chain(relationships.iter().map(|r| {
+            let name = &r.json_name;
+            (&r.ident, &r.field, quote!(MissingRelationship(#name.to_string())))
+        }))
+        .collect();
+
     let json_api_id_ty = &id.field.ty;
     let json_api_id_ident = &id.ident;
     let lower_case_name = Ident::new(name.to_string().to_snake_case());
@@ -24,16 +44,14 @@ pub fn expand_json_api_builders(
     let mut jsonapi_builder_methods: Vec<_> = Vec::with_capacity(fields.len());
     let mut jsonapi_builder_setter: Vec<_> = Vec::with_capacity(fields.len());
 
-    for field in fields {
-        let ty = &field.field.ty;
-        let ident = &field.ident;
-        let ident_string = &ident.to_string();
+    for (ident, field, missing) in fields {
+        let ty = &field.ty;
 
         jsonapi_attrs.push(generate_option_field(ident, ty, true));
         jsonapi_builder_attrs.push(generate_option_field(ident, ty, false));
 
         jsonapi_builder_fields.push(quote! {
-            #ident: self.#ident.ok_or(format!("#{} must be initialized", #ident_string))?
+            #ident: self.#ident.ok_or(self::_rustiful::ConversionError::#missing)?
         });
         jsonapi_setter_fields.push(quote! { new.#ident = Some(model.#ident); });
         jsonapi_builder_methods.push(quote! {
@@ -62,6 +80,9 @@ pub fn expand_json_api_builders(
 
             extern crate rustiful as _rustiful;
 
+            // The types of the fields may be declared (or imported) in the parent module.
+            #[allow(unused_imports)]
+            use super::*;
             use super::#name;
             use self::_rustiful::ToBuilder;
             use self::_rustiful::JsonApiBuilder;
@@ -84,7 +105,7 @@ pub fn expand_json_api_builders(
                     new
                 }
 
-                fn build(self) -> Result<#name, String> {
+                fn build(self) -> Result<#name, self::_rustiful::ConversionError> {
                     Ok(#name {
                         #jsonapi_builder_id,
                         #(#jsonapi_builder_fields),*
@@ -7,10 +7,17 @@ use quote::Tokens;
 use syn::Ty;
 use util;
 use util::JsonApiField;
+use util::JsonApiRelationship;
+use util::RelationshipKind;
+use util::Validation;
 
 pub fn expand_json_api_models(
     name: &syn::Ident,
-    &(ref id, ref fields): &(JsonApiField, Vec<JsonApiField>)
+    &(ref id, ref fields, ref relationships): &(
+        JsonApiField,
+        Vec<JsonApiField>,
+        Vec<JsonApiRelationship>
+    )
 ) -> Tokens {
     let json_api_id_ty = &id.field.ty;
     let json_api_id_ident = &id.ident;
@@ -25,12 +32,19 @@ pub fn expand_json_api_models(
     let mut filtered_option_cases: Vec<_> = Vec::with_capacity(fields.len());
     let mut attr_constructor_args: Vec<_> = Vec::with_capacity(fields.len());
     let mut jsonapi_builder_setter: Vec<_> = Vec::with_capacity(fields.len());
+    let mut validations: Vec<_> = Vec::with_capacity(fields.len());
 
     for field in fields {
         let ty = &field.field.ty;
         let ident = &field.ident;
 
-        jsonapi_attrs.push(generate_option_field(ident, ty, true));
+        let option_field = generate_option_field(ident, ty, true);
+        if field.ident == field.name.as_str() {
+            jsonapi_attrs.push(option_field);
+        } else {
+            let name = &field.name;
+            jsonapi_attrs.push(quote!(#[serde(rename = #name)] #option_field));
+        }
 
         filtered_option_vars.push(quote!(let mut #ident = Some(model.#ident);));
 
@@ -46,8 +60,138 @@ pub fn expand_json_api_models(
         });
 
         attr_constructor_args.push(quote! { #ident: Option<#ty> });
+
+        if !field.validations.is_empty() {
+            validations.push(generate_validations(field));
+        }
+    }
+
+    let mut relationship_names: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_attrs: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_vars: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_fields: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_setters: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_filter_cases: Vec<_> = Vec::with_capacity(relationships.len());
+
+    for relationship in relationships {
+        let ident = &relationship.ident;
+        let name = &relationship.name;
+        let name_string = &relationship.json_name;
+        let id_ty = &relationship.id_ty;
+        let resource = &relationship.resource;
+        let type_name = quote!(<super::#resource as JsonApiResource>::RESOURCE_NAME);
+
+        relationship_names.push(quote!(#name_string));
+
+        let invalid = quote! {
+            |message| self::_rustiful::ConversionError::InvalidRelationship(
+                #name_string.to_string(),
+                message
+            )
+        };
+
+        let (relationship_ty, linkage, setter) = match relationship.kind {
+            RelationshipKind::ToOne => (
+                quote!(self::_rustiful::ToOneRelationship),
+                quote! {
+                    self::_rustiful::ToOneRelationship::new(Some(
+                        self::_rustiful::JsonApiResourceIdentifier::new(
+                            #type_name,
+                            model.#ident.to_string()
+                        )
+                    ))
+                },
+                quote! {
+                    match relationship.parse_id::<#id_ty>(#type_name).map_err(#invalid)? {
+                        Some(id) => builder.#ident(id),
+                        None => {
+                            return Err(self::_rustiful::ConversionError::InvalidRelationship(
+                                #name_string.to_string(),
+                                format!("Relationship {} cannot be null", #name_string)
+                            ));
+                        }
+                    };
+                }
+            ),
+            RelationshipKind::NullableToOne => (
+                quote!(self::_rustiful::ToOneRelationship),
+                quote! {
+                    self::_rustiful::ToOneRelationship::new(model.#ident.as_ref().map(|id| {
+                        self::_rustiful::JsonApiResourceIdentifier::new(#type_name, id.to_string())
+                    }))
+                },
+                quote! {
+                    builder.#ident(relationship.parse_id::<#id_ty>(#type_name).map_err(#invalid)?);
+                }
+            ),
+            RelationshipKind::ToMany => (
+                quote!(self::_rustiful::ToManyRelationship),
+                quote! {
+                    self::_rustiful::ToManyRelationship::new(model.#ident.iter().map(|id| {
+                        self::_rustiful::JsonApiResourceIdentifier::new(#type_name, id.to_string())
+                    }).collect())
+                },
+                quote! {
+                    builder.#ident(relationship.parse_ids::<#id_ty>(#type_name).map_err(#invalid)?);
+                }
+            )
+        };
+
+        relationship_attrs.push(quote! {
+            #[serde(default, skip_serializing_if = "Option::is_none", rename = #name_string)]
+            pub #name: Option<#relationship_ty>
+        });
+
+        relationship_vars.push(quote!(let mut #name = Some(#linkage);));
+        relationship_fields.push(quote!(#name: #name));
+
+        relationship_setters.push(quote! {
+            if let Some(relationship) = relationships.#name {
+                #setter
+            }
+        });
+
+        relationship_filter_cases.push(quote! {
+            &super::#lower_case_name::field::#name => #name = None
+        });
+
+        // Relationships are part of the `field` enum, but not of the attributes.
+        filtered_option_cases.push(quote! {
+            &super::#lower_case_name::field::#name => {}
+        });
     }
 
+    // Likewise, attributes are ignored when filtering relationships.
+    for field in fields {
+        let ident = &field.ident;
+        relationship_filter_cases.push(quote! {
+            &super::#lower_case_name::field::#ident => {}
+        });
+    }
+
+    let relationship_setter = if relationship_setters.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            if let Some(relationships) = updated_attrs.relationships {
+                #(#relationship_setters)*
+            }
+        }
+    };
+
+    let validation = if validations.is_empty() {
+        quote!()
+    } else {
+        quote! {
+            let mut errors = Vec::new();
+            #(#validations)*
+
+            if !errors.is_empty() {
+                return Err(self::_rustiful::ConversionError::Validation(errors));
+            }
+        }
+    };
+
     let mod_name = Ident::new(format!("__json_{}", lower_case_name_as_str));
 
     let uuid = util::get_uuid_tokens();
@@ -58,6 +202,9 @@ pub fn expand_json_api_models(
 
             extern crate rustiful as _rustiful;
 
+            // The types of the fields may be declared (or imported) in the parent module.
+            #[allow(unused_imports)]
+            use super::*;
             use super::#name;
             use std::str::FromStr;
             use self::_rustiful::ToJson;
@@ -68,11 +215,16 @@ pub fn expand_json_api_models(
             use self::_rustiful::JsonApiBuilder;
             use self::_rustiful::JsonApiResource;
 
-            #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+            #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
             pub struct JsonApiAttributes {
                 #(#jsonapi_attrs),*
             }
 
+            #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+            pub struct JsonApiRelationships {
+                #(#relationship_attrs),*
+            }
+
             impl JsonApiAttributes {
                 pub fn new(#(#attr_constructor_args),*) -> JsonApiAttributes {
                     JsonApiAttributes {
@@ -82,13 +234,16 @@ pub fn expand_json_api_models(
             }
 
             impl TryFrom<JsonApiData<#name>> for #name {
-                type Error = String;
+                type Error = self::_rustiful::ConversionError;
 
                 fn try_from(json: JsonApiData<#name>) -> Result<Self, Self::Error> {
                     let id = json.id.clone().map(|id| {
                         match #json_api_id_ty::from_str(&id) {
                             Ok(result) => Ok(result),
-                            Err(e) => return Err(format!("Failed to parse id value {}: {}", &id, e))
+                            Err(e) => {
+                                let message = format!("Failed to parse id value {}: {}", &id, e);
+                                Err(self::_rustiful::ConversionError::InvalidId(message))
+                            }
                         }
                     });
 
@@ -106,24 +261,29 @@ pub fn expand_json_api_models(
             }
 
             impl TryFrom<(#name, JsonApiData<#name>)> for #name {
-                type Error = String;
+                type Error = self::_rustiful::ConversionError;
 
                 fn try_from((model, updated_attrs): (#name, JsonApiData<#name>))
                 -> Result<Self, Self::Error> {
                     let mut builder = <#name as ToBuilder>::Builder::new(model);
                     #(#jsonapi_builder_setter)*
-                    builder.build()
+                    #relationship_setter
+                    let model = builder.build()?;
+                    #validation
+                    Ok(model)
                 }
             }
 
             impl ToJson for #name {
                 type Attrs = JsonApiAttributes;
+                type Relationships = JsonApiRelationships;
 
                 fn id(&self) -> String {
                     self.#json_api_id_ident.to_string()
                 }
 
                 const TYPE_NAME: &'static str = <#name as JsonApiResource>::RESOURCE_NAME;
+                const RELATIONSHIP_NAMES: &'static [&'static str] = &[#(#relationship_names),*];
             }
 
             /// Converts a `(T, T::Params)` to a `JsonApiAttributes`.
@@ -152,10 +312,110 @@ pub fn expand_json_api_models(
                     JsonApiAttributes::new(#(#filtered_option_fields),*)
                 }
             }
+
+            /// Converts a `(&T, T::Params)` to a `JsonApiRelationships`, with resource linkage for
+            /// each field that has the `JsonApiRelationship` attribute. Relationships are filtered
+            /// by `params.fieldset.fields` in the same way as the attributes.
+            impl <'a, 'b> From<(&'b #name, &'a <#name as JsonApiResource>::Params)>
+                for JsonApiRelationships {
+                #[allow(unused_variables, unused_mut)]
+                fn from((model, params): (&'b #name, &'a <#name as JsonApiResource>::Params))
+                    -> Self {
+                    #(#relationship_vars)*
+
+                    let fields = &params.fieldset.fields;
+                    if !fields.is_empty() {
+                        for field in super::#lower_case_name::field::iter() {
+                            if !fields.contains(field) {
+                                match field {
+                                    #(#relationship_filter_cases),*
+                                }
+                            }
+                        }
+                    }
+
+                    JsonApiRelationships {
+                        #(#relationship_fields),*
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Generates the validations of `field`, which add a `ValidationError` to `errors` for each failed
+/// validation. The validations of an `Option` field only run when it's `Some`.
+fn generate_validations(field: &JsonApiField) -> Tokens {
+    let ident = &field.ident;
+    let field_name = &field.name;
+
+    let checks: Vec<_> = field
+        .validations
+        .iter()
+        .map(|validation| {
+            let check = match *validation {
+                Validation::Length(ref min, ref max) => {
+                    let (min, max) = (option_tokens(min), option_tokens(max));
+                    quote!(self::_rustiful::validate::length(#field_name, value, #min, #max))
+                }
+                Validation::Range(ref min, ref max) => {
+                    let (min, max) = (option_tokens(min), option_tokens(max));
+                    quote!(self::_rustiful::validate::range(#field_name, value, #min, #max))
+                }
+                Validation::Regex(ref pattern) => {
+                    // The pattern is compiled once, the first time the field is validated.
+                    quote!({
+                        static PATTERN: self::_rustiful::validate::Pattern =
+                            self::_rustiful::validate::Pattern::new(#pattern);
+                        self::_rustiful::validate::regex(#field_name, value, &PATTERN)
+                    })
+                }
+                Validation::Email => quote!(self::_rustiful::validate::email(#field_name, value)),
+                Validation::Custom(ref path) => {
+                    let path = if path.starts_with("::") {
+                        syn::parse_path(path)
+                    } else {
+                        syn::parse_path(&format!("super::{}", path))
+                    }.unwrap_or_else(|e| panic!("Invalid custom validator {}: {}", path, e));
+
+                    // A closure lets the argument be deref coerced, e.g from `&String` to `&str`.
+                    quote! {
+                        self::_rustiful::validate::custom(#field_name, value, |v| #path(v))
+                    }
+                }
+            };
+
+            quote! {
+                if let Err(e) = #check {
+                    errors.push(e);
+                }
+            }
+        })
+        .collect();
+
+    if util::is_option_ty(&field.field.ty) {
+        quote! {
+            if let Some(ref value) = model.#ident {
+                #(#checks)*
+            }
+        }
+    } else {
+        quote! {
+            {
+                let value = &model.#ident;
+                #(#checks)*
+            }
         }
     }
 }
 
+fn option_tokens(lit: &Option<syn::Lit>) -> Tokens {
+    match *lit {
+        Some(ref lit) => quote!(Some(#lit)),
+        None => quote!(None)
+    }
+}
+
 pub fn generate_option_field(
     ident: &syn::Ident,
     ty: &Ty,
@@ -20,48 +20,48 @@ mod builder;
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
-#[proc_macro_derive(JsonApi, attributes(JsonApiId))]
+#[proc_macro_derive(JsonApi, attributes(JsonApiId, JsonApiRelationship, jsonapi))]
 pub fn generate_json_api(input: TokenStream) -> TokenStream {
 
     let source = parse_derive_input(&input);
     let name = &source.ident;
-    let pair = util::get_attrs_and_id(source.body);
+    let fields = util::get_attrs_and_id(source.body, &source.attrs);
 
     // Build the output
-    let mut expanded = builder::expand_json_api_builders(name, &pair);
+    let mut expanded = builder::expand_json_api_builders(name, &fields);
     expanded.append(
-        params::expand_json_api_fields(name, &source.attrs, &pair).as_str()
+        params::expand_json_api_fields(name, &source.attrs, &fields).as_str()
     );
-    expanded.append(json::expand_json_api_models(name, &pair).as_str());
+    expanded.append(json::expand_json_api_models(name, &fields).as_str());
 
     // Return the generated impl as a TokenStream
     expanded.parse().unwrap()
 }
 
-#[proc_macro_derive(JsonApiResource, attributes(JsonApiId))]
+#[proc_macro_derive(JsonApiResource, attributes(JsonApiId, JsonApiRelationship, jsonapi))]
 pub fn generate_json_api_models(input: TokenStream) -> TokenStream {
     let source = parse_derive_input(&input);
     let name = &source.ident;
-    let pair = util::get_attrs_and_id(source.body);
-    json::expand_json_api_models(name, &pair).parse().unwrap()
+    let fields = util::get_attrs_and_id(source.body, &source.attrs);
+    json::expand_json_api_models(name, &fields).parse().unwrap()
 }
 
-#[proc_macro_derive(JsonApiBuilder, attributes(JsonApiId))]
+#[proc_macro_derive(JsonApiBuilder, attributes(JsonApiId, JsonApiRelationship, jsonapi))]
 pub fn generate_json_api_builders(input: TokenStream) -> TokenStream {
     let source = parse_derive_input(&input);
     let name = &source.ident;
-    let pair = util::get_attrs_and_id(source.body);
-    builder::expand_json_api_builders(name, &pair)
+    let fields = util::get_attrs_and_id(source.body, &source.attrs);
+    builder::expand_json_api_builders(name, &fields)
         .parse()
         .unwrap()
 }
 
-#[proc_macro_derive(JsonApiParams)]
+#[proc_macro_derive(JsonApiParams, attributes(JsonApiRelationship, jsonapi))]
 pub fn generate_json_api_request_parameters(input: TokenStream) -> TokenStream {
     let source = parse_derive_input(&input);
     let name = &source.ident;
-    let pair = util::get_attrs_and_id(source.body);
-    params::expand_json_api_fields(name, &source.attrs, &pair)
+    let fields = util::get_attrs_and_id(source.body, &source.attrs);
+    params::expand_json_api_fields(name, &source.attrs, &fields)
         .parse()
         .unwrap()
 }
@@ -4,16 +4,19 @@ extern crate syn;
 use self::inflector::Inflector;
 use super::quote::*;
 use syn::Attribute;
-use syn::Lit::*;
-use syn::MetaItem::*;
-use syn::NestedMetaItem::*;
 use util;
 use util::JsonApiField;
+use util::JsonApiRelationship;
+use util::RelationshipKind;
 
 pub fn expand_json_api_fields(
     name: &syn::Ident,
     attrs: &[Attribute],
-    &(ref id, ref fields): &(JsonApiField, Vec<JsonApiField>)
+    &(ref id, ref fields, ref relationships): &(
+        JsonApiField,
+        Vec<JsonApiField>,
+        Vec<JsonApiRelationship>
+    )
 ) -> Tokens {
     let json_api_id_ty = &id.field.ty;
 
@@ -22,13 +25,18 @@ pub fn expand_json_api_fields(
     let lower_cased_ident = Ident::new(lower_case_name);
     let json_name = get_json_name(&pluralized_name, attrs);
 
-    let mut option_fields: Vec<_> = Vec::with_capacity(fields.len());
-    let option_fields_len = fields.len();
+    let mut option_fields: Vec<_> = Vec::with_capacity(fields.len() + relationships.len());
+    let option_fields_len = fields.len() + relationships.len();
 
-    let mut filter_fields: Vec<_> = Vec::with_capacity(fields.len());
-    let mut filter_cases: Vec<_> = Vec::with_capacity(fields.len());
+    let mut fieldset_fields: Vec<_> = Vec::with_capacity(fields.len());
+    let mut fieldset_cases: Vec<_> = Vec::with_capacity(fields.len());
     let mut sort_fields: Vec<_> = Vec::with_capacity(fields.len());
     let mut sort_cases: Vec<_> = Vec::with_capacity(fields.len());
+    let mut filter_fields: Vec<_> = Vec::with_capacity(fields.len());
+    let mut filter_cases: Vec<_> = Vec::with_capacity(fields.len());
+    let mut cursor_value_cases: Vec<_> = Vec::with_capacity(fields.len());
+    let mut cursor_order_cases: Vec<_> = Vec::with_capacity(fields.len());
+    let mut cursor_type_cases: Vec<_> = Vec::with_capacity(fields.len());
 
     for field in fields {
         let f = &field.ident;
@@ -36,27 +44,145 @@ pub fn expand_json_api_fields(
         option_fields.push(quote!(#f));
 
         sort_fields.push(quote!(#f(SortOrder)));
-        sort_cases.push(to_match_arm(&f, &quote!(self::sort::#f(order))));
+        sort_cases.push(to_match_arm(&field.name, &quote!(self::sort::#f(order))));
+
+        fieldset_fields.push(quote!(self::field::#f));
+        let name = &field.name;
+        fieldset_cases.push(quote!(#name => Some(self::field::#f)));
+
+        let field_ty = &field.field.ty;
+        cursor_value_cases.push(quote!(self::sort::#f(_) => Cursor::to_value(&resource.#f)));
+        cursor_order_cases.push(quote!(self::sort::#f(order) => order));
+        cursor_type_cases.push(quote! {
+            self::sort::#f(_) => Cursor::from_value::<#field_ty>(value).is_some()
+        });
+
+        // Only fields of a `Filterable` type can be filtered on, and optional fields are filtered
+        // on their inner value.
+        if !field.filterable {
+            continue;
+        }
+
+        let ty = util::get_inner_ty(&field.field.ty, "Option")
+            .unwrap_or_else(|| field.field.ty.clone());
+        let f_string = &field.name;
+
+        filter_fields.push(quote!(#f(FilterValue<#ty>)));
+        filter_cases.push(quote! {
+            #f_string => FilterValue::parse(field, operator, value).map(self::filter::#f)
+        });
+    }
+
+    let mut include_fields: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut include_cases: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_fields: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_values: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_cases: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_name_cases: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_type_cases: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut relationship_to_many_cases: Vec<_> = Vec::with_capacity(relationships.len());
+    let mut included_types: Vec<_> = Vec::with_capacity(relationships.len());
+
+    // Relationships can't be sorted on, but they are part of the sparse fieldset.
+    for relationship in relationships {
+        let f = &relationship.name;
+        let f_string = &relationship.json_name;
+        let resource = &relationship.resource;
+        let include_ty = quote!(<super::#resource as JsonApiResource>::IncludeField);
+        let type_name = quote!(<super::#resource as JsonApiResource>::RESOURCE_NAME);
+        let is_to_many = if relationship.kind == RelationshipKind::ToMany {
+            quote!(true)
+        } else {
+            quote!(false)
+        };
+
+        relationship_fields.push(quote!(#f));
+        relationship_values.push(quote!(self::relationship::#f));
+        relationship_cases.push(to_match_arm(f_string, &quote!(self::relationship::#f)));
+        relationship_name_cases.push(quote!(self::relationship::#f => #f_string));
+        relationship_type_cases.push(quote!(self::relationship::#f => #type_name));
+        relationship_to_many_cases.push(quote!(self::relationship::#f => #is_to_many));
+        let field_ty = quote!(<super::#resource as JsonApiResource>::FieldSetField);
+        included_types.push(quote! {
+            let type_name = <#field_ty as JsonApiFieldSetField>::type_name();
+            if !types.iter().any(|&(t, _)| t == type_name) {
+                types.push((type_name, |name| {
+                    <#field_ty as JsonApiFieldSetField>::from_name(name).is_some()
+                }));
+                <#field_ty as JsonApiFieldSetField>::included_types(types);
+            }
+        });
 
-        filter_fields.push(quote!(self::field::#f));
-        filter_cases.push(to_match_arm(&f, &quote!(self::field::#f)));
+        option_fields.push(quote!(#f));
+
+        fieldset_fields.push(quote!(self::field::#f));
+        fieldset_cases.push(quote!(#f_string => Some(self::field::#f)));
+
+        include_fields.push(quote!(#f(Option<Box<#include_ty>>)));
+        include_cases.push(quote! {
+            #f_string => {
+                let nested = match rest {
+                    Some(rest) => match #include_ty::try_from(rest) {
+                        Ok(nested) => Some(Box::new(nested)),
+                        Err(_) => {
+                            let e = QueryStringParseError::InvalidIncludeValue(path.to_string());
+                            return Err(e)
+                        }
+                    },
+                    None => None
+                };
+
+                Ok(self::include::#f(nested))
+            }
+        });
     }
 
     let uuid = util::get_uuid_tokens();
 
+    // The status type is resolved in the module of the resource, so that it can be imported there.
+    let status = match util::get_attribute_value(attrs, "jsonapi", "status") {
+        Some(status) => {
+            let ty = syn::parse_type(&status)
+                .unwrap_or_else(|e| panic!("Invalid jsonapi status {} on {}: {}", status, name, e));
+            quote!(#ty)
+        }
+        None => quote!(_rustiful::DefaultStatus)
+    };
+    let dummy_const = Ident::new(format!("_IMPL_JSONAPI_HANDLER_FOR_{}", name));
+
     quote! {
+        #[allow(non_upper_case_globals)]
+        const #dummy_const: () = {
+            extern crate rustiful as _rustiful;
+
+            impl _rustiful::Handler for #name {
+                type Status = #status;
+            }
+        };
+
         pub mod #lower_cased_ident {
             #uuid
 
             extern crate rustiful as _rustiful;
 
+            // The types of the fields may be declared (or imported) in the parent module.
+            #[allow(unused_imports)]
+            use super::*;
             use super::#name;
             use std::slice::Iter;
             use std::convert::TryFrom;
             use self::_rustiful::SortOrder;
             use self::_rustiful::JsonApiParams;
             use self::_rustiful::JsonApiResource;
+            use self::_rustiful::FilterValue;
+            use self::_rustiful::Cursor;
+            use self::_rustiful::CursorField;
+            use self::_rustiful::CursorValue;
             use self::_rustiful::QueryStringParseError;
+            use self::_rustiful::JsonApiRelationshipField;
+            use self::_rustiful::JsonApiFieldSetField;
+            use self::_rustiful::IncludedType;
+            use std::str::FromStr;
 
             #[derive(Debug, PartialEq, Eq, Clone)]
             #[allow(non_camel_case_types)]
@@ -71,9 +197,34 @@ pub fn expand_json_api_fields(
                 #(#option_fields),*
             }
 
+            /// A relationship path in the `include` query parameter. A dotted path such as
+            /// `author.company` is represented by the relationship (`author`) along with the
+            /// remaining path of the related resource (`company`).
+            #[derive(Debug, PartialEq, Eq, Clone)]
+            #[allow(non_camel_case_types)]
+            pub enum include {
+                #(#include_fields),*
+            }
+
+            /// A `filter[*]` query parameter, holding the operator and the value parsed to the type
+            /// of the attribute. This isn't `Eq`, since attributes may be floats.
+            #[derive(Debug, PartialEq, Clone)]
+            #[allow(non_camel_case_types)]
+            pub enum filter {
+                #(#filter_fields),*
+            }
+
+            /// A relationship of the resource, used to route requests to its relationship
+            /// endpoints, e.g `/articles/1/relationships/author`.
+            #[derive(Debug, PartialEq, Eq, Copy, Clone)]
+            #[allow(non_camel_case_types)]
+            pub enum relationship {
+                #(#relationship_fields),*
+            }
+
             impl field {
                 pub fn iter() -> Iter<'static, field> {
-                    static FIELDS: [field;  #option_fields_len] = [#(#filter_fields),*];
+                    static FIELDS: [field;  #option_fields_len] = [#(#fieldset_fields),*];
                     FIELDS.into_iter()
                 }
             }
@@ -89,64 +240,128 @@ pub fn expand_json_api_fields(
                 }
             }
 
-            impl<'a> TryFrom<(&'a str, Vec<&'a str>)> for field {
+            impl JsonApiFieldSetField for field {
+                fn type_name() -> &'static str {
+                    #json_name
+                }
+
+                fn from_name(name: &str) -> Option<Self> {
+                    match name {
+                        #(#fieldset_cases,)*
+                        _ => None
+                    }
+                }
+
+                #[allow(unused_variables)]
+                fn included_types(types: &mut Vec<IncludedType>) {
+                    #(#included_types)*
+                }
+            }
+
+            impl<'a> TryFrom<&'a str> for include {
+                type Error = QueryStringParseError;
+
+                #[allow(unused_variables)]
+                fn try_from(path: &'a str) -> Result<Self, Self::Error> {
+                    let mut parts = path.splitn(2, '.');
+                    let relationship = parts.next().unwrap_or("");
+                    let rest = parts.next();
+
+                    match relationship {
+                        #(#include_cases),*
+                        _ => Err(QueryStringParseError::InvalidIncludeValue(path.to_string()))
+                    }
+                }
+            }
+
+            impl<'a> TryFrom<(&'a str, &'a str, &'a str)> for filter {
                 type Error = QueryStringParseError;
 
-                fn try_from((model, fields): (&'a str, Vec<&'a str>)) -> Result<Self, Self::Error> {
-                    match model {
-                        #json_name => {
-                            for field in fields {
-                                match field {
-                                    #(#filter_cases),*
-                                    _ => {
-                                        let field_val = field.to_string();
-                                        let e = QueryStringParseError::InvalidFieldValue(field_val);
-                                        return Err(e)
-                                    }
-                                }
-                            }
-                        },
-                        // TODO: Implement parsing of relationships
-                        _ => return Err(QueryStringParseError::UnImplementedError)
+                #[allow(unused_variables)]
+                fn try_from(
+                    (field, operator, value): (&'a str, &'a str, &'a str)
+                ) -> Result<Self, Self::Error> {
+                    match field {
+                        #(#filter_cases,)*
+                        _ => Err(QueryStringParseError::InvalidFilterKey(field.to_string()))
                     }
+                }
+            }
+
+            impl FromStr for relationship {
+                type Err = ();
 
-                    // TODO: Implement parsing of relationships
-                    return Err(QueryStringParseError::UnImplementedError)
+                fn from_str(name: &str) -> Result<Self, Self::Err> {
+                    match name {
+                        #(#relationship_cases),*
+                        _ => Err(())
+                    }
+                }
+            }
+
+            impl JsonApiRelationshipField for relationship {
+                fn name(&self) -> &'static str {
+                    match *self {
+                        #(#relationship_name_cases),*
+                    }
+                }
+
+                fn type_name(&self) -> &'static str {
+                    match *self {
+                        #(#relationship_type_cases),*
+                    }
+                }
+
+                fn is_to_many(&self) -> bool {
+                    match *self {
+                        #(#relationship_to_many_cases),*
+                    }
+                }
+
+                fn all() -> Vec<Self> {
+                    vec![#(#relationship_values),*]
+                }
+            }
+
+            impl CursorField<#name> for sort {
+                fn cursor_value(&self, resource: &#name) -> CursorValue {
+                    match *self {
+                        #(#cursor_value_cases),*
+                    }
+                }
+
+                fn cursor_order(&self) -> SortOrder {
+                    match *self {
+                        #(#cursor_order_cases),*
+                    }
+                }
+
+                #[allow(unused_variables)]
+                fn is_cursor_value(&self, value: &CursorValue) -> bool {
+                    match *self {
+                        #(#cursor_type_cases),*
+                    }
                 }
             }
 
             impl JsonApiResource for #name {
                 type JsonApiIdType = #json_api_id_ty;
-                type Params = JsonApiParams<field, sort>;
+                type Params = JsonApiParams<field, sort, include, filter>;
                 type SortField = sort;
-                type FilterField = field;
+                type FieldSetField = field;
+                type IncludeField = include;
+                type FilterField = filter;
+                type RelationshipField = relationship;
                 const RESOURCE_NAME: &'static str = #pluralized_name;
             }
         }
     }
 }
 
-fn to_match_arm(ident: &syn::Ident, enum_value: &Tokens) -> Tokens {
-    let ident_string = ident.to_string();
-    quote!(#ident_string => { return Ok(#enum_value) })
+fn to_match_arm(name: &str, enum_value: &Tokens) -> Tokens {
+    quote!(#name => { return Ok(#enum_value) })
 }
 
 fn get_json_name(name: &str, attrs: &[Attribute]) -> String {
-    let serde_struct_rename_attr: Vec<_> = attrs
-        .into_iter()
-        .filter_map(|a| match a.value {
-            List(ref ident, ref values) if ident == "serde" => match values.first() {
-                Some(&MetaItem(NameValue(ref i, Str(ref value, _)))) if i == "rename" => {
-                    Some(value.to_string())
-                }
-                _ => None
-            },
-            _ => None
-        })
-        .collect();
-
-    serde_struct_rename_attr
-        .first()
-        .map(|s| s.to_string())
-        .unwrap_or_else(|| name.to_string())
+    util::get_attribute_value(attrs, "serde", "rename").unwrap_or_else(|| name.to_string())
 }
@@ -1,7 +1,17 @@
+extern crate inflector;
+extern crate regex;
+
+use self::inflector::Inflector;
+use self::regex::Regex;
 use quote::Tokens;
+use syn::Attribute;
 use syn::Body;
 use syn::Field;
 use syn::Ident;
+use syn::Lit;
+use syn::MetaItem;
+use syn::NestedMetaItem;
+use syn::PathParameters;
 use syn::Ty;
 use syn::VariantData;
 
@@ -13,21 +23,96 @@ use syn::VariantData;
 /// dances when we want to do something with the ident.
 pub struct JsonApiField {
     pub field: Field,
-    pub ident: Ident
+    pub ident: Ident,
+    /// The name of the field in the JSON document, which is also used in the `sort`, `fields` and
+    /// `filter` query parameters. See `get_field_name`.
+    pub name: String,
+    /// The validations in the `#[jsonapi(validate(...))]` attributes of the field.
+    pub validations: Vec<Validation>,
+    /// Whether the field can be used in the `filter` query parameter. See `is_filterable`.
+    pub filterable: bool
+}
+
+/// A validation of an attribute, which is run when a resource is converted from a `JsonApiData`.
+///
+/// Validations are declared with e.g `#[jsonapi(validate(length(min = 1), email))]`. See the
+/// `validate` module in `rustiful` for the validators that are called for each of these.
+pub enum Validation {
+    /// `length(min = 1, max = 200)`, where both `min` and `max` are optional.
+    Length(Option<Lit>, Option<Lit>),
+    /// `range(min = 0, max = 5)`, where both `min` and `max` are optional.
+    Range(Option<Lit>, Option<Lit>),
+    /// `regex = "^[a-z]+$"`.
+    Regex(String),
+    /// `email`.
+    Email,
+    /// `custom = "path::to::function"`.
+    Custom(String)
+}
+
+/// The cardinality of a field with the `JsonApiRelationship` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RelationshipKind {
+    /// A field of any type that isn't an `Option` or a `Vec`.
+    ToOne,
+    /// A field of type `Option<T>`, which is serialized as `{"data": null}` when it's `None`.
+    NullableToOne,
+    /// A field of type `Vec<T>`.
+    ToMany
+}
+
+/// This is a wrapper for a field with the `JsonApiRelationship` attribute.
+///
+/// A relationship is declared with `#[JsonApiRelationship]`, optionally followed by the type of
+/// the related resource and/or the name of the relationship, e.g
+/// `#[JsonApiRelationship(Person, name = "writer")]`. If the type is omitted, it is inferred from
+/// the field name, so that a field named `author_id` or `author_ids` is related to `Author`. If
+/// the name is omitted, any `_id` suffix is stripped from the field name, and any `_ids` suffix is
+/// replaced by pluralizing the field name, i.e `author_id` becomes `author` and `tag_ids` becomes
+/// `tags`.
+///
+/// The name in the JSON document is converted with the `rename_all` rule of the struct, unless the
+/// field has a `#[jsonapi(rename = "...")]` or `#[serde(rename = "...")]` attribute.
+pub struct JsonApiRelationship {
+    pub field: Field,
+    pub ident: Ident,
+    /// The name of the relationship in the generated types, e.g the variant of the `include` enum.
+    pub name: Ident,
+    /// The name of the relationship in the `relationships` object, which is also used in the
+    /// `include` and `fields` query parameters and in the relationship routes.
+    pub json_name: String,
+    /// The type of the related resource.
+    pub resource: Ident,
+    pub kind: RelationshipKind,
+    /// The type of the ids of the related resource, i.e `T` in `Option<T>` or `Vec<T>`.
+    pub id_ty: Ty
 }
 
-pub fn get_attrs_and_id(body: Body) -> (JsonApiField, Vec<JsonApiField>) {
+pub fn get_attrs_and_id(
+    body: Body,
+    attrs: &[Attribute]
+) -> (JsonApiField, Vec<JsonApiField>, Vec<JsonApiRelationship>) {
+    let rename_all = get_attribute_value(attrs, "jsonapi", "rename_all")
+        .or_else(|| get_attribute_value(attrs, "serde", "rename_all"));
+
     match body {
         Body::Struct(VariantData::Struct(data)) => {
-            let (mut id, attrs): (Vec<JsonApiField>, Vec<JsonApiField>) = data.into_iter()
+            let (mut id, fields): (Vec<JsonApiField>, Vec<JsonApiField>) = data.into_iter()
                 .map(|f| {
                     let ident = f.ident
                         .clone()
                         .expect("#[derive(JsonApi)] is not supported for tuple structs");
 
+                    let name = get_field_name(&f, &ident, rename_all.as_ref());
+                    let validations = get_validations(&f, &ident);
+                    let filterable = is_filterable(&f, &ident);
+
                     JsonApiField {
                         field: f,
-                        ident: ident
+                        ident: ident,
+                        name: name,
+                        validations: validations,
+                        filterable: filterable
                     }
                 })
                 .partition(|f| {
@@ -52,12 +137,304 @@ pub fn get_attrs_and_id(body: Body) -> (JsonApiField, Vec<JsonApiField>) {
                 );
             }
 
-            return (json_api_id, attrs);
+            let (relationships, attrs): (Vec<JsonApiField>, Vec<JsonApiField>) =
+                fields.into_iter().partition(|f| {
+                    f.field.attrs.iter().any(|a| a.name() == "JsonApiRelationship")
+                });
+
+            let relationships = relationships
+                .into_iter()
+                .map(|f| to_relationship(f, rename_all.as_ref()))
+                .collect();
+
+            return (json_api_id, attrs, relationships);
         }
         _ => panic!("#[derive(JsonApi)] can only be used with structs")
     }
 }
 
+fn to_relationship(
+    json_api_field: JsonApiField,
+    rename_all: Option<&String>
+) -> JsonApiRelationship {
+    let JsonApiField { field, ident, .. } = json_api_field;
+
+    let (kind, id_ty) = match get_inner_ty(&field.ty, "Option") {
+        Some(inner) => (RelationshipKind::NullableToOne, inner),
+        None => match get_inner_ty(&field.ty, "Vec") {
+            Some(inner) => (RelationshipKind::ToMany, inner),
+            None => (RelationshipKind::ToOne, field.ty.clone())
+        }
+    };
+
+    let field_name = ident.to_string();
+    let default_name = if field_name.ends_with("_ids") {
+        field_name.trim_right_matches("_ids").to_plural()
+    } else {
+        field_name.trim_right_matches("_id").to_string()
+    };
+
+    let mut name = default_name.clone();
+    let mut resource = default_name.to_singular().to_pascal_case();
+
+    for attr in field.attrs.iter().filter(|a| a.name() == "JsonApiRelationship") {
+        if let MetaItem::List(_, ref items) = attr.value {
+            for item in items {
+                match *item {
+                    NestedMetaItem::MetaItem(MetaItem::Word(ref ty)) => {
+                        resource = ty.to_string();
+                    }
+                    NestedMetaItem::MetaItem(MetaItem::NameValue(ref key, ref value))
+                        if key == "name" => {
+                        name = lit_to_string(value);
+                    }
+                    _ => panic!("Invalid JsonApiRelationship attribute on {}", &ident)
+                }
+            }
+        }
+    }
+
+    let json_name = get_attribute_value(&field.attrs, "jsonapi", "rename")
+        .or_else(|| get_attribute_value(&field.attrs, "serde", "rename"))
+        .unwrap_or_else(|| match rename_all {
+            Some(rule) => rename_field(&name, rule),
+            None => name.clone()
+        });
+
+    JsonApiRelationship {
+        field: field,
+        ident: ident,
+        name: Ident::new(name),
+        json_name: json_name,
+        resource: Ident::new(resource),
+        kind: kind,
+        id_ty: id_ty
+    }
+}
+
+/// Returns the name of a field in the JSON document.
+///
+/// This is the value of `#[jsonapi(rename = "...")]` or `#[serde(rename = "...")]` on the field if
+/// either is present. Otherwise it's the field name, converted with the `rename_all` rule of the
+/// struct (from `#[jsonapi(rename_all = "...")]` or `#[serde(rename_all = "...")]`) if it has one.
+fn get_field_name(field: &Field, ident: &Ident, rename_all: Option<&String>) -> String {
+    get_attribute_value(&field.attrs, "jsonapi", "rename")
+        .or_else(|| get_attribute_value(&field.attrs, "serde", "rename"))
+        .unwrap_or_else(|| match rename_all {
+            Some(rule) => rename_field(ident.as_ref(), rule),
+            None => ident.to_string()
+        })
+}
+
+/// Converts a snake cased field name with one of the `rename_all` rules that serde supports.
+fn rename_field(field: &str, rule: &str) -> String {
+    let pascal_case = || {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => String::new()
+                }
+            })
+            .collect::<String>()
+    };
+
+    match rule {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_uppercase(),
+        "PascalCase" => pascal_case(),
+        "camelCase" => {
+            let pascal = pascal_case();
+            let mut chars = pascal.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => String::new()
+            }
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_uppercase().replace('_', "-"),
+        _ => panic!("Unknown rename_all rule {}", rule)
+    }
+}
+
+/// Returns the string value of `key` in an attribute such as `#[name(key = "value")]`.
+pub fn get_attribute_value(attrs: &[Attribute], name: &str, key: &str) -> Option<String> {
+    attrs
+        .iter()
+        .filter(|a| a.name() == name)
+        .filter_map(|a| match a.value {
+            MetaItem::List(_, ref items) => Some(items),
+            _ => None
+        })
+        .flat_map(|items| items.iter())
+        .filter_map(|item| match *item {
+            NestedMetaItem::MetaItem(MetaItem::NameValue(ref k, ref value)) if k == key => {
+                Some(lit_to_string(value))
+            }
+            _ => None
+        })
+        .next()
+}
+
+fn get_validations(field: &Field, ident: &Ident) -> Vec<Validation> {
+    let mut validations = Vec::new();
+
+    for attr in field.attrs.iter().filter(|a| a.name() == "jsonapi") {
+        let items = match attr.value {
+            MetaItem::List(_, ref items) => items,
+            _ => panic!("Invalid jsonapi attribute on {}", ident)
+        };
+
+        for item in items {
+            match *item {
+                NestedMetaItem::MetaItem(MetaItem::List(ref name, ref validators))
+                    if name == "validate" => {
+                    validations.extend(validators.iter().map(|v| to_validation(v, ident)));
+                }
+                NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, _)) if name == "rename" => {}
+                NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "filter" => {}
+                _ => panic!("Invalid jsonapi attribute on {}", ident)
+            }
+        }
+    }
+
+    validations
+}
+
+/// The types that implement `rustiful::Filterable`, and can be filtered on without having to
+/// annotate the field.
+#[cfg(feature = "uuid")]
+const FILTERABLE_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64", "char",
+    "bool", "String", "Uuid"
+];
+
+/// The types that implement `rustiful::Filterable`, and can be filtered on without having to
+/// annotate the field.
+#[cfg(not(feature = "uuid"))]
+const FILTERABLE_TYPES: &[&str] = &[
+    "i8", "i16", "i32", "i64", "isize", "u8", "u16", "u32", "u64", "usize", "f32", "f64", "char",
+    "bool", "String"
+];
+
+/// Returns whether a field can be used in the `filter` query parameter, which is the case if the
+/// type of the field (or `T` in `Option<T>`) is one of the `FILTERABLE_TYPES`, or if the field has
+/// the `#[jsonapi(filter)]` attribute. The latter requires the type to implement
+/// `rustiful::Filterable`. Fields of type `Vec<T>` can't be filtered on.
+fn is_filterable(field: &Field, ident: &Ident) -> bool {
+    let has_filter_attribute = field
+        .attrs
+        .iter()
+        .filter(|a| a.name() == "jsonapi")
+        .filter_map(|a| match a.value {
+            MetaItem::List(_, ref items) => Some(items),
+            _ => None
+        })
+        .flat_map(|items| items.iter())
+        .any(|item| match *item {
+            NestedMetaItem::MetaItem(MetaItem::Word(ref name)) => name == "filter",
+            _ => false
+        });
+
+    if get_inner_ty(&field.ty, "Vec").is_some() {
+        if has_filter_attribute {
+            panic!("Fields of type Vec can't be filtered on, found #[jsonapi(filter)] on {}", ident);
+        }
+
+        return false;
+    }
+
+    let ty = get_inner_ty(&field.ty, "Option").unwrap_or_else(|| field.ty.clone());
+    let is_known_type = match ty {
+        Ty::Path(None, ref path) => {
+            path.segments
+                .last()
+                .map(|s| {
+                    s.parameters.is_empty() && FILTERABLE_TYPES.contains(&s.ident.as_ref())
+                })
+                .unwrap_or(false)
+        }
+        _ => false
+    };
+
+    has_filter_attribute || is_known_type
+}
+
+fn to_validation(item: &NestedMetaItem, ident: &Ident) -> Validation {
+    match *item {
+        NestedMetaItem::MetaItem(MetaItem::Word(ref name)) if name == "email" => Validation::Email,
+        NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref value)) if name == "regex" => {
+            // An invalid pattern is a compile error, rather than a panic when validating.
+            let pattern = lit_to_string(value);
+            if let Err(e) = Regex::new(&pattern) {
+                panic!("Invalid regex {} on {}: {}", pattern, ident, e);
+            }
+            Validation::Regex(pattern)
+        }
+        NestedMetaItem::MetaItem(MetaItem::NameValue(ref name, ref value)) if name == "custom" => {
+            Validation::Custom(lit_to_string(value))
+        }
+        NestedMetaItem::MetaItem(MetaItem::List(ref name, ref bounds))
+            if name == "length" || name == "range" => {
+            let mut min = None;
+            let mut max = None;
+
+            for bound in bounds {
+                match *bound {
+                    NestedMetaItem::MetaItem(MetaItem::NameValue(ref key, ref value))
+                        if key == "min" => {
+                        min = Some(value.clone());
+                    }
+                    NestedMetaItem::MetaItem(MetaItem::NameValue(ref key, ref value))
+                        if key == "max" => {
+                        max = Some(value.clone());
+                    }
+                    _ => panic!("Invalid {} validation on {}", name, ident)
+                }
+            }
+
+            if min.is_none() && max.is_none() {
+                panic!("The {} validation on {} needs a min or a max", name, ident);
+            }
+
+            if name == "length" {
+                Validation::Length(min, max)
+            } else {
+                Validation::Range(min, max)
+            }
+        }
+        _ => panic!("Invalid validation on {}", ident)
+    }
+}
+
+fn lit_to_string(lit: &::syn::Lit) -> String {
+    match *lit {
+        ::syn::Lit::Str(ref value, _) => value.to_string(),
+        _ => panic!("Expected a string literal, found {:?}", lit)
+    }
+}
+
+/// Returns `T` if `ty` is `wrapper<T>`, e.g `Option<T>` or `Vec<T>`.
+pub fn get_inner_ty(ty: &Ty, wrapper: &str) -> Option<Ty> {
+    match *ty {
+        Ty::Path(_, ref path) => {
+            path.segments
+                .last()
+                .and_then(|s| if s.ident == wrapper {
+                    match s.parameters {
+                        PathParameters::AngleBracketed(ref data) => data.types.first().cloned(),
+                        _ => None
+                    }
+                } else {
+                    None
+                })
+        }
+        _ => None
+    }
+}
+
 pub fn is_option_ty(ty: &Ty) -> bool {
     let option_ident = Ident::new("Option");
     match *ty {
@@ -0,0 +1,112 @@
+//! The fixtures that the hyper, tower and Rocket adapter tests share. Each of these test modules
+//! has a `call` function, which sends a `TestRequest` to the service or the routes of its
+//! adapter and returns the `TestResponse`.
+
+use rustiful::ATOMIC_EXTENSION;
+use serde_json;
+use serde_json::Value;
+
+/// Registers the endpoints of `Foo` and `Bar` that every adapter test uses on `$builder`, which
+/// can be the builder of any adapter.
+macro_rules! jsonapi_endpoints {
+    ($builder:expr) => {{
+        let builder = &mut $builder;
+        builder.jsonapi_get::<Foo>();
+        builder.jsonapi_post::<Foo>();
+        builder.jsonapi_index::<Foo>();
+        builder.jsonapi_delete::<Foo>();
+        builder.jsonapi_patch::<Foo>();
+        builder.jsonapi_get_relationship::<Bar>();
+        builder.jsonapi_patch_relationship::<Bar>();
+        builder.jsonapi_post_relationship::<Bar>();
+        builder.jsonapi_delete_relationship::<Bar>();
+        builder.jsonapi_get_related::<Bar, Foo>();
+    }};
+}
+
+/// Registers the endpoints of the async resource `Qux` on `$builder`, which can be the builder of
+/// any adapter that serves async resources.
+macro_rules! jsonapi_async_endpoints {
+    ($builder:expr) => {{
+        let builder = &mut $builder;
+        builder.jsonapi_get_async::<Qux>();
+        builder.jsonapi_post_async::<Qux>();
+        builder.jsonapi_index_async::<Qux>();
+        builder.jsonapi_delete_async::<Qux>();
+        builder.jsonapi_patch_async::<Qux>();
+    }};
+}
+
+pub static NEW_FOO: &'static str = r#"{ "data": { "type": "foos", "attributes": {
+    "title": "test", "body": "test", "published": true } } }"#;
+
+/// An `add` operation with a local id, which `FooService` answers with the id `1`.
+pub static ADD_FOO: &'static str = r#"{"atomic:operations": [
+    {"op": "add", "data": {"type": "foos", "lid": "a", "attributes": {"title": "test"}}}
+]}"#;
+
+/// A JSONAPI media type with an extension that no adapter supports.
+pub static UNSUPPORTED_EXTENSION: &'static str =
+    r#"application/vnd.api+json; ext="https://example.com/ext""#;
+
+/// The JSONAPI media type with the Atomic Operations extension.
+pub fn atomic_content_type() -> String {
+    format!(r#"application/vnd.api+json; ext="{}""#, ATOMIC_EXTENSION)
+}
+
+/// A request from `localhost:3000`.
+pub struct TestRequest {
+    pub method: &'static str,
+    pub uri: String,
+    pub headers: Vec<(String, String)>,
+    pub body: String
+}
+
+impl TestRequest {
+    /// A request with the JSONAPI `Content-Type` if `body` isn't empty.
+    pub fn new(method: &'static str, uri: &str, body: &str) -> Self {
+        let request = TestRequest {
+            method: method,
+            uri: uri.to_string(),
+            headers: Vec::new(),
+            body: body.to_string()
+        }.with_header("Host", "localhost:3000");
+
+        if body.is_empty() {
+            request
+        } else {
+            request.with_header("Content-Type", "application/vnd.api+json")
+        }
+    }
+
+    pub fn get(uri: &str) -> Self {
+        Self::new("GET", uri, "")
+    }
+
+    /// Sets the header `name`, replacing any header with the same name.
+    pub fn with_header(mut self, name: &str, value: &str) -> Self {
+        self.headers.retain(|&(ref n, _)| !n.eq_ignore_ascii_case(name));
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+}
+
+/// A response with the status type `S` of the adapter.
+pub struct TestResponse<S> {
+    pub status: S,
+    pub headers: Vec<(String, String)>,
+    pub body: String
+}
+
+impl<S> TestResponse<S> {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+            .map(|&(_, ref value)| value.as_str())
+    }
+
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).expect("Cannot deserialize json!")
+    }
+}
@@ -0,0 +1,166 @@
+extern crate futures_executor;
+
+use self::futures_executor::block_on;
+use resources::async_resource::*;
+use resources::mock_resource::FooService;
+use resources::mock_resource::TestError;
+use rustiful::*;
+use serde_json;
+use std::str::FromStr;
+
+fn ctx() -> Result<FooService, (TestError, u16)> {
+    Ok(FooService {})
+}
+
+fn errors(response: &JsonApiResponse) -> Vec<JsonApiError> {
+    let body = response.body.as_ref().expect("no body found!");
+    serde_json::from_str::<JsonApiErrorArray>(body)
+        .expect("Cannot deserialize json!")
+        .errors
+}
+
+#[test]
+fn dispatch_get() {
+    let request = JsonApiRequest::new("GET", "/quxes/1")
+        .with_param("id", "1")
+        .with_base_url("https://example.com/api");
+    let response = block_on(<Qux as AsyncGetDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(200, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+
+    let body = response.body.expect("no body found!");
+    let record: JsonApiContainer<JsonApiData<Qux>> = serde_json::from_str(&body).unwrap();
+    let params = <Qux as JsonApiResource>::Params::from_str("").unwrap();
+    let expected = JsonApiContainer::from(Qux::new("1", "test").into_json(&params))
+        .with_resource_links("https://example.com/api")
+        .with_self_link("https://example.com/api/quxes/1".to_string());
+
+    assert_eq!(expected, record);
+}
+
+#[test]
+fn dispatch_get_not_found() {
+    let request = JsonApiRequest::new("GET", "/quxes/missing").with_param("id", "missing");
+    let response = block_on(<Qux as AsyncGetDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(404, response.status);
+    assert_eq!("Not found", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_get_with_error() {
+    let request = JsonApiRequest::new("GET", "/quxes/fail").with_param("id", "fail");
+    let response = block_on(<Qux as AsyncGetDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(418, response.status);
+    assert_eq!("fail in get", errors(&response)[0].detail);
+}
+
+#[test]
+fn dispatch_get_with_invalid_query_string() {
+    let request = JsonApiRequest::new("GET", "/quxes/1")
+        .with_param("id", "1")
+        .with_query("fields[quxes]=non_existent");
+    let response = block_on(<Qux as AsyncGetDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(400, response.status);
+}
+
+#[test]
+fn dispatch_index() {
+    let request = JsonApiRequest::new("GET", "/quxes");
+    let response = block_on(<Qux as AsyncIndexDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(200, response.status);
+
+    let body = response.body.expect("no body found!");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!(2, json["data"].as_array().map(|data| data.len()).unwrap_or(0));
+}
+
+#[test]
+fn dispatch_with_context_error() {
+    let request = JsonApiRequest::new("GET", "/quxes");
+    let response = block_on(<Qux as AsyncIndexDispatcher>::dispatch(&request, || {
+        Err::<FooService, _>((TestError("no context".to_string()), 503u16))
+    }));
+
+    assert_eq!(503, response.status);
+    assert_eq!("no context", errors(&response)[0].detail);
+}
+
+#[test]
+fn dispatch_post() {
+    let body = r#"{ "data": { "type": "quxes", "attributes": { "title": "new" } } }"#;
+    let request = JsonApiRequest::new("POST", "/quxes")
+        .with_body(body)
+        .with_base_url("http://localhost:3000");
+    let response = block_on(<Qux as AsyncPostDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(201, response.status);
+    assert_eq!(Some("http://localhost:3000/quxes/1"), response.header("location"));
+}
+
+#[test]
+fn dispatch_post_unchanged_with_client_id() {
+    let body = r#"{
+        "data": { "id": "1", "type": "quxes", "attributes": { "title": "unchanged" } }
+    }"#;
+    let request = JsonApiRequest::new("POST", "/quxes").with_body(body);
+    let response = block_on(<Qux as AsyncPostDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(204, response.status);
+    assert_eq!(None, response.body);
+}
+
+#[test]
+fn dispatch_post_unchanged_without_client_id() {
+    let body = r#"{ "data": { "type": "quxes", "attributes": { "title": "unchanged" } } }"#;
+    let request = JsonApiRequest::new("POST", "/quxes").with_body(body);
+    let response = block_on(<Qux as AsyncPostDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(500, response.status);
+    assert_eq!("Missing created resource", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_post_without_body() {
+    let request = JsonApiRequest::new("POST", "/quxes");
+    let response = block_on(<Qux as AsyncPostDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(400, response.status);
+    assert_eq!("No body", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_patch() {
+    let body = r#"{ "data": { "id": "1", "type": "quxes", "attributes": { "title": "new" } } }"#;
+    let request = JsonApiRequest::new("PATCH", "/quxes/1")
+        .with_param("id", "1")
+        .with_body(body);
+    let response = block_on(<Qux as AsyncPatchDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(200, response.status);
+
+    let body = response.body.expect("no body found!");
+    let record: JsonApiContainer<JsonApiData<Qux>> = serde_json::from_str(&body).unwrap();
+    assert_eq!(Some("new".to_string()), record.data.attributes.title);
+}
+
+#[test]
+fn dispatch_delete() {
+    let request = JsonApiRequest::new("DELETE", "/quxes/1").with_param("id", "1");
+    let response = block_on(<Qux as AsyncDeleteDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(204, response.status);
+    assert_eq!(None, response.body);
+}
+
+#[test]
+fn dispatch_delete_with_error() {
+    let request = JsonApiRequest::new("DELETE", "/quxes/fail").with_param("id", "fail");
+    let response = block_on(<Qux as AsyncDeleteDispatcher>::dispatch(&request, ctx));
+
+    assert_eq!(418, response.status);
+}
@@ -1,7 +1,9 @@
 use resources::diesel_resource::Test;
-use resources::simple_resources::{Bar, Foo};
+use resources::simple_resources::{Article, Author, Bar, Event, Foo, Post, Profile, Review};
 use rustiful::*;
+use serde_json;
 use std::convert::TryInto;
+use std::str::FromStr;
 
 #[test]
 fn test_into_conversions_with_int_id() {
@@ -49,3 +51,344 @@ fn test_setting_of_id_in_try_from() {
     let result: Test = (test, json).try_into().unwrap();
     assert_eq!(expected_id, result.id)
 }
+
+fn article() -> Article {
+    Article {
+        id: "1".to_string(),
+        title: "foo".to_string(),
+        author_id: "2".to_string(),
+        editor_id: None,
+        tag_ids: vec![3, 4]
+    }
+}
+
+#[test]
+fn test_into_conversions_without_relationships() {
+    let test = Bar {
+        id: "test".to_string(),
+        bar: 1
+    };
+
+    let result: JsonApiData<Bar> = test.into_json(&Default::default());
+    assert_eq!(None, result.relationships);
+}
+
+#[test]
+fn test_into_conversions_with_relationships() {
+    let result: JsonApiData<Article> = article().into_json(&Default::default());
+    let relationships = result.relationships.expect("unexpected None on relationships!");
+
+    let author = JsonApiResourceIdentifier::new("authors", "2");
+    assert_eq!(Some(ToOneRelationship::new(Some(author))), relationships.author);
+    assert_eq!(Some(ToOneRelationship::new(None)), relationships.editor);
+
+    let tags = vec![
+        JsonApiResourceIdentifier::new("tags", "3"),
+        JsonApiResourceIdentifier::new("tags", "4")
+    ];
+    assert_eq!(Some(ToManyRelationship::new(tags)), relationships.tags);
+}
+
+#[test]
+fn test_relationships_are_filtered_by_fieldset() {
+    let params = <Article as JsonApiResource>::Params::from_str("fields[articles]=author")
+        .expect("failed to unwrap params");
+    let result: JsonApiData<Article> = article().into_json(&params);
+    let relationships = result.relationships.expect("unexpected None on relationships!");
+
+    assert_eq!(None, result.attributes.title);
+    assert!(relationships.author.is_some());
+    assert_eq!(None, relationships.editor);
+    assert_eq!(None, relationships.tags);
+}
+
+#[test]
+fn test_serialize_relationships() {
+    let json = serde_json::to_string(&article().into_json(&Default::default())).unwrap();
+    let expected = r#"{"id":"1","type":"articles","attributes":{"title":"foo"},"relationships":{"author":{"data":{"id":"2","type":"authors"}},"editor":{"data":null},"tags":{"data":[{"id":"3","type":"tags"},{"id":"4","type":"tags"}]}}}"#;
+    assert_eq!(expected, json);
+}
+
+#[test]
+fn test_serialize_renamed_attributes() {
+    let event = Event {
+        id: 1,
+        created_at: 5,
+        title: "foo".to_string(),
+        venue_name: None
+    };
+
+    let json = serde_json::to_string(&event.into_json(&Default::default())).unwrap();
+    let expected = r#"{"id":"1","type":"events","attributes":{"createdAt":5,"headline":"foo","place":null}}"#;
+    assert_eq!(expected, json);
+}
+
+#[test]
+fn test_try_from_with_renamed_attributes() {
+    let json = r#"{
+        "type": "events",
+        "attributes": { "createdAt": 5, "headline": "foo", "place": "bar" }
+    }"#;
+
+    let data: JsonApiData<Event> = serde_json::from_str(json).unwrap();
+    let result: Event = data.try_into().unwrap();
+
+    assert_eq!(5, result.created_at);
+    assert_eq!("foo", result.title);
+    assert_eq!(Some("bar".to_string()), result.venue_name);
+}
+
+#[test]
+fn test_serialize_renamed_relationships() {
+    let review = Review {
+        id: "1".to_string(),
+        star_rating: 4,
+        lead_reviewer_id: "2".to_string(),
+        reviewed_article_id: None
+    };
+
+    let json = serde_json::to_string(&review.into_json(&Default::default())).unwrap();
+    let expected = r#"{"id":"1","type":"reviews","attributes":{"starRating":4},"relationships":{"leadReviewer":{"data":{"id":"2","type":"authors"}},"subject":{"data":null}}}"#;
+    assert_eq!(expected, json);
+}
+
+#[test]
+fn test_try_from_with_renamed_relationships() {
+    let json = r#"{
+        "type": "reviews",
+        "attributes": { "starRating": 4 },
+        "relationships": {
+            "leadReviewer": { "data": { "type": "authors", "id": "5" } },
+            "subject": { "data": { "type": "articles", "id": "6" } }
+        }
+    }"#;
+
+    let data: JsonApiData<Review> = serde_json::from_str(json).unwrap();
+    let result: Review = data.try_into().unwrap();
+
+    assert_eq!("5", result.lead_reviewer_id);
+    assert_eq!(Some("6".to_string()), result.reviewed_article_id);
+}
+
+#[test]
+fn test_try_from_with_relationships() {
+    let json = r#"{
+        "type": "articles",
+        "attributes": {
+            "title": "foo"
+        },
+        "relationships": {
+            "author": { "data": { "type": "authors", "id": "5" } },
+            "editor": { "data": { "type": "authors", "id": "6" } },
+            "tags": { "data": [{ "type": "tags", "id": "7" }] }
+        }
+    }"#;
+
+    let data: JsonApiData<Article> = serde_json::from_str(json).unwrap();
+    let result: Article = data.try_into().unwrap();
+
+    assert_eq!("5", result.author_id);
+    assert_eq!(Some("6".to_string()), result.editor_id);
+    assert_eq!(vec![7], result.tag_ids);
+}
+
+#[test]
+fn test_try_from_fails_on_invalid_relationship_type() {
+    let json = r#"{
+        "type": "articles",
+        "attributes": {
+            "title": "foo"
+        },
+        "relationships": {
+            "author": { "data": { "type": "tags", "id": "5" } },
+            "tags": { "data": [] }
+        }
+    }"#;
+
+    let data: JsonApiData<Article> = serde_json::from_str(json).unwrap();
+    let result: Result<Article, _> = data.try_into();
+
+    assert_eq!(
+        Err(ConversionError::InvalidRelationship(
+            "author".to_string(),
+            "Invalid type name 'tags', expected 'authors'".to_string()
+        )),
+        result
+    );
+}
+
+#[test]
+fn test_patch_only_updates_present_relationships() {
+    let json = r#"{
+        "id": "1",
+        "type": "articles",
+        "attributes": {},
+        "relationships": {
+            "editor": { "data": { "type": "authors", "id": "6" } }
+        }
+    }"#;
+
+    let data: JsonApiData<Article> = serde_json::from_str(json).unwrap();
+    let result: Article = (article(), data).try_into().unwrap();
+
+    assert_eq!("2", result.author_id);
+    assert_eq!(Some("6".to_string()), result.editor_id);
+    assert_eq!(vec![3, 4], result.tag_ids);
+}
+
+fn profile_data(attributes: &str) -> JsonApiData<Profile> {
+    let json = format!(r#"{{"type": "profiles", "attributes": {}}}"#, attributes);
+    serde_json::from_str(&json).unwrap()
+}
+
+#[test]
+fn test_try_from_with_valid_attributes() {
+    let data = profile_data(r#"{"name": "foo", "age": 30, "email": null, "handle": "foo_1"}"#);
+    let result: Profile = data.try_into().unwrap();
+
+    assert_eq!("foo", result.name);
+    assert_eq!(None, result.email);
+}
+
+fn validation_errors(result: Result<Profile, ConversionError>) -> Vec<ValidationError> {
+    match result {
+        Err(ConversionError::Validation(errors)) => errors,
+        other => panic!("expected validation errors, got {:?}", other)
+    }
+}
+
+#[test]
+fn test_try_from_fails_on_invalid_attributes() {
+    let data = profile_data(
+        r#"{"name": "", "age": 200, "email": "foo", "handle": "Not a handle"}"#
+    );
+    let errors = validation_errors(data.try_into());
+
+    let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+    let codes: Vec<_> = errors.iter().map(|e| e.code.as_str()).collect();
+
+    assert_eq!(vec!["name", "age", "email", "handle"], fields);
+    assert_eq!(vec!["length", "range", "email", "regex"], codes);
+}
+
+#[test]
+fn test_try_from_runs_custom_validators() {
+    let data = profile_data(r#"{"name": "foo", "age": 30, "email": null, "handle": "admin"}"#);
+    let errors = validation_errors(data.try_into());
+
+    assert_eq!(
+        vec![ValidationError::new("handle", "custom", "admin is a reserved handle")],
+        errors
+    );
+}
+
+#[test]
+fn test_try_from_validates_the_updated_resource() {
+    let profile = Profile {
+        id: "1".to_string(),
+        name: "foo".to_string(),
+        age: 30,
+        email: Some("foo@example.com".to_string()),
+        handle: "foo".to_string()
+    };
+
+    let data = profile_data(r#"{"email": "not an email"}"#);
+    let errors = validation_errors((profile, data).try_into());
+
+    assert_eq!(1, errors.len());
+    assert_eq!("email", errors[0].field);
+}
+
+#[test]
+fn test_try_from_fails_on_invalid_id() {
+    let json = r#"{"id": "abc", "type": "posts", "attributes": {}}"#;
+    let data: JsonApiData<Post> = serde_json::from_str(json).unwrap();
+    let result: Result<Post, _> = data.try_into();
+
+    match result {
+        Err(ref error @ ConversionError::InvalidId(_)) => {
+            let array = JsonApiErrorArray::from_error(error, 500);
+            assert_eq!(Some(400), array.status());
+            assert_eq!(Some(JsonApiErrorSource::pointer("/data/id")), array.errors[0].source);
+        }
+        other => panic!("expected an invalid id, got {:?}", other)
+    }
+}
+
+#[test]
+fn test_build_fails_on_missing_attribute() {
+    let builder: <Profile as ToBuilder>::Builder = Default::default();
+
+    assert_eq!(
+        Err(ConversionError::MissingAttribute("name".to_string())),
+        builder.build()
+    );
+}
+
+#[test]
+fn test_compound_document_deduplicates_included_resources() {
+    let author = || Author {
+        id: "2".to_string(),
+        name: "bar".to_string(),
+        article_ids: vec!["1".to_string()]
+    };
+
+    let mut other = article();
+    other.id = "5".to_string();
+
+    let params = Default::default();
+    let data = vec![article().into_json(&params), other.into_json(&params)];
+
+    let container = JsonApiContainer::from(data)
+        .include(author().into_json(&Default::default()))
+        .include(author().into_json(&Default::default()))
+        .include(article().into_json(&params));
+    assert_eq!(1, container.included.len());
+
+    let json = serde_json::to_value(&container).unwrap();
+    assert_eq!("authors", json["included"][0]["type"]);
+    assert_eq!("2", json["included"][0]["id"]);
+    assert_eq!("bar", json["included"][0]["attributes"]["name"]);
+}
+
+#[test]
+fn test_page_with_included_resources() {
+    let author = || Author {
+        id: "2".to_string(),
+        name: "bar".to_string(),
+        article_ids: vec!["1".to_string()]
+    };
+
+    let params = Default::default();
+    let page = JsonApiPage::new(vec![article().into_json(&params)])
+        .include(author().into_json(&Default::default()))
+        .include(author().into_json(&Default::default()));
+
+    let container = page.into_container("http://localhost/articles", None);
+    assert_eq!(1, container.included.len());
+    assert_eq!(
+        Some(JsonApiResourceIdentifier::new("authors", "2")),
+        container.included[0].identifier()
+    );
+}
+
+#[derive(Serialize)]
+struct Permissions {
+    can_edit: bool
+}
+
+#[test]
+fn test_resource_and_document_meta() {
+    let mut deprecation = JsonApiMeta::new();
+    deprecation.insert("deprecated".to_string(), serde_json::Value::Bool(true));
+
+    let data = article()
+        .into_json(&Default::default())
+        .with_meta(to_meta(&Permissions { can_edit: false }).unwrap());
+
+    let container = JsonApiContainer::from(data).with_meta(deprecation);
+
+    let json = serde_json::to_value(&container).unwrap();
+    assert_eq!(false, json["data"]["meta"]["can_edit"]);
+    assert_eq!(true, json["meta"]["deprecated"]);
+}
@@ -0,0 +1,261 @@
+use resources::mock_resource::*;
+use resources::simple_resources::{article, Article, Author};
+use rustiful::*;
+use serde_json;
+use std::str::FromStr;
+
+fn ctx() -> Result<FooService, (TestError, u16)> {
+    Ok(FooService {})
+}
+
+fn errors(response: &JsonApiResponse) -> Vec<JsonApiError> {
+    let body = response.body.as_ref().expect("no body found!");
+    serde_json::from_str::<JsonApiErrorArray>(body)
+        .expect("Cannot deserialize json!")
+        .errors
+}
+
+#[test]
+fn dispatch_get() {
+    let request = JsonApiRequest::new("GET", "/foos/1")
+        .with_param("id", "1")
+        .with_query("fields[foos]=title")
+        .with_base_url("https://example.com/api");
+    let response = <Foo as GetDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(200, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+
+    let body = response.body.expect("no body found!");
+    let record: JsonApiContainer<JsonApiData<Foo>> = serde_json::from_str(&body).unwrap();
+    let params = <Foo as JsonApiResource>::Params::from_str("fields[foos]=title").unwrap();
+    let expected = JsonApiContainer::from(Foo::new("1", "test", "test", true).into_json(&params))
+        .with_resource_links("https://example.com/api")
+        .with_self_link("https://example.com/api/foos/1?fields[foos]=title".to_string());
+
+    assert_eq!(expected, record);
+}
+
+#[test]
+fn dispatch_get_not_found() {
+    let request = JsonApiRequest::new("GET", "/foos/missing").with_param("id", "missing");
+    let response = <Foo as GetDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(404, response.status);
+    assert_eq!("Not found", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_get_with_invalid_query_string() {
+    let request = JsonApiRequest::new("GET", "/foos/1")
+        .with_param("id", "1")
+        .with_query("fields[foos]=non_existent");
+    let response = <Foo as GetDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(400, response.status);
+}
+
+#[test]
+fn dispatch_with_context_error() {
+    let request = JsonApiRequest::new("GET", "/foos");
+    let response = <Foo as IndexDispatcher>::dispatch(&request, || {
+        Err::<FooService, _>((TestError("no context".to_string()), 503u16))
+    });
+
+    assert_eq!(503, response.status);
+    assert_eq!("no context", errors(&response)[0].detail);
+}
+
+#[test]
+fn dispatch_index_with_page_out_of_range() {
+    let request = JsonApiRequest::new("GET", "/foos")
+        .with_query("page[number]=18446744073709551615&page[size]=10");
+    let response = <Foo as IndexDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(400, response.status);
+    assert_eq!("page[number]", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_post() {
+    let body = r#"{ "data": { "type": "foos", "attributes": { "title": "test", "body": "test",
+        "published": true } } }"#;
+    let request = JsonApiRequest::new("POST", "/foos")
+        .with_body(body)
+        .with_base_url("http://localhost:3000");
+    let response = <Foo as PostDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(201, response.status);
+    assert_eq!(Some("http://localhost:3000/foos/1"), response.header("location"));
+
+    let body = response.body.expect("no body found!");
+    let record: JsonApiContainer<JsonApiData<Foo>> = serde_json::from_str(&body).unwrap();
+    assert_eq!(Some(&1.into()), record.meta.get("revision"));
+}
+
+#[test]
+fn dispatch_patch() {
+    let body = r#"{ "data": { "id": "1", "type": "foos", "attributes": { "title": "new" } } }"#;
+    let request = JsonApiRequest::new("PATCH", "/foos/1")
+        .with_param("id", "1")
+        .with_body(body);
+    let response = <Foo as PatchDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(200, response.status);
+
+    let body = response.body.expect("no body found!");
+    let record: JsonApiContainer<JsonApiData<Foo>> = serde_json::from_str(&body).unwrap();
+    assert_eq!(Some("1".to_string()), record.data.id);
+    assert_eq!(Some(&2.into()), record.meta.get("revision"));
+}
+
+#[test]
+fn dispatch_post_unchanged_with_client_id() {
+    let body = r#"{ "data": { "id": "unchanged", "type": "foos", "attributes": {} } }"#;
+    let request = JsonApiRequest::new("POST", "/foos").with_body(body);
+    let response = <Foo as PostDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(204, response.status);
+    assert_eq!(None, response.body);
+}
+
+#[test]
+fn dispatch_post_unchanged_without_client_id() {
+    let body = r#"{ "data": { "type": "foos", "attributes": { "title": "unchanged" } } }"#;
+    let request = JsonApiRequest::new("POST", "/foos").with_body(body);
+    let response = <Foo as PostDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(500, response.status);
+    assert_eq!("Missing created resource", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_post_without_body() {
+    let request = JsonApiRequest::new("POST", "/foos");
+    let response = <Foo as PostDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(400, response.status);
+    assert_eq!("No body", errors(&response)[0].title);
+}
+
+#[test]
+fn dispatch_post_with_invalid_body() {
+    let body = r#"{ "data": { "type": "foos", "attributes": { "published": "fail" } } }"#;
+    let request = JsonApiRequest::new("POST", "/foos").with_body(body);
+    let response = <Foo as PostDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(400, response.status);
+    assert_eq!(
+        Some(JsonApiErrorSource::pointer("/data")),
+        errors(&response)[0].source
+    );
+}
+
+#[test]
+fn dispatch_delete() {
+    let request = JsonApiRequest::new("DELETE", "/foos/1").with_param("id", "1");
+    let response = <Foo as DeleteDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(204, response.status);
+    assert_eq!(None, response.body);
+    assert_eq!(None, response.header("Content-Type"));
+}
+
+#[test]
+fn dispatch_unknown_relationship() {
+    let request = JsonApiRequest::new("GET", "/bars/1/relationships/baz")
+        .with_param("id", "1")
+        .with_param("relationship", "baz");
+    let response = <Bar as GetRelationshipDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(404, response.status);
+}
+
+#[test]
+fn dispatch_without_route_params() {
+    let request = JsonApiRequest::new("GET", "/foos/1");
+    let response = <Foo as GetDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(500, response.status);
+    assert!(response.body.unwrap().contains("Missing route parameter"));
+
+    let request = JsonApiRequest::new("GET", "/bars/1/relationships/foos").with_param("id", "1");
+    let response = <Bar as GetRelationshipDispatcher>::dispatch(&request, ctx);
+
+    assert_eq!(500, response.status);
+}
+
+/// The author of an article, with the article side-loaded if it's requested with `include`.
+impl JsonGetRelated<Author> for Article {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find_related(
+        id: Self::JsonApiIdType,
+        _: Self::RelationshipField,
+        params: &<Author as JsonApiResource>::Params,
+        _: Self::Context
+    ) -> Result<Option<JsonApiRelated<Author>>, (Self::Error, Self::Status)> {
+        let author = Author {
+            id: "2".to_string(),
+            name: "bar".to_string(),
+            article_ids: vec![id.clone()]
+        };
+        let mut container = JsonApiContainer::from(author.into_json(params));
+
+        if !params.include.fields.is_empty() {
+            let article = Article {
+                id: id,
+                title: "foo".to_string(),
+                author_id: "2".to_string(),
+                editor_id: None,
+                tag_ids: vec![]
+            };
+            container = container.include(article.into_json(&Default::default()));
+        }
+
+        Ok(Some(container.into()))
+    }
+}
+
+#[test]
+fn dispatch_to_one_related_with_included_resources() {
+    let request = JsonApiRequest::new("GET", "/articles/1/author")
+        .with_param("id", "1")
+        .with_query("include=articles");
+    let relationship = article::relationship::author;
+    let response = <Article as GetRelatedDispatcher<Author>>::dispatch(&request, relationship, ctx);
+
+    assert_eq!(200, response.status);
+
+    let body = response.body.expect("no body found!");
+    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+    assert_eq!("authors", json["data"]["type"]);
+    assert_eq!("articles", json["included"][0]["type"]);
+    assert_eq!("1", json["included"][0]["id"]);
+}
+
+#[test]
+fn dispatch_operations_with_unknown_local_id() {
+    let mut operations = OperationsHandler::<FooService>::new();
+    operations.add::<Foo>();
+
+    let mut media_type = JsonApiMediaType::default();
+    media_type.ext.push(ATOMIC_EXTENSION.to_string());
+
+    let body = r#"{"atomic:operations": [
+        {"op": "add", "data": {"type": "foos", "lid": "a", "attributes": {"title": "test"}}},
+        {"op": "add", "data": {"type": "foos", "attributes": {"title": "test"},
+            "relationships": {"foo": {"data": {"type": "foos", "lid": "b"}}}}}
+    ]}"#;
+    let request = JsonApiRequest::new("POST", "/operations")
+        .with_body(body)
+        .with_media_type(media_type);
+    let response = operations.dispatch(&request, ctx);
+    let errors = errors(&response);
+
+    assert_eq!(400, response.status);
+    assert_eq!("No resource was added with the local id 'b'", errors[0].detail);
+    assert_eq!(Some(JsonApiErrorSource::pointer("/atomic:operations/1")), errors[0].source);
+    assert_eq!(Some(TransactionOutcome::RolledBack), last_transaction());
+}
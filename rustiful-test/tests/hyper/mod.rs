@@ -0,0 +1,4 @@
+extern crate futures;
+extern crate hyper;
+
+mod service_tests;
@@ -0,0 +1,270 @@
+use super::futures::Future;
+use super::futures::Stream;
+use super::hyper::Body;
+use super::hyper::Request;
+use super::hyper::StatusCode;
+use super::hyper::service::Service;
+use adapter::*;
+use resources::async_resource::*;
+use resources::mock_resource::*;
+use rustiful::IntoJson;
+use rustiful::JsonApiContainer;
+use rustiful::JsonApiData;
+use rustiful::JsonGet;
+use rustiful::OperationsHandler;
+use rustiful::hyper::FromRequest;
+use rustiful::hyper::JsonApiServiceBuilder;
+
+impl FromRequest for FooService {
+    type Error = TestError;
+    fn from_request(request: &Request<()>) -> Result<Self, (Self::Error, StatusCode)> {
+        if request.headers().contains_key("test-fail") {
+            return Err((
+                TestError("from request fail".to_string()),
+                StatusCode::INTERNAL_SERVER_ERROR
+            ));
+        }
+        Ok(FooService {})
+    }
+}
+
+/// A resource with the hyper status as the status of its service traits.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+#[jsonapi(status = "StatusCode")]
+pub struct Gadget {
+    pub id: String,
+    pub name: String
+}
+
+impl JsonGet for Gadget {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find(
+        id: Self::JsonApiIdType,
+        params: &Self::Params,
+        _: Self::Context
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, StatusCode)> {
+        if id == "fail" {
+            return Err((TestError("fail in get".to_string()), StatusCode::IM_A_TEAPOT));
+        }
+
+        let gadget = Gadget {
+            id: id,
+            name: "test".to_string()
+        };
+        Ok(Some(gadget.into_json(params).into()))
+    }
+}
+
+fn builder() -> JsonApiServiceBuilder {
+    let mut builder = JsonApiServiceBuilder::default();
+    jsonapi_endpoints!(builder);
+    jsonapi_async_endpoints!(builder);
+    builder.jsonapi_get::<Gadget>();
+    builder
+}
+
+fn call(builder: JsonApiServiceBuilder, req: TestRequest) -> TestResponse<StatusCode> {
+    let mut request = Request::builder();
+    request.method(req.method).uri(req.uri.as_str());
+
+    for &(ref name, ref value) in &req.headers {
+        request.header(name.as_str(), value.as_str());
+    }
+
+    let request = request.body(Body::from(req.body)).unwrap();
+    let response = builder.build().call(request).wait().unwrap();
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+        .collect();
+    let body = response.into_body().concat2().wait().unwrap();
+
+    TestResponse {
+        status: status,
+        headers: headers,
+        body: String::from_utf8(body.to_vec()).unwrap()
+    }
+}
+
+#[test]
+fn get() {
+    let response = call(builder(), TestRequest::get("/foos/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn get_with_base_url() {
+    let mut builder = builder();
+    builder.set_base_url("https://example.com/api");
+    let response = call(builder, TestRequest::get("/foos/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!("https://example.com/api/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn index() {
+    let response = call(builder(), TestRequest::get("/foos"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert!(response.json()["data"].is_array());
+}
+
+#[test]
+fn post() {
+    let response = call(builder(), TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(StatusCode::CREATED, response.status);
+    assert_eq!(Some("http://localhost:3000/foos/1"), response.header("Location"));
+}
+
+#[test]
+fn delete() {
+    let response = call(builder(), TestRequest::new("DELETE", "/foos/1", ""));
+
+    assert_eq!(StatusCode::NO_CONTENT, response.status);
+    assert_eq!(None, response.header("Content-Type"));
+    assert_eq!("", response.body);
+}
+
+#[test]
+fn get_relationship() {
+    let response = call(builder(), TestRequest::get("/bars/1/relationships/foos"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(2, response.json()["data"].as_array().unwrap().len());
+}
+
+#[test]
+fn get_related() {
+    let response = call(builder(), TestRequest::get("/bars/1/foo"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!("foos", response.json()["data"]["type"]);
+}
+
+#[test]
+fn unknown_route() {
+    let response = call(builder(), TestRequest::get("/bazs/1"));
+
+    assert_eq!(StatusCode::NOT_FOUND, response.status);
+    assert_eq!("Not found", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_with_percent_encoded_id() {
+    let response = call(builder(), TestRequest::get("/foos/f%61il"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn method_not_allowed() {
+    let response = call(builder(), TestRequest::new("PUT", "/foos/1", NEW_FOO));
+
+    assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status);
+    assert!(response.header("Allow").unwrap().contains("GET"));
+    assert_eq!("Method not allowed", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn context_error() {
+    let req = TestRequest::get("/foos/1").with_header("test-fail", "true");
+    let response = call(builder(), req);
+
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status);
+    assert_eq!("from request fail", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn body_too_large() {
+    let mut builder = builder();
+    builder.set_max_body_length(10);
+    let response = call(builder, TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status);
+    assert_eq!("Body too large", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_async() {
+    let response = call(builder(), TestRequest::get("/quxes/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/quxes/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn get_async_with_error() {
+    let response = call(builder(), TestRequest::get("/quxes/fail"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn post_async() {
+    let body = r#"{ "data": { "type": "quxes", "attributes": { "title": "test" } } }"#;
+    let response = call(builder(), TestRequest::new("POST", "/quxes", body));
+
+    assert_eq!(StatusCode::CREATED, response.status);
+    assert_eq!(Some("http://localhost:3000/quxes/1"), response.header("Location"));
+}
+
+#[test]
+fn get_with_hyper_status() {
+    let response = call(builder(), TestRequest::get("/gadgets/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!("1", response.json()["data"]["id"]);
+
+    let response = call(builder(), TestRequest::get("/gadgets/fail"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn post_without_json_api_content_type() {
+    let req = TestRequest::new("POST", "/foos", NEW_FOO)
+        .with_header("Content-Type", "application/json");
+    let response = call(builder(), req);
+
+    assert_eq!(StatusCode::UNSUPPORTED_MEDIA_TYPE, response.status);
+    assert_eq!("Unsupported media type", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_with_unsupported_extension_in_accept() {
+    let req = TestRequest::get("/foos/1").with_header("Accept", UNSUPPORTED_EXTENSION);
+
+    assert_eq!(StatusCode::NOT_ACCEPTABLE, call(builder(), req).status);
+}
+
+#[test]
+fn operations() {
+    let mut operations = OperationsHandler::<FooService>::new();
+    operations.add::<Foo>();
+
+    let mut builder = builder();
+    builder.jsonapi_operations(operations);
+
+    let req = TestRequest::new("POST", "/operations", ADD_FOO)
+        .with_header("Content-Type", &atomic_content_type());
+    let response = call(builder, req);
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(Some(atomic_content_type().as_str()), response.header("Content-Type"));
+    assert_eq!("1", response.json()["atomic:results"][0]["data"]["id"]);
+}
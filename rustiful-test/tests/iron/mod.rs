@@ -3,3 +3,4 @@ extern crate iron_test;
 
 mod request_tests;
 mod post_and_patch_tests;
+mod operations_tests;
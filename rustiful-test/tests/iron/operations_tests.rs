@@ -0,0 +1,129 @@
+extern crate iron;
+
+use self::iron::Chain;
+use self::iron::Headers;
+use self::iron::prelude::*;
+use super::iron_test::{request, response};
+use resources::mock_resource::*;
+use rustiful::*;
+use rustiful::iron::*;
+use rustiful::iron::status::Status;
+use serde_json;
+
+fn operations_router() -> Chain {
+    let mut operations = OperationsHandler::<FooService>::new();
+    operations.add::<Foo>();
+    operations.update::<Foo>();
+    operations.remove::<Foo>();
+
+    let mut router = JsonApiRouterBuilder::default();
+    router.jsonapi_operations(operations);
+    router.build()
+}
+
+fn post_operations(content_type: &str, data: &str) -> Result<Response, IronError> {
+    let mut headers = Headers::new();
+    headers.set_raw("Content-Type", vec![content_type.as_bytes().to_vec()]);
+
+    request::post("http://localhost:3000/operations", headers, data, &operations_router())
+}
+
+fn atomic_content_type() -> String {
+    format!(r#"application/vnd.api+json; ext="{}""#, ATOMIC_EXTENSION)
+}
+
+#[test]
+fn apply_operations_with_local_ids() {
+    let data = r#"{"atomic:operations": [
+        {"op": "add", "data": {"type": "foos", "lid": "a", "attributes": {"title": "test"}}},
+        {"op": "update", "ref": {"type": "foos", "lid": "a"},
+            "data": {"type": "foos", "attributes": {"title": "updated"}}},
+        {"op": "remove", "ref": {"type": "foos", "lid": "a"}}
+    ]}"#;
+    let response = post_operations(&atomic_content_type(), data).unwrap();
+
+    assert_eq!(Some(Status::Ok), response.status);
+    assert_eq!(
+        Some(&[atomic_content_type().into_bytes()][..]),
+        response.headers.get_raw("Content-Type")
+    );
+
+    let result = response::extract_body_to_string(response);
+    let document: JsonApiOperationResults = serde_json::from_str(&result).unwrap();
+
+    assert_eq!(3, document.results.len());
+    assert_eq!(Some("1"), document.results[0].data.as_ref().and_then(|d| d["id"].as_str()));
+    assert_eq!(Some("1"), document.results[1].data.as_ref().and_then(|d| d["id"].as_str()));
+    assert_eq!(None, document.results[2].data);
+    assert_eq!(Some(&1.into()), document.results[0].meta.get("revision"));
+    assert_eq!(Some(&2.into()), document.results[1].meta.get("revision"));
+    assert_eq!(Some(TransactionOutcome::Committed), last_transaction());
+}
+
+#[test]
+fn apply_operations_without_results() {
+    let data = r#"{"atomic:operations": [{"op": "remove", "ref": {"type": "foos", "id": "1"}}]}"#;
+    let response = post_operations(&atomic_content_type(), data).unwrap();
+
+    assert_eq!(Some(Status::NoContent), response.status);
+    assert_eq!(Some(TransactionOutcome::Committed), last_transaction());
+}
+
+#[test]
+fn apply_operations_with_unknown_local_id() {
+    let data = r#"{"atomic:operations": [
+        {"op": "add", "data": {"type": "foos", "lid": "a", "attributes": {"title": "test"}}},
+        {"op": "remove", "ref": {"type": "foos", "lid": "b"}}
+    ]}"#;
+    let response = post_operations(&atomic_content_type(), data).unwrap();
+
+    assert_eq!(Some(Status::BadRequest), response.status);
+
+    let result = response::extract_body_to_string(response);
+    let errors: JsonApiErrorArray = serde_json::from_str(&result).unwrap();
+
+    assert_eq!("Unknown local id", errors.errors[0].title);
+    assert_eq!("No resource was added with the local id 'b'", errors.errors[0].detail);
+    assert_eq!(
+        Some(JsonApiErrorSource::pointer("/atomic:operations/1")),
+        errors.errors[0].source
+    );
+    assert_eq!(Some(TransactionOutcome::RolledBack), last_transaction());
+}
+
+#[test]
+fn apply_operations_with_failing_operation() {
+    let data = r#"{"atomic:operations": [
+        {"op": "remove", "ref": {"type": "foos", "id": "1"}},
+        {"op": "remove", "ref": {"type": "foos", "id": "fail"}}
+    ]}"#;
+    let response = post_operations(&atomic_content_type(), data).unwrap();
+
+    assert_eq!(Some(Status::ImATeapot), response.status);
+
+    let result = response::extract_body_to_string(response);
+    let errors: JsonApiErrorArray = serde_json::from_str(&result).unwrap();
+
+    assert_eq!("fail in delete", errors.errors[0].title);
+    assert_eq!(
+        Some(JsonApiErrorSource::pointer("/atomic:operations/1")),
+        errors.errors[0].source
+    );
+    assert_eq!(Some(TransactionOutcome::RolledBack), last_transaction());
+}
+
+#[test]
+fn apply_operations_on_unregistered_type() {
+    let data = r#"{"atomic:operations": [{"op": "remove", "ref": {"type": "bars", "id": "1"}}]}"#;
+    let response = post_operations(&atomic_content_type(), data).unwrap();
+
+    assert_eq!(Some(Status::BadRequest), response.status);
+}
+
+#[test]
+fn apply_operations_without_extension() {
+    let data = r#"{"atomic:operations": []}"#;
+    let response = post_operations("application/vnd.api+json", data).unwrap();
+
+    assert_eq!(Some(Status::UnsupportedMediaType), response.status);
+}
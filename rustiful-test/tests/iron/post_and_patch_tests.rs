@@ -38,11 +38,11 @@ impl FromRequest for DB {
     }
 }
 
-impl From<MyErr> for (MyErr, Status) {
+impl From<MyErr> for (MyErr, u16) {
     fn from(err: MyErr) -> Self {
         match err {
-            MyErr::UpdateError(_) => (err, Status::ImATeapot),
-            _ => (err, Status::InternalServerError)
+            MyErr::UpdateError(_) => (err, 418),
+            _ => (err, 500)
         }
     }
 }
@@ -127,7 +127,7 @@ mutex_test! {
         let expected = JsonApiData::new(
             Some(id),
             <Test as ToJson>::Attrs::new(Some("test".to_string()), Some(None), None),
-        );
+        ).with_self_link("http://localhost:3000");
 
         assert_eq!(created.data, expected);
     }
@@ -267,7 +267,7 @@ mutex_test! {
             let expected = JsonApiData::new(
                 Some(id),
                 <Test as ToJson>::Attrs::new(Some("funky".to_string()), Some(None), None),
-            );
+            ).with_self_link("http://localhost:3000");
 
             assert_eq!(updated.data, expected);
         }
@@ -294,8 +294,10 @@ fn do_post_with_url(json: &str, url: &str) -> JsonApiContainer<JsonApiData<Test>
     let mut headers = Headers::new();
     headers.set::<ContentType>(ContentType(content_type));
 
-    let response = request::post(url, headers, &json, &app_router());
-    let result = response::extract_body_to_string(response.unwrap());
+    let response = request::post(url, headers, &json, &app_router()).unwrap();
+    assert_eq!(Some(Status::Created), response.status);
+
+    let result = response::extract_body_to_string(response);
 
     serde_json::from_str(&result).unwrap()
 }
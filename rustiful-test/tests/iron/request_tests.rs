@@ -3,9 +3,12 @@ extern crate iron;
 use self::iron::Chain;
 use self::iron::Headers;
 use self::iron::headers::ContentType;
+use self::iron::headers::Location;
+use self::iron::method::Method;
 use self::iron::mime::Mime;
 use self::iron::prelude::*;
 use super::iron_test::{request, response};
+use adapter::NEW_FOO;
 use resources::mock_resource::*;
 use rustiful::*;
 use rustiful::iron::*;
@@ -26,12 +29,6 @@ impl FromRequest for FooService {
     }
 }
 
-impl From<TestError> for (TestError, Status) {
-    fn from(err: TestError) -> Self {
-        (err, Status::ImATeapot)
-    }
-}
-
 fn app_router() -> Chain {
     let mut router = JsonApiRouterBuilder::default();
     router.jsonapi_get::<Foo>();
@@ -39,6 +36,24 @@ fn app_router() -> Chain {
     router.jsonapi_index::<Foo>();
     router.jsonapi_delete::<Foo>();
     router.jsonapi_patch::<Foo>();
+    router.jsonapi_get_relationship::<Bar>();
+    router.jsonapi_patch_relationship::<Bar>();
+    router.jsonapi_post_relationship::<Bar>();
+    router.jsonapi_delete_relationship::<Bar>();
+    router.jsonapi_get_related::<Bar, Foo>();
+    router.build()
+}
+
+static ATOMIC: &'static str = "https://jsonapi.org/ext/atomic";
+static CURSOR_PAGINATION: &'static str =
+    "https://jsonapi.org/profiles/ethanresnick/cursor-pagination";
+
+fn extension_router() -> Chain {
+    let mut router = JsonApiRouterBuilder::default();
+    router.jsonapi_get::<Foo>();
+    router.jsonapi_post::<Foo>();
+    router.add_extension(ATOMIC);
+    router.add_profile(CURSOR_PAGINATION);
     router.build()
 }
 
@@ -61,7 +76,8 @@ fn parse_json_api_index_get() {
         published: true
     };
     let data: JsonApiData<Foo> = (test, &params).into();
-    let expected = JsonApiContainer { data: vec![data] };
+    let expected = JsonApiContainer::new(vec![data.with_self_link("http://localhost:3000")])
+        .with_self_link("http://localhost:3000/foos".to_string());
 
     assert_eq!(expected, records);
 }
@@ -83,12 +99,77 @@ fn parse_json_api_index_get_with_fieldset() {
     let data = JsonApiData::new(
         Some("1"),
         <Foo as ToJson>::Attrs::new(Some("test".to_string()), None, None)
-    );
-    let expected = JsonApiContainer { data: vec![data] };
+    ).with_self_link("http://localhost:3000");
+    let expected = JsonApiContainer::new(vec![data])
+        .with_self_link("http://localhost:3000/foos?fields[foos]=title".to_string());
 
     assert_eq!(expected, records);
 }
 
+#[test]
+fn parse_json_api_index_get_with_page() {
+    let response = request::get(
+        "http://localhost:3000/foos?page[number]=2&page[size]=10",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+    let result = response::extract_body_to_string(response);
+    let records: JsonApiContainer<Vec<JsonApiData<Foo>>> = serde_json::from_str(&result).unwrap();
+    let links = records.links.expect("expected pagination links");
+
+    let url = |n| {
+        format!(
+            "http://localhost:3000/foos?page%5Bnumber%5D={}&page%5Bsize%5D=10",
+            n
+        )
+    };
+    assert_eq!(Some(url(1)), links.first);
+    assert_eq!(Some(url(1)), links.prev);
+    assert_eq!(Some(url(3)), links.next);
+    assert_eq!(Some(url(3)), links.last);
+    assert_eq!(Some(&serde_json::Value::from(25)), records.meta.get("total"));
+}
+
+#[test]
+fn parse_json_api_index_get_with_cursor() {
+    let response = request::get(
+        "http://localhost:3000/foos?sort=title&page[size]=1&page[after]=5b5d",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+    let result = response::extract_body_to_string(response);
+    let records: JsonApiContainer<Vec<JsonApiData<Foo>>> = serde_json::from_str(&result).unwrap();
+    let links = records.links.expect("expected pagination links");
+
+    let foo = Foo::new("1", "test", "test", true);
+    let cursor = Cursor::new(&foo, &[foo::sort::title(SortOrder::Asc)]).encode();
+    let url = |key, cursor| {
+        format!(
+            "http://localhost:3000/foos?sort=title&{}={}&page%5Bsize%5D=1",
+            key,
+            cursor
+        )
+    };
+
+    assert_eq!(
+        Some("http://localhost:3000/foos?sort=title&page%5Bsize%5D=1".to_string()),
+        links.first
+    );
+    assert_eq!(Some(url("page%5Bbefore%5D", &cursor)), links.prev);
+    assert_eq!(Some(url("page%5Bafter%5D", &cursor)), links.next);
+    assert_eq!(None, links.last);
+}
+
+#[test]
+fn parse_json_api_index_get_without_page() {
+    let response = request::get("http://localhost:3000/foos", Headers::new(), &app_router());
+    let result = response::extract_body_to_string(response.unwrap());
+    let records: JsonApiContainer<Vec<JsonApiData<Foo>>> = serde_json::from_str(&result).unwrap();
+
+    let expected = JsonApiLinks::with_self("http://localhost:3000/foos".to_string());
+    assert_eq!(Some(expected), records.links);
+    assert!(!result.contains("meta"));
+}
 
 #[test]
 fn parse_json_api_single_get() {
@@ -108,11 +189,188 @@ fn parse_json_api_single_get() {
         published: true
     };
     let data: JsonApiData<Foo> = (test, &params).into();
-    let expected = JsonApiContainer { data: data };
+    let expected = JsonApiContainer::new(data.with_self_link("http://localhost:3000"))
+        .with_self_link("http://localhost:3000/foos/1".to_string());
 
     assert_eq!(expected, record);
 }
 
+#[test]
+fn parse_json_api_single_get_with_base_url() {
+    let mut router = JsonApiRouterBuilder::default();
+    router.jsonapi_get::<Foo>();
+    router.set_base_url("https://example.com/api/");
+
+    let response = request::get(
+        "http://localhost:3000/foos/1?fields[foos]=title",
+        Headers::new(),
+        &router.build()
+    );
+    let result = response::extract_body_to_string(response.unwrap());
+    let record: JsonApiContainer<JsonApiData<Foo>> = serde_json::from_str(&result).unwrap();
+
+    assert_eq!(
+        Some(JsonApiLinks::with_self("https://example.com/api/foos/1".to_string())),
+        record.data.links
+    );
+    assert_eq!(
+        Some(JsonApiLinks::with_self(
+            "https://example.com/api/foos/1?fields[foos]=title".to_string()
+        )),
+        record.links
+    );
+}
+
+#[test]
+fn parse_json_api_single_get_missing() {
+    let response = request::get(
+        "http://localhost:3000/foos/missing",
+        Headers::new(),
+        &app_router()
+    );
+
+    assert_json_api_error(
+        response,
+        JsonApiError {
+            title: "Not found".to_string(),
+            detail: "Not found".to_string(),
+            status: "404".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn parse_json_api_post_created() {
+    let response = post_foo("new").unwrap();
+
+    assert_eq!(Some(Status::Created), response.status);
+    assert_eq!(
+        Some(&Location("http://localhost:3000/foos/1".to_string())),
+        response.headers.get::<Location>()
+    );
+}
+
+#[test]
+fn parse_json_api_post_unchanged() {
+    let response = post_foo("unchanged").unwrap();
+
+    assert_eq!(Some(Status::NoContent), response.status);
+    assert_eq!("", response::extract_body_to_string(response));
+}
+
+#[test]
+fn post_with_json_content_type() {
+    let response = post_with_content_type("application/json");
+
+    assert_json_api_error(
+        response,
+        JsonApiError {
+            title: "Unsupported media type".to_string(),
+            detail: "The request body must have the JSONAPI media type without parameters"
+                .to_string(),
+            status: "415".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn post_with_json_api_content_type_parameters() {
+    let response = post_with_content_type("application/vnd.api+json; version=1").unwrap();
+
+    assert_eq!(Some(Status::UnsupportedMediaType), response.status);
+}
+
+#[test]
+fn get_with_json_api_accept_parameters() {
+    let response = get_with_accept("application/vnd.api+json; version=1").unwrap();
+
+    assert_eq!(Some(Status::NotAcceptable), response.status);
+}
+
+#[test]
+fn get_with_json_api_accept_with_and_without_parameters() {
+    let accept = "application/vnd.api+json; version=1, application/vnd.api+json";
+    let response = get_with_accept(accept).unwrap();
+
+    assert_eq!(Some(Status::Ok), response.status);
+}
+
+#[test]
+fn get_with_other_accept() {
+    let response = get_with_accept("application/json; charset=utf-8").unwrap();
+
+    assert_eq!(Some(Status::Ok), response.status);
+}
+
+#[test]
+fn post_with_supported_extension() {
+    let content_type = format!(r#"application/vnd.api+json; ext="{}""#, ATOMIC);
+    let mut headers = Headers::new();
+    headers.set_raw("Content-Type", vec![content_type.clone().into_bytes()]);
+
+    let data = r#"{"data": {"type": "foos", "attributes": {"title": "test"}}}"#;
+    let response = request::post(
+        "http://localhost:3000/foos",
+        headers,
+        data,
+        &extension_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::Created), response.status);
+    assert_eq!(
+        Some(&[content_type.into_bytes()][..]),
+        response.headers.get_raw("Content-Type")
+    );
+}
+
+#[test]
+fn post_with_unsupported_extension() {
+    let mut headers = Headers::new();
+    let content_type = r#"application/vnd.api+json; ext="https://example.com/ext""#;
+    headers.set_raw("Content-Type", vec![content_type.as_bytes().to_vec()]);
+
+    let data = r#"{"data": {"type": "foos", "attributes": {"title": "test"}}}"#;
+    let response = request::post(
+        "http://localhost:3000/foos",
+        headers,
+        data,
+        &extension_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::UnsupportedMediaType), response.status);
+}
+
+#[test]
+fn get_with_unsupported_extension_in_accept() {
+    let mut headers = Headers::new();
+    let accept = r#"application/vnd.api+json; ext="https://example.com/ext"; q=0.9"#;
+    headers.set_raw("Accept", vec![accept.as_bytes().to_vec()]);
+
+    let response = request::get("http://localhost:3000/foos/1", headers, &extension_router());
+
+    assert_eq!(Some(Status::NotAcceptable), response.unwrap().status);
+}
+
+#[test]
+fn get_with_profiles_in_accept() {
+    let mut headers = Headers::new();
+    let accept = format!(
+        r#"application/vnd.api+json; profile="https://example.com/profile {}""#,
+        CURSOR_PAGINATION
+    );
+    headers.set_raw("Accept", vec![accept.into_bytes()]);
+
+    let response = request::get("http://localhost:3000/foos/1", headers, &extension_router());
+    let content_type = format!(r#"application/vnd.api+json; profile="{}""#, CURSOR_PAGINATION);
+
+    assert_eq!(
+        Some(&[content_type.into_bytes()][..]),
+        response.unwrap().headers.get_raw("Content-Type")
+    );
+}
+
 #[test]
 fn parse_json_api_single_get_fail_in_from_request() {
     let mut headers = Headers::new();
@@ -125,7 +383,8 @@ fn parse_json_api_single_get_fail_in_from_request() {
         JsonApiError {
             title: "from request fail".to_string(),
             detail: "from request fail".to_string(),
-            status: "500".to_string()
+            status: "500".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -142,7 +401,8 @@ fn parse_json_api_index_get_fail_in_from_request() {
         JsonApiError {
             title: "from request fail".to_string(),
             detail: "from request fail".to_string(),
-            status: "500".to_string()
+            status: "500".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -159,7 +419,8 @@ fn parse_json_api_delete_fail_in_from_request() {
         JsonApiError {
             title: "from request fail".to_string(),
             detail: "from request fail".to_string(),
-            status: "500".to_string()
+            status: "500".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -190,7 +451,8 @@ fn parse_json_api_post_fail_in_from_request() {
         JsonApiError {
             title: "from request fail".to_string(),
             detail: "from request fail".to_string(),
-            status: "500".to_string()
+            status: "500".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -226,7 +488,8 @@ fn parse_json_api_patch_fail_in_from_request() {
         JsonApiError {
             title: "from request fail".to_string(),
             detail: "from request fail".to_string(),
-            status: "500".to_string()
+            status: "500".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -244,7 +507,8 @@ fn parse_json_api_custom_failure_in_get() {
         JsonApiError {
             title: "fail in get".to_string(),
             detail: "fail in get".to_string(),
-            status: "418".to_string()
+            status: "418".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -262,7 +526,8 @@ fn parse_json_api_custom_failure_in_index() {
         JsonApiError {
             title: "fail in index".to_string(),
             detail: "fail in index".to_string(),
-            status: "418".to_string()
+            status: "418".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -280,7 +545,8 @@ fn parse_json_api_custom_failure_in_delete() {
         JsonApiError {
             title: "fail in delete".to_string(),
             detail: "fail in delete".to_string(),
-            status: "418".to_string()
+            status: "418".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -310,11 +576,40 @@ fn parse_json_api_custom_failure_in_post() {
         JsonApiError {
             title: "fail in post".to_string(),
             detail: "fail in post".to_string(),
-            status: "418".to_string()
+            status: "418".to_string(),
+            ..Default::default()
         }
     );
 }
 
+#[test]
+fn parse_json_api_multiple_failures_in_post() {
+    let response = post_foo("invalid").unwrap();
+
+    assert_eq!(Some(Status::BadRequest), response.status);
+
+    let result = response::extract_body_to_string(response);
+    let errors: JsonApiErrorArray = serde_json::from_str(&result).unwrap();
+    let expected = vec![
+        JsonApiError {
+            title: "Invalid attribute".to_string(),
+            detail: "Invalid value for title".to_string(),
+            status: "422".to_string(),
+            source: Some(JsonApiErrorSource::pointer("/data/attributes/title")),
+            ..Default::default()
+        },
+        JsonApiError {
+            title: "Invalid attribute".to_string(),
+            detail: "Invalid value for body".to_string(),
+            status: "409".to_string(),
+            source: Some(JsonApiErrorSource::pointer("/data/attributes/body")),
+            ..Default::default()
+        }
+    ];
+
+    assert_eq!(expected, errors.errors);
+}
+
 #[test]
 fn parse_json_api_custom_failure_in_patch() {
     let content_type: Mime = "application/vnd.api+json".parse().unwrap();
@@ -345,7 +640,8 @@ fn parse_json_api_custom_failure_in_patch() {
         JsonApiError {
             title: "fail in patch".to_string(),
             detail: "fail in patch".to_string(),
-            status: "418".to_string()
+            status: "418".to_string(),
+            ..Default::default()
         }
     )
 }
@@ -363,7 +659,9 @@ fn parse_json_api_failure_in_query_parse_in_get() {
         JsonApiError {
             title: "fail".to_string(),
             detail: "Query string parse error: Invalid value: fail".to_string(),
-            status: "400".to_string()
+            status: "400".to_string(),
+            source: Some(JsonApiErrorSource::parameter("sort")),
+            ..Default::default()
         }
     );
 }
@@ -381,7 +679,9 @@ fn parse_json_api_failure_in_query_parse_in_index() {
         JsonApiError {
             title: "fail".to_string(),
             detail: "Query string parse error: Invalid value: fail".to_string(),
-            status: "400".to_string()
+            status: "400".to_string(),
+            source: Some(JsonApiErrorSource::parameter("sort")),
+            ..Default::default()
         }
     );
 }
@@ -400,7 +700,8 @@ fn parse_no_json_in_post() {
         JsonApiError {
             detail: "No body".to_string(),
             status: "400".to_string(),
-            title: "No body".to_string()
+            title: "No body".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -419,7 +720,8 @@ fn parse_no_json_in_patch() {
         JsonApiError {
             detail: "No body".to_string(),
             status: "400".to_string(),
-            title: "No body".to_string()
+            title: "No body".to_string(),
+            ..Default::default()
         }
     );
 }
@@ -448,7 +750,9 @@ fn parse_invalid_json_in_post() {
         JsonApiError {
             title: "Can't parse body to the struct".to_string(),
             detail: "Can't parse body to the struct".to_string(),
-            status: "400".to_string()
+            status: "400".to_string(),
+            source: Some(JsonApiErrorSource::pointer("/data")),
+            ..Default::default()
         }
     );
 }
@@ -477,11 +781,283 @@ fn parse_invalid_json_in_patch() {
         JsonApiError {
             title: "Can't parse body to the struct".to_string(),
             detail: "Can't parse body to the struct".to_string(),
-            status: "400".to_string()
+            status: "400".to_string(),
+            source: Some(JsonApiErrorSource::pointer("/data")),
+            ..Default::default()
+        }
+    );
+}
+
+#[test]
+fn get_to_one_relationship() {
+    let response = request::get(
+        "http://localhost:3000/bars/1/relationships/foo",
+        Headers::new(),
+        &app_router()
+    );
+    let result = response::extract_body_to_string(response.unwrap());
+    let linkage: JsonApiContainer<JsonApiLinkage> = serde_json::from_str(&result).unwrap();
+
+    let foo = JsonApiResourceIdentifier::new("foos", "1");
+    let expected = JsonApiContainer::new(JsonApiLinkage::ToOne(Some(foo)))
+        .with_self_link("http://localhost:3000/bars/1/relationships/foo".to_string());
+
+    assert_eq!(expected, linkage);
+}
+
+#[test]
+fn get_unknown_relationship() {
+    let response = request::get(
+        "http://localhost:3000/bars/1/relationships/baz",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::NotFound), response.status);
+}
+
+#[test]
+fn get_relationship_of_missing_resource() {
+    let response = request::get(
+        "http://localhost:3000/bars/missing/relationships/foos",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::NotFound), response.status);
+}
+
+#[test]
+fn patch_to_one_relationship() {
+    let response = relationship_request(Method::Patch, "foo", r#"{"data": null}"#).unwrap();
+    let result = response::extract_body_to_string(response);
+    let linkage: JsonApiContainer<JsonApiLinkage> = serde_json::from_str(&result).unwrap();
+
+    assert_eq!(JsonApiLinkage::ToOne(None), linkage.data);
+}
+
+#[test]
+fn patch_to_many_relationship_with_to_one_linkage() {
+    let data = r#"{"data": {"type": "foos", "id": "3"}}"#;
+    let response = relationship_request(Method::Patch, "foos", data).unwrap();
+
+    assert_eq!(Some(Status::BadRequest), response.status);
+}
+
+#[test]
+fn post_to_many_relationship() {
+    let data = r#"{"data": [{"type": "foos", "id": "2"}, {"type": "foos", "id": "3"}]}"#;
+    let response = relationship_request(Method::Post, "foos", data).unwrap();
+    let result = response::extract_body_to_string(response);
+    let linkage: JsonApiContainer<JsonApiLinkage> = serde_json::from_str(&result).unwrap();
+
+    let foos = vec!["1", "2", "3"]
+        .into_iter()
+        .map(|id| JsonApiResourceIdentifier::new("foos", id))
+        .collect();
+    assert_eq!(JsonApiLinkage::ToMany(foos), linkage.data);
+}
+
+#[test]
+fn post_to_one_relationship() {
+    let data = r#"{"data": [{"type": "foos", "id": "2"}]}"#;
+    let response = relationship_request(Method::Post, "foo", data).unwrap();
+
+    assert_eq!(Some(Status::Forbidden), response.status);
+}
+
+#[test]
+fn post_to_many_relationship_with_wrong_type() {
+    let data = r#"{"data": [{"type": "bars", "id": "2"}]}"#;
+    let response = relationship_request(Method::Post, "foos", data).unwrap();
+
+    assert_eq!(Some(Status::Conflict), response.status);
+}
+
+#[test]
+fn delete_from_to_many_relationship() {
+    let data = r#"{"data": [{"type": "foos", "id": "1"}]}"#;
+    let response = relationship_request(Method::Delete, "foos", data).unwrap();
+    let result = response::extract_body_to_string(response);
+    let linkage: JsonApiContainer<JsonApiLinkage> = serde_json::from_str(&result).unwrap();
+
+    let foos = vec![JsonApiResourceIdentifier::new("foos", "2")];
+    assert_eq!(JsonApiLinkage::ToMany(foos), linkage.data);
+}
+
+#[test]
+fn get_to_one_related_resource() {
+    let response = request::get("http://localhost:3000/bars/1/foo", Headers::new(), &app_router());
+    let result = response::extract_body_to_string(response.unwrap());
+    let record: JsonApiContainer<JsonApiData<Foo>> = serde_json::from_str(&result).unwrap();
+    let params = <Foo as JsonApiResource>::Params::from_str("").expect("failed to unwrap params");
+
+    let data = Foo::new("1", "test", "test", true)
+        .into_json(&params)
+        .with_self_link("http://localhost:3000");
+    let expected = JsonApiContainer::new(data)
+        .with_self_link("http://localhost:3000/bars/1/foo".to_string());
+
+    assert_eq!(expected, record);
+}
+
+#[test]
+fn get_to_many_related_resources_with_fields() {
+    let response = request::get(
+        "http://localhost:3000/bars/1/foos?fields[foos]=title",
+        Headers::new(),
+        &app_router()
+    );
+    let result = response::extract_body_to_string(response.unwrap());
+    let records: JsonApiContainer<Vec<JsonApiData<Foo>>> = serde_json::from_str(&result).unwrap();
+
+    let data = |id, title: &str| {
+        JsonApiData::new(
+            Some(id),
+            <Foo as ToJson>::Attrs::new(Some(title.to_string()), None, None)
+        ).with_self_link("http://localhost:3000")
+    };
+    let expected = JsonApiContainer::new(vec![data("1", "test"), data("2", "test2")])
+        .with_self_link("http://localhost:3000/bars/1/foos?fields[foos]=title".to_string());
+
+    assert_eq!(expected, records);
+}
+
+#[test]
+fn get_to_many_related_resources_with_page() {
+    let response = request::get(
+        "http://localhost:3000/bars/1/foos?page[number]=2&page[size]=10",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+    let result = response::extract_body_to_string(response);
+    let records: JsonApiContainer<Vec<JsonApiData<Foo>>> = serde_json::from_str(&result).unwrap();
+    let links = records.links.expect("expected pagination links");
+
+    let url = |n| {
+        format!(
+            "http://localhost:3000/bars/1/foos?page%5Bnumber%5D={}&page%5Bsize%5D=10",
+            n
+        )
+    };
+    assert_eq!(Some(url(1)), links.first);
+    assert_eq!(Some(url(3)), links.next);
+    assert_eq!(Some(&serde_json::Value::from(25)), records.meta.get("total"));
+}
+
+#[test]
+fn get_related_resource_of_missing_resource() {
+    let response = request::get(
+        "http://localhost:3000/bars/missing/foo",
+        Headers::new(),
+        &app_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::NotFound), response.status);
+}
+
+fn post_with_content_type(content_type: &str) -> Result<Response, IronError> {
+    let mut headers = Headers::new();
+    headers.set_raw("Content-Type", vec![content_type.as_bytes().to_vec()]);
+
+    let data = r#"{"data": {"type": "foos", "attributes": {"title": "test"}}}"#;
+    request::post("http://localhost:3000/foos", headers, data, &app_router())
+}
+
+fn get_with_accept(accept: &str) -> Result<Response, IronError> {
+    let mut headers = Headers::new();
+    headers.set_raw("Accept", vec![accept.as_bytes().to_vec()]);
+
+    request::get("http://localhost:3000/foos/1", headers, &app_router())
+}
+
+#[test]
+fn get_with_percent_encoded_id() {
+    let response = request::get(
+        "http://localhost:3000/foos/f%61il",
+        Headers::new(),
+        &app_router()
+    );
+
+    assert_json_api_error(
+        response,
+        JsonApiError {
+            title: "fail in get".to_string(),
+            detail: "fail in get".to_string(),
+            status: "418".to_string(),
+            ..Default::default()
         }
     );
 }
 
+#[test]
+fn method_not_allowed() {
+    let response = request::put(
+        "http://localhost:3000/foos/1",
+        Headers::new(),
+        "",
+        &app_router()
+    ).unwrap();
+
+    assert_eq!(Some(Status::MethodNotAllowed), response.status);
+    assert_eq!(
+        Some(&vec![b"GET, DELETE, PATCH".to_vec()]),
+        response.headers.get_raw("Allow")
+    );
+}
+
+#[test]
+fn body_too_large() {
+    let content_type: Mime = "application/vnd.api+json".parse().unwrap();
+
+    let mut headers = Headers::new();
+    headers.set::<ContentType>(ContentType(content_type));
+
+    let mut router = JsonApiRouterBuilder::default();
+    router.jsonapi_post::<Foo>();
+    router.set_max_body_length(10);
+
+    let chain = router.build();
+    let response = request::post("http://localhost:3000/foos", headers, NEW_FOO, &chain);
+
+    assert_json_api_error(
+        response,
+        JsonApiError {
+            title: "Body too large".to_string(),
+            detail: "The request body is larger than the maximum body length".to_string(),
+            status: "413".to_string(),
+            ..Default::default()
+        }
+    );
+}
+
+fn post_foo(id: &str) -> Result<Response, IronError> {
+    let content_type: Mime = "application/vnd.api+json".parse().unwrap();
+
+    let mut headers = Headers::new();
+    headers.set::<ContentType>(ContentType(content_type));
+
+    let data = format!(
+        r#"{{"data": {{"id": "{}", "type": "foos", "attributes": {{"title": "test"}}}}}}"#,
+        id
+    );
+    request::post("http://localhost:3000/foos", headers, &data, &app_router())
+}
+
+fn relationship_request(
+    method: Method,
+    relationship: &str,
+    data: &str
+) -> Result<Response, IronError> {
+    let content_type: Mime = "application/vnd.api+json".parse().unwrap();
+
+    let mut headers = Headers::new();
+    headers.set::<ContentType>(ContentType(content_type));
+
+    let url = format!("http://localhost:3000/bars/1/relationships/{}", relationship);
+    request::request(method, &url, data, headers, &app_router())
+}
+
 fn assert_json_api_error(response: Result<Response, IronError>, error: JsonApiError) {
     let json = response::extract_body_to_string(response.unwrap());
     let result: JsonApiErrorArray = serde_json::from_str(&json).unwrap();
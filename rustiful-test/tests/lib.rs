@@ -20,7 +20,14 @@ extern crate diesel_codegen;
 #[macro_use]
 extern crate lazy_static;
 
+#[macro_use]
+mod adapter;
 mod iron;
+mod hyper;
+mod tower;
+mod rocket;
+mod async_dispatch_tests;
 mod conversion_tests;
+mod dispatch_tests;
 mod params_tests;
 mod resources;
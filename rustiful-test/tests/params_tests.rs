@@ -1,7 +1,18 @@
 use resources::simple_resources::*;
+use rustiful::Cursor;
+use rustiful::CursorDirection;
+use rustiful::CursorKey;
+use rustiful::CursorValue;
+use rustiful::FilterOperator;
+use rustiful::FilterValue::*;
+use rustiful::JsonApiErrorDetails;
+use rustiful::JsonApiErrorSource;
+use rustiful::JsonApiRelationshipField;
 use rustiful::JsonApiResource;
+use rustiful::Page;
 use rustiful::QueryStringParseError;
 use rustiful::SortOrder::*;
+use std::cmp::Ordering;
 use std::str::FromStr;
 
 #[test]
@@ -17,7 +28,12 @@ fn parse_renamed_json_struct() {
 fn parse_renamed_json_struct_fails_on_original_name() {
     match <Bar as JsonApiResource>::Params::from_str("fields[bar]=bar") {
         Ok(_) => assert!(false, "expected error but no error happened!"),
-        Err(e) => assert_eq!(QueryStringParseError::UnImplementedError, e)
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFieldsetType("bar".to_string()),
+                e
+            )
+        }
     }
 }
 
@@ -64,7 +80,7 @@ fn parse_field_that_is_not_present() {
 fn parse_fields_fails_if_query_param_is_not_valid() {
     match <Foo as JsonApiResource>::Params::from_str("fields=body=foo") {
         Ok(_) => assert!(false, "expected error but no error happened!"),
-        Err(e) => assert_eq!(QueryStringParseError::InvalidFieldsetKey("".to_string()), e)
+        Err(e) => assert_eq!(QueryStringParseError::InvalidFieldsetKey("fields".to_string()), e)
     }
 }
 
@@ -95,15 +111,16 @@ fn parse_fields_fails_if_field_value_contains_field_that_does_not_exist() {
 }
 
 #[test]
-fn parse_single_field_fails_if_field_doesnt_contain_left_bracket() {
+fn parse_key_that_starts_with_fields_without_left_bracket_as_query_param() {
     match <Foo as JsonApiResource>::Params::from_str("fieldsarticles]=title") {
-        Ok(_) => assert!(false, "expected error but no error happened!"),
-        Err(e) => {
+        Ok(result) => {
+            assert_eq!(true, result.fieldset.fields.is_empty());
             assert_eq!(
-                QueryStringParseError::InvalidFieldsetKey("articles]".to_string()),
-                e
-            )
+                Some(&vec!["title".to_string()]),
+                result.query_params.get("fieldsarticles]")
+            );
         }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
     }
 }
 
@@ -113,7 +130,7 @@ fn parse_single_field_fails_if_field_does_not_contain_right_bracket() {
         Ok(_) => assert!(false, "expected error but no error happened!"),
         Err(e) => {
             assert_eq!(
-                QueryStringParseError::InvalidFieldsetKey("[articles".to_string()),
+                QueryStringParseError::InvalidFieldsetKey("fields[articles".to_string()),
                 e
             )
         }
@@ -218,3 +235,655 @@ fn parse_sort_field_fails_on_non_existent_param() {
         }
     }
 }
+
+#[test]
+fn parse_include() {
+    use self::article::include::*;
+    match <Article as JsonApiResource>::Params::from_str("include=author,tags") {
+        Ok(result) => assert_eq!(vec![author(None), tags(None)], result.include.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_nested_include() {
+    match <Article as JsonApiResource>::Params::from_str("include=author.articles.tags") {
+        Ok(result) => {
+            let tags = article::include::tags(None);
+            let articles = author::include::articles(Some(Box::new(tags)));
+            let expected = vec![article::include::author(Some(Box::new(articles)))];
+            assert_eq!(expected, result.include.fields)
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_include_fails_on_attribute() {
+    match <Article as JsonApiResource>::Params::from_str("include=title") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidIncludeValue("title".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_include_fails_on_invalid_nested_path() {
+    match <Article as JsonApiResource>::Params::from_str("include=author.tags") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidIncludeValue("author.tags".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_include_fails_on_multiple_include_params() {
+    match <Article as JsonApiResource>::Params::from_str("include=author&include=tags") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::DuplicateIncludeKey("tags".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_filter() {
+    use self::post::filter::*;
+    match <Post as JsonApiResource>::Params::from_str("filter[published]=true") {
+        Ok(result) => assert_eq!(vec![published(Eq(true))], result.filter.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_multiple_filters() {
+    use self::post::filter::*;
+    match <Post as JsonApiResource>::Params::from_str("filter[title]=foo&filter[rating]=5") {
+        Ok(result) => {
+            assert_eq!(
+                vec![title(Eq("foo".to_string())), rating(Eq(5))],
+                result.filter.fields
+            )
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_url_encoded_filter() {
+    use self::post::filter::*;
+    match <Post as JsonApiResource>::Params::from_str("filter%5Btitle%5D=foo%20bar") {
+        Ok(result) => assert_eq!(vec![title(Eq("foo bar".to_string()))], result.filter.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_invalid_value() {
+    match <Post as JsonApiResource>::Params::from_str("filter[published]=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFilterValue("published".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_non_existent_field() {
+    match <Post as JsonApiResource>::Params::from_str("filter[body]=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidFilterKey("body".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_list_field() {
+    match <Post as JsonApiResource>::Params::from_str("filter[keywords]=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFilterKey("keywords".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_float_filter() {
+    use self::measurement::filter::*;
+    match <Measurement as JsonApiResource>::Params::from_str("filter[value][gt]=1.5") {
+        Ok(result) => assert_eq!(vec![value(Gt(1.5))], result.filter.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_field_that_is_not_filterable() {
+    match <Measurement as JsonApiResource>::Params::from_str("filter[unit]=Celsius") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidFilterKey("unit".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_filter_on_annotated_field() {
+    use self::measurement::filter::*;
+    match <Measurement as JsonApiResource>::Params::from_str("filter[station]=north") {
+        Ok(result) => {
+            assert_eq!(
+                vec![station(Eq(Station("north".to_string())))],
+                result.filter.fields
+            )
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_filter_fails_if_filter_key_is_not_valid() {
+    match <Post as JsonApiResource>::Params::from_str("filter=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidFilterKey("filter".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_filter_with_operator() {
+    use self::post::filter::*;
+    let query_string = "filter[rating][gt]=3&filter[rating][lt]=5&filter[title][like]=foo";
+    match <Post as JsonApiResource>::Params::from_str(query_string) {
+        Ok(result) => {
+            assert_eq!(
+                vec![rating(Gt(3)), rating(Lt(5)), title(Like("foo".to_string()))],
+                result.filter.fields
+            )
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_in_filter() {
+    use self::post::filter::*;
+    match <Post as JsonApiResource>::Params::from_str("filter[rating][in]=1,2,3") {
+        Ok(result) => assert_eq!(vec![rating(In(vec![1, 2, 3]))], result.filter.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_unsupported_operator() {
+    match <Post as JsonApiResource>::Params::from_str("filter[published][lt]=true") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFilterOperator(
+                    "published".to_string(),
+                    "lt".to_string()
+                ),
+                e
+            );
+            assert_eq!(
+                Some(JsonApiErrorSource::parameter("filter[published][lt]")),
+                e.error_source()
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_like_operator_on_number() {
+    match <Post as JsonApiResource>::Params::from_str("filter[rating][like]=1") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFilterOperator(
+                    "rating".to_string(),
+                    "like".to_string()
+                ),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_filter_fails_on_too_many_operators() {
+    match <Post as JsonApiResource>::Params::from_str("filter[rating][gt][lt]=1") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFilterKey("filter[rating][gt][lt]".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_page_number() {
+    match <Foo as JsonApiResource>::Params::from_str("page[number]=2&page[size]=10") {
+        Ok(result) => {
+            let expected = Page::Number {
+                number: 2,
+                size: Some(10)
+            };
+            assert_eq!(Some(expected), result.page)
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_page_offset() {
+    match <Foo as JsonApiResource>::Params::from_str("page[offset]=20") {
+        Ok(result) => {
+            let expected = Page::Offset {
+                offset: 20,
+                limit: None
+            };
+            assert_eq!(Some(expected), result.page)
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_without_page() {
+    match <Foo as JsonApiResource>::Params::from_str("sort=foo") {
+        Ok(result) => assert_eq!(None, result.page),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_page_fails_on_mixed_strategies() {
+    match <Foo as JsonApiResource>::Params::from_str("page[number]=2&page[limit]=10") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidPageKey("page[limit]".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_page_fails_on_unknown_key() {
+    match <Foo as JsonApiResource>::Params::from_str("page[foo]=2") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidPageKey("page[foo]".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_key_that_starts_with_page_as_query_param() {
+    match <Foo as JsonApiResource>::Params::from_str("pageSize=2&filters=a") {
+        Ok(result) => {
+            assert_eq!(None, result.page);
+            assert_eq!(true, result.filter.fields.is_empty());
+            assert_eq!(Some(&vec!["2".to_string()]), result.query_params.get("pageSize"));
+            assert_eq!(Some(&vec!["a".to_string()]), result.query_params.get("filters"));
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_page_fails_on_invalid_value() {
+    match <Foo as JsonApiResource>::Params::from_str("page[size]=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidPageValue("page[size]".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_page_fails_on_zero_page_number() {
+    match <Foo as JsonApiResource>::Params::from_str("page[number]=0") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidPageValue("page[number]".to_string()),
+                e
+            )
+        }
+    }
+}
+
+fn post(id: i32, title: &str, rating: Option<i32>) -> Post {
+    Post {
+        id: id,
+        title: title.to_string(),
+        published: true,
+        rating: rating,
+        keywords: vec![]
+    }
+}
+
+fn sample_post() -> Post {
+    post(1, "foo", Some(5))
+}
+
+type Key = CursorKey<post::sort>;
+
+fn cursor_key(field: Option<post::sort>, value: CursorValue, operator: FilterOperator) -> Key {
+    CursorKey {
+        field: field,
+        value: value,
+        operator: operator
+    }
+}
+
+/// Compares `post` to the value of `key`, ordering `None` first like `Option` does.
+fn compare(post: &Post, key: &Key) -> Ordering {
+    match key.field {
+        Some(post::sort::title(_)) => post.title.cmp(&Cursor::from_value(&key.value).unwrap()),
+        Some(post::sort::rating(_)) => post.rating.cmp(&Cursor::from_value(&key.value).unwrap()),
+        Some(_) => unimplemented!(),
+        None => post.id.cmp(&key.value.as_str().unwrap().parse().unwrap())
+    }
+}
+
+/// Selects the ids of the posts after `cursor` with its keyset predicate, like a service would.
+fn ids_after(posts: &[Post], cursor: &Cursor, sort: &[post::sort]) -> Vec<i32> {
+    let keys = cursor.keys::<Post, _>(sort, CursorDirection::After).unwrap();
+    let selects = |post: &Post, key: &Key| match key.operator {
+        FilterOperator::Gt => compare(post, key) == Ordering::Greater,
+        _ => compare(post, key) == Ordering::Less
+    };
+
+    posts
+        .iter()
+        .filter(|post| {
+            (0..keys.len()).any(|i| {
+                keys[..i].iter().all(|k| compare(post, k) == Ordering::Equal) &&
+                    selects(post, &keys[i])
+            })
+        })
+        .map(|post| post.id)
+        .collect()
+}
+
+#[test]
+fn parse_page_cursor() {
+    let sort = vec![post::sort::title(Asc), post::sort::rating(Desc)];
+    let cursor = Cursor::new(&sample_post(), &sort);
+    let query_string = format!("sort=title,-rating&page[after]={}&page[size]=5", cursor.encode());
+
+    match <Post as JsonApiResource>::Params::from_str(&query_string) {
+        Ok(result) => {
+            let expected = Page::Cursor {
+                after: Some(cursor.clone()),
+                before: None,
+                size: Some(5)
+            };
+            assert_eq!(Some(expected), result.page);
+
+            let keys = cursor.keys::<Post, _>(&result.sort.fields, CursorDirection::After);
+            let expected = vec![
+                cursor_key(Some(sort[0].clone()), CursorValue::from("foo"), FilterOperator::Gt),
+                cursor_key(Some(sort[1].clone()), CursorValue::from(5), FilterOperator::Lt),
+                cursor_key(None, CursorValue::from("1"), FilterOperator::Gt)
+            ];
+            assert_eq!(Ok(expected), keys);
+            assert_eq!(Ok(1), cursor.id::<Post>(CursorDirection::After));
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_page_cursor_keys_before() {
+    let sort = vec![post::sort::title(Asc)];
+    let cursor = Cursor::new(&sample_post(), &sort);
+    let expected = vec![
+        cursor_key(Some(sort[0].clone()), CursorValue::from("foo"), FilterOperator::Lt),
+        cursor_key(None, CursorValue::from("1"), FilterOperator::Lt)
+    ];
+
+    assert_eq!(Ok(expected), cursor.keys::<Post, _>(&sort, CursorDirection::Before));
+}
+
+#[test]
+fn parse_page_cursor_keys_fail_on_different_sort() {
+    let cursor = Cursor::new(&sample_post(), &[post::sort::title(Asc)]);
+    let sort = vec![post::sort::title(Asc), post::sort::rating(Asc)];
+
+    assert_eq!(
+        Err(QueryStringParseError::InvalidPageValue("page[after]".to_string())),
+        cursor.keys::<Post, _>(&sort, CursorDirection::After)
+    );
+
+    let sort = vec![post::sort::rating(Asc)];
+    assert_eq!(
+        Err(QueryStringParseError::InvalidPageValue("page[after]".to_string())),
+        cursor.keys::<Post, _>(&sort, CursorDirection::After)
+    );
+}
+
+#[test]
+fn parse_page_cursor_keys_with_null_value() {
+    let sort = vec![post::sort::rating(Asc)];
+    let cursor = Cursor::new(&post(1, "foo", None), &sort);
+    let keys = cursor.keys::<Post, _>(&sort, CursorDirection::After).unwrap();
+
+    assert_eq!(CursorValue::Null, keys[0].value);
+    assert_eq!(Some(None), Cursor::from_value::<Option<i32>>(&keys[0].value));
+}
+
+#[test]
+fn parse_page_cursor_keys_with_duplicate_sort_values() {
+    let sort = vec![post::sort::title(Asc), post::sort::rating(Asc)];
+    let posts = vec![
+        post(1, "a", Some(1)),
+        post(2, "b", None),
+        post(3, "b", Some(2)),
+        post(4, "b", Some(2)),
+        post(5, "c", Some(1))
+    ];
+
+    let after = |i: usize| ids_after(&posts, &Cursor::new(&posts[i], &sort), &sort);
+    assert_eq!(vec![2, 3, 4, 5], after(0));
+    assert_eq!(vec![3, 4, 5], after(1));
+    assert_eq!(vec![4, 5], after(2));
+    assert_eq!(vec![5], after(3));
+    assert_eq!(Vec::<i32>::new(), after(4));
+}
+
+#[test]
+fn parse_page_cursor_fails_on_invalid_cursor() {
+    match <Post as JsonApiResource>::Params::from_str("page[after]=abc") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidPageValue("page[after]".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_page_cursor_fails_on_mixed_strategies() {
+    let cursor = Cursor::new(&sample_post(), &[post::sort::title(Asc)]);
+    let query_string = format!("page[after]={}&page[number]=2", cursor.encode());
+
+    match <Post as JsonApiResource>::Params::from_str(&query_string) {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidPageKey("page[after]".to_string()), e)
+    }
+}
+
+#[test]
+fn parse_renamed_sort_field() {
+    use self::event::sort::*;
+    match <Event as JsonApiResource>::Params::from_str("sort=-createdAt,headline") {
+        Ok(result) => assert_eq!(vec![created_at(Desc), title(Asc)], result.sort.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_renamed_sort_field_fails_on_original_name() {
+    match <Event as JsonApiResource>::Params::from_str("sort=created_at") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidSortValue("created_at".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_renamed_fields() {
+    use self::event::field::*;
+    match <Event as JsonApiResource>::Params::from_str("fields[events]=createdAt,place") {
+        Ok(result) => assert_eq!(vec![created_at, venue_name], result.fieldset.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_renamed_include() {
+    use self::review::include::*;
+    match <Review as JsonApiResource>::Params::from_str("include=leadReviewer,subject") {
+        Ok(result) => {
+            assert_eq!(vec![lead_reviewer(None), reviewed_article(None)], result.include.fields)
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_renamed_include_fails_on_original_name() {
+    match <Review as JsonApiResource>::Params::from_str("include=lead_reviewer") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidIncludeValue("lead_reviewer".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn renamed_relationship_names() {
+    use self::review::relationship::*;
+    assert_eq!(Ok(lead_reviewer), "leadReviewer".parse());
+    assert_eq!(Ok(reviewed_article), "subject".parse());
+    assert_eq!("leadReviewer", lead_reviewer.name());
+    assert_eq!("subject", reviewed_article.name());
+}
+
+#[test]
+fn parse_renamed_filter() {
+    use self::event::filter::*;
+    match <Event as JsonApiResource>::Params::from_str("filter[createdAt]=5&filter[place]=x") {
+        Ok(result) => {
+            assert_eq!(
+                vec![created_at(Eq(5)), venue_name(Eq("x".to_string()))],
+                result.filter.fields
+            )
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_multiple_fields() {
+    use self::post::field::*;
+    match <Post as JsonApiResource>::Params::from_str("fields[posts]=title,rating") {
+        Ok(result) => assert_eq!(vec![title, rating], result.fieldset.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_fields_of_included_types() {
+    use self::article::field::*;
+    let query = "fields[articles]=title,author&fields[authors]=name&fields[tags]=label";
+    match <Article as JsonApiResource>::Params::from_str(query) {
+        Ok(result) => {
+            assert_eq!(vec![title, author], result.fieldset.fields);
+            assert_eq!(
+                Some(&["name".to_string()][..]),
+                result.fieldset.related_fields("authors")
+            );
+            assert_eq!(
+                Some(&["label".to_string()][..]),
+                result.fieldset.related_fields("tags")
+            );
+            assert_eq!(None, result.fieldset.related_fields("articles"));
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_fields_of_nested_included_types() {
+    match <Tag as JsonApiResource>::Params::from_str("fields[authors]=name") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFieldsetType("authors".to_string()),
+                e
+            )
+        }
+    }
+
+    // Articles are included through the relationships of authors
+    match <Author as JsonApiResource>::Params::from_str("fields[tags]=label") {
+        Ok(result) => {
+            assert_eq!(
+                Some(&["label".to_string()][..]),
+                result.fieldset.related_fields("tags")
+            )
+        }
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
+
+#[test]
+fn parse_fields_of_unknown_type() {
+    match <Article as JsonApiResource>::Params::from_str("fields[people]=name") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => {
+            assert_eq!(
+                QueryStringParseError::InvalidFieldsetType("people".to_string()),
+                e
+            )
+        }
+    }
+}
+
+#[test]
+fn parse_fields_of_included_type_fails_on_unknown_field() {
+    match <Article as JsonApiResource>::Params::from_str("fields[authors]=name,age") {
+        Ok(_) => assert!(false, "expected error but no error happened!"),
+        Err(e) => assert_eq!(QueryStringParseError::InvalidFieldValue("age".to_string()), e)
+    }
+}
+
+#[test]
+fn included_params_of_related_type() {
+    use self::author::field::*;
+    let params = <Article as JsonApiResource>::Params::from_str("fields[authors]=name").unwrap();
+    match params.included_params::<Author>() {
+        Ok(result) => assert_eq!(vec![name], result.fieldset.fields),
+        Err(e) => assert!(false, format!("unexpected error!, {:?}", e))
+    }
+}
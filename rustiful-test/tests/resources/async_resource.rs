@@ -0,0 +1,123 @@
+use resources::mock_resource::FooService;
+use resources::mock_resource::TestError;
+use rustiful::*;
+use std::future::ready;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Qux {
+    pub id: String,
+    pub title: String
+}
+
+impl Qux {
+    pub fn new<Id: Into<String>, Title: Into<String>>(id: Id, title: Title) -> Self {
+        Qux {
+            id: id.into(),
+            title: title.into()
+        }
+    }
+}
+
+impl AsyncJsonGet for Qux {
+    type AsyncError = TestError;
+    type AsyncContext = FooService;
+
+    fn find(
+        id: Self::JsonApiIdType,
+        params: Self::Params,
+        _: Self::AsyncContext
+    ) -> JsonApiFuture<
+        Option<JsonApiContainer<JsonApiData<Self>>>,
+        Self::AsyncError,
+        Self::Status
+    > {
+        let result = if id == "fail" {
+            Err(TestError("fail in get".to_string()).into())
+        } else if id == "missing" {
+            Ok(None)
+        } else {
+            Ok(Some(Qux::new(id, "test").into_json(&params).into()))
+        };
+
+        Box::pin(ready(result))
+    }
+}
+
+impl AsyncJsonIndex for Qux {
+    type AsyncError = TestError;
+    type AsyncContext = FooService;
+
+    fn find_all(
+        params: Self::Params,
+        _: Self::AsyncContext
+    ) -> JsonApiFuture<JsonApiPage<Self>, Self::AsyncError, Self::Status> {
+        let resources = vec![Qux::new("1", "test"), Qux::new("2", "test")];
+        Box::pin(ready(Ok(JsonApiPage::new(resources.into_json(&params)))))
+    }
+}
+
+impl AsyncJsonDelete for Qux {
+    type AsyncError = TestError;
+    type AsyncContext = FooService;
+
+    fn delete(
+        id: Self::JsonApiIdType,
+        _: Self::AsyncContext
+    ) -> JsonApiFuture<(), Self::AsyncError, Self::Status> {
+        let result = if id == "fail" {
+            Err(TestError("fail in delete".to_string()).into())
+        } else {
+            Ok(())
+        };
+
+        Box::pin(ready(result))
+    }
+}
+
+impl AsyncJsonPost for Qux {
+    type AsyncError = TestError;
+    type AsyncContext = FooService;
+
+    fn create(
+        json: JsonApiData<Self>,
+        params: Self::Params,
+        _: Self::AsyncContext
+    ) -> JsonApiFuture<
+        Option<JsonApiContainer<JsonApiData<Self>>>,
+        Self::AsyncError,
+        Self::Status
+    > {
+        if json.attributes.title == Some("unchanged".to_string()) {
+            return Box::pin(ready(Ok(None)));
+        }
+
+        let result = match Qux::default().patch(json) {
+            Ok(mut qux) => {
+                qux.id = "1".to_string();
+                Ok(Some(qux.into_json(&params).into()))
+            }
+            Err(e) => Err(TestError(e.to_string()).into())
+        };
+
+        Box::pin(ready(result))
+    }
+}
+
+impl AsyncJsonPatch for Qux {
+    type AsyncError = TestError;
+    type AsyncContext = FooService;
+
+    fn update(
+        id: Self::JsonApiIdType,
+        json: JsonApiData<Self>,
+        params: Self::Params,
+        _: Self::AsyncContext
+    ) -> JsonApiFuture<JsonApiContainer<JsonApiData<Self>>, Self::AsyncError, Self::Status> {
+        let result = match Qux::new(id, "test").patch(json) {
+            Ok(qux) => Ok(qux.into_json(&params).into()),
+            Err(e) => Err(TestError(e.to_string()).into())
+        };
+
+        Box::pin(ready(result))
+    }
+}
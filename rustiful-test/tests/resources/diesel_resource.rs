@@ -30,21 +30,23 @@ pub struct Test {
 #[derive(Debug)]
 pub enum MyErr {
     Diesel(diesel::result::Error),
-    UpdateError(String)
+    UpdateError(String),
+    Invalid(ConversionError)
 }
 
 impl Error for MyErr {
     fn description(&self) -> &str {
         match *self {
             MyErr::Diesel(ref err) => err.description(),
-            MyErr::UpdateError(ref err) => err
+            MyErr::UpdateError(ref err) => err,
+            MyErr::Invalid(ref err) => err.description()
         }
     }
 
     fn cause(&self) -> Option<&Error> {
         match *self {
             MyErr::Diesel(ref err) => err.cause(),
-            MyErr::UpdateError(_) => None
+            MyErr::UpdateError(_) | MyErr::Invalid(_) => None
         }
     }
 }
@@ -53,7 +55,17 @@ impl Display for MyErr {
     fn fmt(&self, f: &mut Formatter) -> ::std::fmt::Result {
         match *self {
             MyErr::Diesel(ref err) => err.fmt(f),
-            MyErr::UpdateError(ref err) => err.fmt(f)
+            MyErr::UpdateError(ref err) => err.fmt(f),
+            MyErr::Invalid(ref err) => err.fmt(f)
+        }
+    }
+}
+
+impl JsonApiErrorDetails for MyErr {
+    fn error_objects(&self, status: u16) -> Vec<JsonApiError> {
+        match *self {
+            MyErr::Invalid(ref err) => err.error_objects(status),
+            _ => vec![JsonApiError::from_error(self, status)]
         }
     }
 }
@@ -66,7 +78,7 @@ impl JsonGet for Test {
         id: Self::JsonApiIdType,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Option<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
         if id == "fail" {
             return Err(MyErr::UpdateError("test fail".to_string()).into());
         }
@@ -74,7 +86,7 @@ impl JsonGet for Test {
             .find(id)
             .first::<Test>(ctx.conn())
             .optional()
-            .map(|r| r.map(|i| i.into_json(params)))
+            .map(|r| r.map(|i| i.into_json(params).into()))
             .map_err(|e| MyErr::Diesel(e).into())
     }
 }
@@ -88,18 +100,18 @@ impl JsonPatch for Test {
         json: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)> {
+    ) -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Self::Status)> {
         let record = table
             .find(&id)
             .first(ctx.conn())
             .map_err(|e| MyErr::Diesel(e))?;
         let patch = (record, json)
             .try_into()
-            .map_err(|e| MyErr::UpdateError(e))?;
+            .map_err(|e| MyErr::Invalid(e))?;
         diesel::update(table.find(&id))
             .set(&patch)
             .execute(ctx.conn())
-            .map(|_| patch.into_json(params))
+            .map(|_| patch.into_json(params).into())
             .map_err(|e| MyErr::Diesel(e).into())
     }
 }
@@ -111,9 +123,15 @@ impl JsonIndex for Test {
     fn find_all(
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Vec<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+    ) -> Result<JsonApiPage<Self>, (Self::Error, Self::Status)> {
         let mut query = table.into_boxed();
 
+        if let Some(page) = params.page.as_ref() {
+            if let (Some(offset), Some(size)) = (page.offset(), page.size()) {
+                query = query.offset(offset as i64).limit(size as i64);
+            }
+        }
+
         {
             use self::test::sort::*;
             for order in &params.sort.fields {
@@ -142,7 +160,7 @@ impl JsonIndex for Test {
 
         query
             .load::<Test>(ctx.conn())
-            .map(|r| r.into_json(params))
+            .map(|r| JsonApiPage::new(r.into_json(params)))
             .map_err(|e| MyErr::Diesel(e).into())
     }
 }
@@ -170,9 +188,9 @@ impl JsonPost for Test {
         json: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
         let has_client_id = json.has_id(); // Client-supplied id
-        let mut result: Test = json.try_into().map_err(|e| MyErr::UpdateError(e))?;
+        let mut result: Test = json.try_into().map_err(|e| MyErr::Invalid(e))?;
 
         // SQlite hack; instead of using auto-generated id, create a UUID if the id hasn't
         // already been supplied by the client.
@@ -184,7 +202,7 @@ impl JsonPost for Test {
             .into(table)
             .execute(ctx.conn())
             .map_err(|e| MyErr::Diesel(e).into())
-            .map(|_| result.into_json(params))
+            .map(|_| Some(result.into_json(params).into()))
     }
 }
 
@@ -1,4 +1,5 @@
 use rustiful::*;
+use std::cell::Cell;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
@@ -11,7 +12,7 @@ pub struct Foo {
 }
 
 impl Foo {
-    fn new<Id: Into<String>, Title: Into<String>, Body: Into<String>>(
+    pub fn new<Id: Into<String>, Title: Into<String>, Body: Into<String>>(
         id: Id,
         title: Title,
         body: Body,
@@ -26,8 +27,42 @@ impl Foo {
     }
 }
 
+#[derive(Debug, Clone)]
 pub struct FooService;
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TransactionOutcome {
+    Committed,
+    RolledBack
+}
+
+thread_local! {
+    static LAST_TRANSACTION: Cell<Option<TransactionOutcome>> = Cell::new(None);
+}
+
+/// The outcome of the last transaction that was run on this thread, if any.
+pub fn last_transaction() -> Option<TransactionOutcome> {
+    LAST_TRANSACTION.with(|last| last.get())
+}
+
+impl JsonApiTransaction for FooService {
+    type Error = TestError;
+
+    fn transaction<T, E, F>(&self, operations: F) -> Result<Result<T, E>, Self::Error>
+    where
+        F: FnOnce(&Self) -> Result<T, E>
+    {
+        let result = operations(self);
+        let outcome = match result {
+            Ok(_) => TransactionOutcome::Committed,
+            Err(_) => TransactionOutcome::RolledBack
+        };
+
+        LAST_TRANSACTION.with(|last| last.set(Some(outcome)));
+        Ok(result)
+    }
+}
+
 #[derive(Debug)]
 pub struct TestError(pub String);
 
@@ -37,6 +72,12 @@ impl TestError {
     }
 }
 
+impl From<TestError> for (TestError, u16) {
+    fn from(err: TestError) -> Self {
+        (err, 418)
+    }
+}
+
 
 impl Error for TestError {
     fn description(&self) -> &str {
@@ -54,6 +95,29 @@ impl Display for TestError {
     }
 }
 
+impl JsonApiErrorDetails for TestError {}
+
+#[derive(Debug)]
+pub struct InvalidAttribute(pub &'static str);
+
+impl Error for InvalidAttribute {
+    fn description(&self) -> &str {
+        "Invalid attribute"
+    }
+}
+
+impl Display for InvalidAttribute {
+    fn fmt(&self, f: &mut Formatter) -> ::std::fmt::Result {
+        write!(f, "Invalid value for {}", self.0)
+    }
+}
+
+impl JsonApiErrorDetails for InvalidAttribute {
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        Some(JsonApiErrorSource::pointer(format!("/data/attributes/{}", self.0)))
+    }
+}
+
 impl JsonGet for Foo {
     type Error = TestError;
     type Context = FooService;
@@ -62,12 +126,14 @@ impl JsonGet for Foo {
         id: Self::JsonApiIdType,
         params: &Self::Params,
         _: Self::Context
-    ) -> Result<Option<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
 
         if id == "fail" {
             Err(TestError("fail in get".to_string()).into())
+        } else if id == "missing" {
+            Ok(None)
         } else {
-            Ok(Some(Foo::new("1", "test", "test", true).into_json(params)))
+            Ok(Some(Foo::new("1", "test", "test", true).into_json(params).into()))
         }
     }
 }
@@ -79,13 +145,21 @@ impl JsonIndex for Foo {
     fn find_all(
         params: &Self::Params,
         _: Self::Context
-    ) -> Result<Vec<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+    ) -> Result<JsonApiPage<Self>, (Self::Error, Self::Status)> {
         if let Some(_) = params.query_params.get("fail") {
             let err = TestError::new("fail in index");
             return Err(err.into());
         }
 
-        Ok(vec![Foo::new("1", "test", "test", true)].into_json(params))
+        let resources = vec![Foo::new("1", "test", "test", true)];
+        let cursors = Cursor::bounds(&resources, &params.sort.fields);
+        let page = JsonApiPage::new(resources.into_json(params));
+
+        match params.page {
+            Some(Page::Cursor { .. }) => Ok(page.with_cursors(cursors)),
+            Some(_) => Ok(page.with_total(25)),
+            None => Ok(page)
+        }
     }
 }
 
@@ -107,22 +181,37 @@ impl JsonDelete for Foo {
 }
 
 impl JsonPost for Foo {
-    type Error = TestError;
+    type Error = JsonApiErrors;
     type Context = FooService;
 
     fn create(
         json: JsonApiData<Self>,
         params: &Self::Params,
         _: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)> {
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
         if let Some(id) = json.id {
+            let mut errors = JsonApiErrors::new();
+
             if id == "fail" {
-                let err = TestError::new("fail in post");
-                return Err(err.into());
+                errors.push(&TestError::new("fail in post"), 418);
+                return Err(errors.into());
+            } else if id == "invalid" {
+                errors.push(&InvalidAttribute("title"), 422);
+                errors.push(&InvalidAttribute("body"), 409);
+                return Err(errors.into());
+            } else if id == "unchanged" {
+                return Ok(None);
             }
+        } else if json.attributes.title == Some("unchanged".to_string()) {
+            // Not created as sent, since the client doesn't know the id.
+            return Ok(None);
         }
 
-        Ok(Foo::new("1", "test", "test", true).into_json(params))
+        let mut meta = JsonApiMeta::new();
+        meta.insert("revision".to_string(), 1.into());
+
+        let created = Foo::new("1", "test", "test", true).into_json(params);
+        Ok(Some(JsonApiContainer::from(created).with_meta(meta)))
     }
 }
 
@@ -135,7 +224,7 @@ impl JsonPatch for Foo {
         json: JsonApiData<Self>,
         params: &Self::Params,
         _: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)> {
+    ) -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Self::Status)> {
         if let Some(id) = json.id {
             if id == "fail" {
                 let err = TestError::new("fail in patch");
@@ -143,6 +232,135 @@ impl JsonPatch for Foo {
             }
         }
 
-        Ok(Foo::new("1", "test", "test", true).into_json(params))
+        let mut meta = JsonApiMeta::new();
+        meta.insert("revision".to_string(), 2.into());
+
+        let updated = Foo::new("1", "test", "test", true).into_json(params);
+        Ok(JsonApiContainer::from(updated).with_meta(meta))
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Bar {
+    pub id: String,
+    #[JsonApiRelationship(Foo)]
+    pub foo_id: Option<String>,
+    #[JsonApiRelationship(Foo)]
+    pub foo_ids: Vec<String>
+}
+
+fn foo_identifier(id: &str) -> JsonApiResourceIdentifier {
+    JsonApiResourceIdentifier::new("foos", id)
+}
+
+fn bar_linkage(relationship: bar::relationship) -> JsonApiLinkage {
+    match relationship {
+        bar::relationship::foo => JsonApiLinkage::ToOne(Some(foo_identifier("1"))),
+        bar::relationship::foos => {
+            JsonApiLinkage::ToMany(vec![foo_identifier("1"), foo_identifier("2")])
+        }
+    }
+}
+
+impl JsonGetRelationship for Bar {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find_relationship(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        _: Self::Context
+    ) -> Result<Option<JsonApiLinkage>, (Self::Error, Self::Status)> {
+        if id == "missing" {
+            Ok(None)
+        } else {
+            Ok(Some(bar_linkage(relationship)))
+        }
+    }
+}
+
+impl JsonPatchRelationship for Bar {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn update_relationship(
+        _: Self::JsonApiIdType,
+        _: Self::RelationshipField,
+        linkage: JsonApiLinkage,
+        _: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)> {
+        Ok(linkage)
+    }
+}
+
+impl JsonPostRelationship for Bar {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn add_to_relationship(
+        _: Self::JsonApiIdType,
+        _: Self::RelationshipField,
+        members: Vec<JsonApiResourceIdentifier>,
+        _: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)> {
+        let mut foos = vec![foo_identifier("1"), foo_identifier("2")];
+        for member in members {
+            if !foos.contains(&member) {
+                foos.push(member);
+            }
+        }
+
+        Ok(JsonApiLinkage::ToMany(foos))
+    }
+}
+
+impl JsonDeleteRelationship for Bar {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn remove_from_relationship(
+        _: Self::JsonApiIdType,
+        _: Self::RelationshipField,
+        members: Vec<JsonApiResourceIdentifier>,
+        _: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)> {
+        let mut foos = vec![foo_identifier("1"), foo_identifier("2")];
+        foos.retain(|foo| !members.contains(foo));
+
+        Ok(JsonApiLinkage::ToMany(foos))
+    }
+}
+
+impl JsonGetRelated<Foo> for Bar {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find_related(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        params: &<Foo as JsonApiResource>::Params,
+        _: Self::Context
+    ) -> Result<Option<JsonApiRelated<Foo>>, (Self::Error, Self::Status)> {
+        if id == "missing" {
+            return Ok(None);
+        }
+
+        match relationship {
+            bar::relationship::foo => {
+                Ok(Some(Foo::new("1", "test", "test", true).into_json(params).into()))
+            }
+            bar::relationship::foos => {
+                let foos = vec![
+                    Foo::new("1", "test", "test", true),
+                    Foo::new("2", "test2", "test2", false)
+                ];
+                let page = JsonApiPage::new(foos.into_json(params));
+
+                match params.page {
+                    Some(_) => Ok(Some(page.with_total(25).into())),
+                    None => Ok(Some(page.into()))
+                }
+            }
+        }
     }
 }
@@ -1,3 +1,4 @@
 pub mod mock_resource;
+pub mod async_resource;
 pub mod diesel_resource;
 pub mod simple_resources;
@@ -1,3 +1,6 @@
+use rustiful::Filterable;
+use std::str::FromStr;
+
 #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
 pub struct Foo {
     #[JsonApiId]
@@ -12,3 +15,118 @@ pub struct Bar {
     pub id: String,
     pub bar: i32
 }
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Author {
+    pub id: String,
+    pub name: String,
+    #[JsonApiRelationship]
+    pub article_ids: Vec<String>
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Tag {
+    pub id: i32,
+    pub label: String
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Article {
+    pub id: String,
+    pub title: String,
+    #[JsonApiRelationship]
+    pub author_id: String,
+    #[JsonApiRelationship(Author)]
+    pub editor_id: Option<String>,
+    #[JsonApiRelationship]
+    pub tag_ids: Vec<i32>
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Post {
+    pub id: i32,
+    pub title: String,
+    pub published: bool,
+    pub rating: Option<i32>,
+    pub keywords: Vec<String>
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+pub struct Profile {
+    pub id: String,
+    #[jsonapi(validate(length(min = 1, max = 10)))]
+    pub name: String,
+    #[jsonapi(validate(range(min = 0, max = 150)))]
+    pub age: i32,
+    #[jsonapi(validate(email))]
+    pub email: Option<String>,
+    #[jsonapi(validate(regex = "^[a-z0-9_]+$", custom = "not_reserved"))]
+    pub handle: String
+}
+
+fn not_reserved(handle: &str) -> Result<(), String> {
+    if handle == "admin" {
+        Err("admin is a reserved handle".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub id: i32,
+    pub created_at: i64,
+    #[serde(rename = "headline")]
+    pub title: String,
+    #[jsonapi(rename = "place")]
+    pub venue_name: Option<String>
+}
+
+#[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+#[serde(rename_all = "camelCase")]
+pub struct Review {
+    pub id: String,
+    pub star_rating: i32,
+    #[JsonApiRelationship(Author)]
+    pub lead_reviewer_id: String,
+    #[JsonApiRelationship(Article)]
+    #[serde(rename = "subject")]
+    pub reviewed_article_id: Option<String>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Unit {
+    Celsius,
+    Fahrenheit
+}
+
+impl Default for Unit {
+    fn default() -> Self {
+        Unit::Celsius
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Station(pub String);
+
+impl FromStr for Station {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Station(s.to_string()))
+    }
+}
+
+impl Filterable for Station {}
+
+/// A resource with a float attribute, an attribute that doesn't implement `FromStr`, and an
+/// attribute of a custom `Filterable` type.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize, JsonApi)]
+pub struct Measurement {
+    pub id: String,
+    pub value: f64,
+    pub unit: Unit,
+    #[jsonapi(filter)]
+    pub station: Station
+}
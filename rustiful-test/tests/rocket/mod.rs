@@ -0,0 +1,3 @@
+extern crate rocket;
+
+mod routes_tests;
@@ -0,0 +1,259 @@
+use super::rocket;
+use super::rocket::Request;
+use super::rocket::http::Header;
+use super::rocket::http::Status;
+use super::rocket::http::Method;
+use super::rocket::local::blocking::Client;
+use super::rocket::outcome::Outcome;
+use super::rocket::request;
+use super::rocket::request::FromRequest;
+use adapter::*;
+use resources::async_resource::*;
+use resources::mock_resource::*;
+use rustiful::IntoJson;
+use rustiful::JsonApiContainer;
+use rustiful::JsonApiData;
+use rustiful::JsonGet;
+use rustiful::OperationsHandler;
+use rustiful::rocket::JsonApiRoutesBuilder;
+use std::str::FromStr;
+use std::future::Future;
+use std::future::ready;
+use std::pin::Pin;
+
+/// What `#[rocket::async_trait]` expands `async fn from_request` to.
+impl<'r> FromRequest<'r> for FooService {
+    type Error = TestError;
+
+    fn from_request<'life0, 'async_trait>(
+        request: &'r Request<'life0>
+    ) -> Pin<Box<Future<Output = request::Outcome<Self, Self::Error>> + Send + 'async_trait>>
+    where
+        'r: 'async_trait,
+        'life0: 'async_trait,
+        Self: 'async_trait
+    {
+        let outcome = if request.headers().contains("test-fail") {
+            let error = TestError("from request fail".to_string());
+            Outcome::Error((Status::InternalServerError, error))
+        } else {
+            Outcome::Success(FooService {})
+        };
+
+        Box::pin(ready(outcome))
+    }
+}
+
+/// A resource with the Rocket status as the status of its service traits.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+#[jsonapi(status = "Status")]
+pub struct Gizmo {
+    pub id: String,
+    pub name: String
+}
+
+impl JsonGet for Gizmo {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find(
+        id: Self::JsonApiIdType,
+        params: &Self::Params,
+        _: Self::Context
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Status)> {
+        if id == "fail" {
+            return Err((TestError("fail in get".to_string()), Status::ImATeapot));
+        }
+
+        let gizmo = Gizmo {
+            id: id,
+            name: "test".to_string()
+        };
+        Ok(Some(gizmo.into_json(params).into()))
+    }
+}
+
+fn builder() -> JsonApiRoutesBuilder {
+    let mut builder = JsonApiRoutesBuilder::default();
+    jsonapi_endpoints!(builder);
+    jsonapi_async_endpoints!(builder);
+    builder.jsonapi_get::<Gizmo>();
+    builder
+}
+
+fn call(builder: JsonApiRoutesBuilder, req: TestRequest) -> TestResponse<Status> {
+    let client = Client::tracked(rocket::build().mount("/", builder.build()))
+        .expect("valid rocket instance");
+    let method = Method::from_str(req.method).expect("valid method");
+    let mut request = client.req(method, req.uri).body(req.body);
+
+    for (name, value) in req.headers {
+        request = request.header(Header::new(name, value));
+    }
+
+    let response = request.dispatch();
+    let status = response.status();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|header| (header.name().to_string(), header.value().to_string()))
+        .collect();
+
+    TestResponse {
+        status: status,
+        headers: headers,
+        body: response.into_string().unwrap_or_default()
+    }
+}
+
+#[test]
+fn get() {
+    let response = call(builder(), TestRequest::get("/foos/1"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn get_with_base_url() {
+    let mut builder = builder();
+    builder.set_base_url("https://example.com/api");
+    let response = call(builder, TestRequest::get("/foos/1"));
+
+    assert_eq!("https://example.com/api/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn index() {
+    let response = call(builder(), TestRequest::get("/foos"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert!(response.json()["data"].is_array());
+}
+
+#[test]
+fn post() {
+    let response = call(builder(), TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(Status::Created, response.status);
+    assert_eq!(Some("http://localhost:3000/foos/1"), response.header("Location"));
+}
+
+#[test]
+fn delete() {
+    let response = call(builder(), TestRequest::new("DELETE", "/foos/1", ""));
+
+    assert_eq!(Status::NoContent, response.status);
+    assert_eq!(None, response.header("Content-Type"));
+}
+
+#[test]
+fn get_relationship() {
+    let response = call(builder(), TestRequest::get("/bars/1/relationships/foo"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!("foos", response.json()["data"]["type"]);
+}
+
+#[test]
+fn get_related() {
+    let response = call(builder(), TestRequest::get("/bars/1/foos"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!(2, response.json()["data"].as_array().unwrap().len());
+}
+
+#[test]
+fn guard_error() {
+    let req = TestRequest::get("/foos/1").with_header("test-fail", "true");
+    let response = call(builder(), req);
+
+    assert_eq!(Status::InternalServerError, response.status);
+    assert_eq!("from request fail", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn body_too_large() {
+    let mut builder = builder();
+    builder.set_max_body_length(10);
+    let response = call(builder, TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(Status::PayloadTooLarge, response.status);
+    assert_eq!("Body too large", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_async() {
+    let response = call(builder(), TestRequest::get("/quxes/1"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/quxes/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn delete_async() {
+    let response = call(builder(), TestRequest::new("DELETE", "/quxes/1", ""));
+
+    assert_eq!(Status::NoContent, response.status);
+}
+
+#[test]
+fn patch_async() {
+    let body = r#"{ "data": { "id": "1", "type": "quxes", "attributes": { "title": "new" } } }"#;
+    let response = call(builder(), TestRequest::new("PATCH", "/quxes/1", body));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!("new", response.json()["data"]["attributes"]["title"]);
+}
+
+#[test]
+fn get_with_rocket_status() {
+    let response = call(builder(), TestRequest::get("/gizmos/1"));
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!("1", response.json()["data"]["id"]);
+
+    let response = call(builder(), TestRequest::get("/gizmos/fail"));
+
+    assert_eq!(Status::ImATeapot, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn post_without_json_api_content_type() {
+    let req = TestRequest::new("POST", "/foos", NEW_FOO)
+        .with_header("Content-Type", "application/json");
+    let response = call(builder(), req);
+
+    assert_eq!(Status::UnsupportedMediaType, response.status);
+    assert_eq!("Unsupported media type", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn negotiation_before_guard() {
+    let req = TestRequest::get("/foos/1")
+        .with_header("Accept", UNSUPPORTED_EXTENSION)
+        .with_header("test-fail", "true");
+
+    assert_eq!(Status::NotAcceptable, call(builder(), req).status);
+}
+
+#[test]
+fn operations() {
+    let mut operations = OperationsHandler::<FooService>::new();
+    operations.add::<Foo>();
+
+    let mut builder = builder();
+    builder.jsonapi_operations(operations);
+
+    let req = TestRequest::new("POST", "/operations", ADD_FOO)
+        .with_header("Content-Type", &atomic_content_type());
+    let response = call(builder, req);
+
+    assert_eq!(Status::Ok, response.status);
+    assert_eq!(Some(atomic_content_type().as_str()), response.header("Content-Type"));
+    assert_eq!("1", response.json()["atomic:results"][0]["data"]["id"]);
+}
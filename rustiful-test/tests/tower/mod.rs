@@ -0,0 +1,5 @@
+extern crate futures_executor;
+extern crate http;
+extern crate tower_service;
+
+mod service_tests;
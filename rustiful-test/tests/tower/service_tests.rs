@@ -0,0 +1,257 @@
+use super::futures_executor::block_on;
+use super::http::Request;
+use super::http::StatusCode;
+use super::http::request::Parts;
+use super::tower_service::Service;
+use adapter::*;
+use resources::async_resource::*;
+use resources::mock_resource::*;
+use rustiful::IntoJson;
+use rustiful::JsonApiContainer;
+use rustiful::JsonApiData;
+use rustiful::JsonGet;
+use rustiful::OperationsHandler;
+use rustiful::tower::FromRequest;
+use rustiful::tower::JsonApiServiceBuilder;
+
+impl FromRequest for FooService {
+    type Error = TestError;
+    fn from_request(parts: &Parts) -> Result<Self, (Self::Error, StatusCode)> {
+        if parts.headers.contains_key("test-fail") {
+            return Err((
+                TestError("from request fail".to_string()),
+                StatusCode::INTERNAL_SERVER_ERROR
+            ));
+        }
+        Ok(FooService {})
+    }
+}
+
+/// A resource with the `http` status as the status of its service traits.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, JsonApi)]
+#[jsonapi(status = "StatusCode")]
+pub struct Widget {
+    pub id: String,
+    pub name: String
+}
+
+impl JsonGet for Widget {
+    type Error = TestError;
+    type Context = FooService;
+
+    fn find(
+        id: Self::JsonApiIdType,
+        params: &Self::Params,
+        _: Self::Context
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, StatusCode)> {
+        if id == "fail" {
+            return Err((TestError("fail in get".to_string()), StatusCode::IM_A_TEAPOT));
+        }
+
+        let widget = Widget {
+            id: id,
+            name: "test".to_string()
+        };
+        Ok(Some(widget.into_json(params).into()))
+    }
+}
+
+fn builder() -> JsonApiServiceBuilder {
+    let mut builder = JsonApiServiceBuilder::default();
+    jsonapi_endpoints!(builder);
+    jsonapi_async_endpoints!(builder);
+    builder.jsonapi_get::<Widget>();
+    builder
+}
+
+fn call(builder: JsonApiServiceBuilder, req: TestRequest) -> TestResponse<StatusCode> {
+    let mut request = Request::builder().method(req.method).uri(req.uri.as_str());
+
+    for &(ref name, ref value) in &req.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+
+    let request = request.body(req.body).unwrap();
+    let response = block_on(builder.build().call(request)).unwrap();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_str().unwrap().to_string()))
+        .collect();
+
+    TestResponse {
+        status: response.status(),
+        headers: headers,
+        body: response.into_body()
+    }
+}
+
+#[test]
+fn get() {
+    let response = call(builder(), TestRequest::get("/foos/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn get_with_base_url() {
+    let mut builder = builder();
+    builder.set_base_url("https://example.com/api");
+    let response = call(builder, TestRequest::get("/foos/1"));
+
+    assert_eq!("https://example.com/api/foos/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn index() {
+    let response = call(builder(), TestRequest::get("/foos"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert!(response.json()["data"].is_array());
+}
+
+#[test]
+fn post() {
+    let response = call(builder(), TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(StatusCode::CREATED, response.status);
+    assert_eq!(Some("http://localhost:3000/foos/1"), response.header("Location"));
+}
+
+#[test]
+fn delete() {
+    let response = call(builder(), TestRequest::new("DELETE", "/foos/1", ""));
+
+    assert_eq!(StatusCode::NO_CONTENT, response.status);
+    assert_eq!(None, response.header("Content-Type"));
+    assert_eq!("", response.body);
+}
+
+#[test]
+fn get_related() {
+    let response = call(builder(), TestRequest::get("/bars/1/foos"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(2, response.json()["data"].as_array().unwrap().len());
+}
+
+#[test]
+fn unknown_relationship() {
+    let response = call(builder(), TestRequest::get("/bars/1/relationships/baz"));
+
+    assert_eq!(StatusCode::NOT_FOUND, response.status);
+    assert_eq!(Some("application/vnd.api+json"), response.header("Content-Type"));
+    assert_eq!("Not found", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_with_percent_encoded_id() {
+    let response = call(builder(), TestRequest::get("/foos/f%61il"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn method_not_allowed() {
+    let response = call(builder(), TestRequest::new("PUT", "/foos/1", NEW_FOO));
+
+    assert_eq!(StatusCode::METHOD_NOT_ALLOWED, response.status);
+    assert!(response.header("Allow").unwrap().contains("GET"));
+    assert_eq!("Method not allowed", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn context_error() {
+    let req = TestRequest::get("/foos/1").with_header("test-fail", "true");
+    let response = call(builder(), req);
+
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status);
+    assert_eq!("from request fail", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn body_too_large() {
+    let mut builder = builder();
+    builder.set_max_body_length(10);
+    let response = call(builder, TestRequest::new("POST", "/foos", NEW_FOO));
+
+    assert_eq!(StatusCode::PAYLOAD_TOO_LARGE, response.status);
+    assert_eq!("Body too large", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_async() {
+    let response = call(builder(), TestRequest::get("/quxes/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!("1", response.json()["data"]["id"]);
+    assert_eq!("http://localhost:3000/quxes/1", response.json()["links"]["self"]);
+}
+
+#[test]
+fn get_async_with_error() {
+    let response = call(builder(), TestRequest::get("/quxes/fail"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn post_async() {
+    let body = r#"{ "data": { "type": "quxes", "attributes": { "title": "test" } } }"#;
+    let response = call(builder(), TestRequest::new("POST", "/quxes", body));
+
+    assert_eq!(StatusCode::CREATED, response.status);
+    assert_eq!(Some("http://localhost:3000/quxes/1"), response.header("Location"));
+}
+
+#[test]
+fn get_with_http_status() {
+    let response = call(builder(), TestRequest::get("/widgets/1"));
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!("1", response.json()["data"]["id"]);
+
+    let response = call(builder(), TestRequest::get("/widgets/fail"));
+
+    assert_eq!(StatusCode::IM_A_TEAPOT, response.status);
+    assert_eq!("fail in get", response.json()["errors"][0]["detail"]);
+}
+
+#[test]
+fn post_without_json_api_content_type() {
+    let req = TestRequest::new("POST", "/foos", NEW_FOO)
+        .with_header("Content-Type", "application/json");
+    let response = call(builder(), req);
+
+    assert_eq!(StatusCode::UNSUPPORTED_MEDIA_TYPE, response.status);
+    assert_eq!("Unsupported media type", response.json()["errors"][0]["title"]);
+}
+
+#[test]
+fn get_with_unsupported_extension_in_accept() {
+    let req = TestRequest::get("/foos/1").with_header("Accept", UNSUPPORTED_EXTENSION);
+
+    assert_eq!(StatusCode::NOT_ACCEPTABLE, call(builder(), req).status);
+}
+
+#[test]
+fn operations() {
+    let mut operations = OperationsHandler::<FooService>::new();
+    operations.add::<Foo>();
+
+    let mut builder = builder();
+    builder.jsonapi_operations(operations);
+
+    let req = TestRequest::new("POST", "/operations", ADD_FOO)
+        .with_header("Content-Type", &atomic_content_type());
+    let response = call(builder, req);
+
+    assert_eq!(StatusCode::OK, response.status);
+    assert_eq!(Some(atomic_content_type().as_str()), response.header("Content-Type"));
+    assert_eq!("1", response.json()["atomic:results"][0]["data"]["id"]);
+}
@@ -0,0 +1,211 @@
+use container::JsonApiContainer;
+use data::JsonApiData;
+use error::JsonApiErrorDetails;
+use errors::ConversionError;
+use page::JsonApiPage;
+use resource::JsonApiResource;
+use service::Handler;
+use std::convert::TryFrom;
+use std::convert::TryInto;
+use std::future::Future;
+use std::pin::Pin;
+use to_json::ToJson;
+
+/// The result of an async service trait, i.e a boxed future of the result of the corresponding
+/// synchronous service trait.
+///
+/// In Rust 2018 and later, this is typically created with `Box::pin(async move { ... })`.
+pub type JsonApiFuture<T, E, S> = Pin<Box<Future<Output = Result<T, (E, S)>> + Send>>;
+
+/// An async variant of `JsonGet`, for implementing GET `/{resource-name}/{id}` on a resource
+/// type without blocking a thread while the resource is fetched.
+///
+/// Since the returned future can't borrow from the request, `params` is passed by value. Async
+/// resources are served by the `hyper`, `tower` and `rocket` adapters, see `jsonapi_get_async`.
+///
+/// The associated types are named `AsyncError` and `AsyncContext` rather than `Error` and
+/// `Context`, so that `Self::Error` isn't ambiguous in a resource that also implements one of the
+/// synchronous traits. Only the five resource endpoints have async variants, the relationship and
+/// related resource endpoints are always served by the synchronous traits.
+///
+/// # Example
+///
+/// ```
+/// extern crate rustiful;
+///
+/// #[macro_use]
+/// extern crate serde_derive;
+///
+/// #[macro_use]
+/// extern crate rustiful_derive;
+///
+/// use std::error::Error;
+/// use std::fmt::Display;
+/// use std::future::ready;
+/// use rustiful::AsyncJsonGet;
+/// use rustiful::IntoJson;
+/// use rustiful::JsonApiContainer;
+/// use rustiful::JsonApiFuture;
+/// use rustiful::JsonApiData;
+///
+/// #[derive(Debug, PartialEq, Eq, JsonApi, Default)]
+/// struct MyResource {
+///     id: String,
+///     foo: bool,
+///     bar: String
+/// }
+///
+/// # #[derive(Debug, PartialEq, Eq)]
+/// # struct MyError(String);
+/// #
+/// # impl Error for MyError {
+/// #    fn description(&self) -> &str {
+/// #        &self.0
+/// #    }
+/// # }
+/// #
+/// # impl Display for MyError {
+/// #    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+/// #        self.0.fmt(f)
+/// #    }
+/// # }
+/// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
+/// # struct MyCtx {
+/// # }
+/// #
+/// impl AsyncJsonGet for MyResource {
+///     type AsyncError = MyError;
+///     type AsyncContext = MyCtx;
+///
+///     fn find(id: Self::JsonApiIdType,
+///         params: Self::Params,
+///         ctx: Self::AsyncContext)
+///         -> JsonApiFuture<Option<JsonApiContainer<JsonApiData<Self>>>, Self::AsyncError,
+///             Self::Status> {
+///         // A real implementation would await an async database driver here.
+///         let resource = MyResource {
+///             id: id,
+///             foo: true,
+///             bar: "hello".to_string()
+///         };
+///
+///         Box::pin(ready(Ok(Some(resource.into_json(&params).into()))))
+///     }
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub trait AsyncJsonGet: Handler
+where
+    Self: JsonApiResource + ToJson
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type AsyncError: JsonApiErrorDetails + Send;
+    /// A user-defined type
+    type AsyncContext;
+
+    /// Gets a resource, along with any side-loaded resources. See `JsonGet::find`.
+    fn find(
+        id: Self::JsonApiIdType,
+        params: Self::Params,
+        ctx: Self::AsyncContext
+    ) -> JsonApiFuture<
+        Option<JsonApiContainer<JsonApiData<Self>>>,
+        Self::AsyncError,
+        Self::Status
+    >;
+}
+
+/// An async variant of `JsonPost`, for implementing POST `/{resource-name}` on a resource type.
+pub trait AsyncJsonPost: Handler
+where
+    Self: JsonApiResource + ToJson
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type AsyncError: JsonApiErrorDetails + Send;
+    /// A user-defined type
+    type AsyncContext;
+
+    /// Creates a resource, and returns it in a document. See `JsonPost::create`.
+    fn create(
+        json: JsonApiData<Self>,
+        params: Self::Params,
+        ctx: Self::AsyncContext
+    ) -> JsonApiFuture<
+        Option<JsonApiContainer<JsonApiData<Self>>>,
+        Self::AsyncError,
+        Self::Status
+    >;
+}
+
+/// An async variant of `JsonPatch`, for implementing PATCH `/{resource-name}/{id}` on a resource
+/// type.
+pub trait AsyncJsonPatch: Handler
+where
+    Self: JsonApiResource + ToJson
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type AsyncError: JsonApiErrorDetails + Send;
+    /// A user-defined type
+    type AsyncContext;
+
+    /// Applies the attributes and relationships of `json` that are present to `self`. See
+    /// `JsonPatch::patch`.
+    fn patch(self, json: JsonApiData<Self>) -> Result<Self, ConversionError>
+    where
+        Self: TryFrom<(Self, JsonApiData<Self>), Error = ConversionError>
+    {
+        (self, json).try_into()
+    }
+
+    /// Updates a resource, and returns it in a document. See `JsonPatch::update`.
+    fn update(
+        id: Self::JsonApiIdType,
+        json: JsonApiData<Self>,
+        params: Self::Params,
+        ctx: Self::AsyncContext
+    ) -> JsonApiFuture<JsonApiContainer<JsonApiData<Self>>, Self::AsyncError, Self::Status>;
+}
+
+/// An async variant of `JsonIndex`, for implementing GET `/{resource-name}` on a resource type.
+pub trait AsyncJsonIndex: Handler
+where
+    Self: JsonApiResource + ToJson
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type AsyncError: JsonApiErrorDetails + Send;
+    /// A user-defined type
+    type AsyncContext;
+
+    /// The page size used when the client doesn't specify one, see `JsonIndex::DEFAULT_PAGE_SIZE`.
+    const DEFAULT_PAGE_SIZE: Option<u64> = None;
+
+    /// The largest page size that a client can request, see `JsonIndex::MAX_PAGE_SIZE`.
+    const MAX_PAGE_SIZE: Option<u64> = None;
+
+    /// Gets a page of resources. See `JsonIndex::find_all`.
+    fn find_all(
+        params: Self::Params,
+        ctx: Self::AsyncContext
+    ) -> JsonApiFuture<JsonApiPage<Self>, Self::AsyncError, Self::Status>;
+}
+
+/// An async variant of `JsonDelete`, for implementing DELETE `/{resource-name}/{id}` on a
+/// resource type.
+pub trait AsyncJsonDelete: Handler
+where
+    Self: JsonApiResource
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type AsyncError: JsonApiErrorDetails + Send;
+    /// A user-defined type
+    type AsyncContext;
+
+    /// Deletes a resource. See `JsonDelete::delete`.
+    fn delete(
+        id: Self::JsonApiIdType,
+        ctx: Self::AsyncContext
+    ) -> JsonApiFuture<(), Self::AsyncError, Self::Status>;
+}
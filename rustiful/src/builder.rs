@@ -1,3 +1,5 @@
+use errors::ConversionError;
+
 /// A trait for implementing a builder for any `Default` type.
 ///
 /// The implementing type is generated in jsonapi-derive.
@@ -7,7 +9,9 @@ where
 {
     fn new(model: T) -> Self;
 
-    fn build(self) -> Result<T, String>;
+    /// Builds a `T`, or returns a `ConversionError::MissingAttribute` (or `MissingRelationship`)
+    /// for the first field that was never set.
+    fn build(self) -> Result<T, ConversionError>;
 }
 
 /// A trait for setting a `JsonApiBuilder<Self>` on any type that implements `Default`.
@@ -1,8 +1,318 @@
+extern crate serde_json;
+
+use self::serde_json::Map;
+use self::serde_json::Value;
+use data::JsonApiData;
+use data::SelfLinks;
+use links::JsonApiLinks;
+use links::resource_url;
+use meta::JsonApiMeta;
+use relationship::JsonApiResourceIdentifier;
+use serde::de::Deserialize;
+use serde::de::Deserializer;
+use serde::ser::Error;
+use serde::ser::Serialize;
+use serde::ser::Serializer;
+use std::collections::HashSet;
+use std::mem;
+use to_json::ToJson;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// Contains JSONAPI data.
 ///
 /// `data` should be either `JsonApiData<T::Attrs>` or `Vec<JsonApiData<T:Attrs>>`, where `T` is an
 /// implementation of `ToJson`.
 pub struct JsonApiContainer<T> {
-    pub data: T
+    pub data: T,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Resources that are related to the primary data, i.e the resources that were requested with
+    /// the `include` query parameter.
+    pub included: Vec<JsonApiIncluded>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Top-level links, such as the pagination links of a collection.
+    pub links: Option<JsonApiLinks>,
+    #[serde(default, skip_serializing_if = "Map::is_empty")]
+    /// Top-level meta information, such as the total number of resources in a collection.
+    pub meta: JsonApiMeta
+}
+
+impl<T> JsonApiContainer<T> {
+    pub fn new(data: T) -> JsonApiContainer<T> {
+        JsonApiContainer {
+            data: data,
+            included: vec![],
+            links: None,
+            meta: JsonApiMeta::new()
+        }
+    }
+
+    /// Adds top-level meta information. Any existing members with the same names are replaced.
+    pub fn with_meta(mut self, meta: JsonApiMeta) -> JsonApiContainer<T> {
+        self.meta.extend(meta);
+        self
+    }
+
+    /// Sets the top-level `self` link, i.e the URL of this document. Any other top-level links,
+    /// such as pagination links, are kept.
+    pub fn with_self_link(mut self, url: String) -> JsonApiContainer<T> {
+        let mut links = self.links.take().unwrap_or_default();
+        links.self_link = Some(url);
+        self.links = Some(links);
+        self
+    }
+
+    /// Sets the `self` links of the primary data and of the included resources, relative to
+    /// `base_url`. See `JsonApiData::with_self_link`.
+    pub fn with_resource_links(mut self, base_url: &str) -> JsonApiContainer<T>
+    where
+        T: SelfLinks
+    {
+        self.data.set_self_links(base_url);
+        for resource in &mut self.included {
+            resource.set_self_link(base_url);
+        }
+        self
+    }
+
+    /// Side-loads a related resource in the top-level `included` member. This is typically used
+    /// when the resource has been requested with the `include` query parameter. A resource that
+    /// is part of the primary data or that has already been included is skipped.
+    ///
+    /// If `resource` cannot be serialized to JSON, neither can this document, so the response is
+    /// a `500 Internal Server Error` with the serialization error.
+    pub fn include<U: ToJson>(self, resource: JsonApiData<U>) -> JsonApiContainer<T>
+    where
+        T: PrimaryData
+    {
+        self.with_included(vec![JsonApiIncluded::from_data(&resource)])
+    }
+
+    /// Side-loads resources of any type in the top-level `included` member, see `include`.
+    pub fn with_included(mut self, included: Vec<JsonApiIncluded>) -> JsonApiContainer<T>
+    where
+        T: PrimaryData
+    {
+        let mut resources = mem::replace(&mut self.included, vec![]);
+        resources.extend(included);
+        self.included = deduplicate(resources, &self.data.identifiers());
+        self
+    }
+}
+
+/// Converts a `JsonApiData<T>` to a document with the resource as its primary data.
+impl<T> From<JsonApiData<T>> for JsonApiContainer<JsonApiData<T>>
+where
+    T: ToJson
+{
+    fn from(data: JsonApiData<T>) -> Self {
+        JsonApiContainer::new(data)
+    }
+}
+
+/// Converts a `Vec<JsonApiData<T>>` to a document with the resources as its primary data.
+impl<T> From<Vec<JsonApiData<T>>> for JsonApiContainer<Vec<JsonApiData<T>>>
+where
+    T: ToJson
+{
+    fn from(data: Vec<JsonApiData<T>>) -> Self {
+        JsonApiContainer::new(data)
+    }
+}
+
+/// The `(type, id)` pairs of the primary data of a `JsonApiContainer`, which are left out of the
+/// `included` member of the document.
+pub trait PrimaryData {
+    fn identifiers(&self) -> Vec<JsonApiResourceIdentifier>;
+}
+
+impl<T: ToJson> PrimaryData for JsonApiData<T> {
+    fn identifiers(&self) -> Vec<JsonApiResourceIdentifier> {
+        identifier(self).into_iter().collect()
+    }
+}
+
+impl<T: ToJson> PrimaryData for Vec<JsonApiData<T>> {
+    fn identifiers(&self) -> Vec<JsonApiResourceIdentifier> {
+        self.iter().filter_map(identifier).collect()
+    }
+}
+
+fn identifier<T: ToJson>(data: &JsonApiData<T>) -> Option<JsonApiResourceIdentifier> {
+    data.id
+        .as_ref()
+        .map(|id| JsonApiResourceIdentifier::new(T::TYPE_NAME, id.as_str()))
+}
+
+/// Removes any resources from `included` that are either part of the primary data or that have
+/// already been included.
+fn deduplicate(
+    included: Vec<JsonApiIncluded>,
+    primary: &[JsonApiResourceIdentifier]
+) -> Vec<JsonApiIncluded> {
+    let mut seen: HashSet<JsonApiResourceIdentifier> = primary.iter().cloned().collect();
+
+    included
+        .into_iter()
+        .filter(|resource| match resource.identifier() {
+            Some(identifier) => seen.insert(identifier),
+            None => true
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A resource object of any type, used for the resources in the `included` member of a compound
+/// document.
+pub struct JsonApiIncluded(Result<Value, String>);
+
+impl JsonApiIncluded {
+    /// Converts a `JsonApiData<T>` to an included resource object.
+    ///
+    /// Returns an error if `data` cannot be serialized to JSON.
+    pub fn new<T: ToJson>(data: &JsonApiData<T>) -> Result<Self, serde_json::Error> {
+        serde_json::to_value(data).map(JsonApiIncluded::from)
+    }
+
+    /// Converts a `JsonApiData<T>` to an included resource object like `new`, but keeps the
+    /// serialization error instead of returning it. Serializing the resource then fails with the
+    /// same error, so that a document with this resource is rendered as a `500` error document.
+    pub fn from_data<T: ToJson>(data: &JsonApiData<T>) -> Self {
+        JsonApiIncluded(serde_json::to_value(data).map_err(|e| e.to_string()))
+    }
+
+    /// The resource object, or `None` if the resource couldn't be serialized.
+    pub fn value(&self) -> Option<&Value> {
+        self.0.as_ref().ok()
+    }
+
+    /// The `(type, id)` pair of this resource, if present.
+    pub fn identifier(&self) -> Option<JsonApiResourceIdentifier> {
+        let value = self.value();
+        let type_name = value.and_then(|v| v.get("type")).and_then(Value::as_str);
+        let id = value.and_then(|v| v.get("id")).and_then(Value::as_str);
+
+        match (type_name, id) {
+            (Some(type_name), Some(id)) => Some(JsonApiResourceIdentifier::new(type_name, id)),
+            _ => None
+        }
+    }
+
+    /// Sets the `self` link of this resource to `{base_url}/{type}/{id}`, unless the resource
+    /// already has one or doesn't have an id.
+    pub fn set_self_link(&mut self, base_url: &str) {
+        let url = match self.identifier() {
+            Some(identifier) => resource_url(base_url, &identifier.type_name, &identifier.id),
+            None => return
+        };
+
+        if let Ok(Value::Object(ref mut resource)) = self.0 {
+            let links = resource
+                .entry("links")
+                .or_insert_with(|| Value::Object(Map::new()));
+
+            if let Value::Object(ref mut links) = *links {
+                links.entry("self").or_insert_with(|| Value::String(url));
+            }
+        }
+    }
+}
+
+/// Converts a resource object of any type to an included resource.
+impl From<Value> for JsonApiIncluded {
+    fn from(value: Value) -> Self {
+        JsonApiIncluded(Ok(value))
+    }
+}
+
+impl Serialize for JsonApiIncluded {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Ok(ref value) => value.serialize(serializer),
+            Err(ref error) => Err(S::Error::custom(error))
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for JsonApiIncluded {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Value::deserialize(deserializer).map(JsonApiIncluded::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dispatch::JsonApiResponse;
+
+    fn included(type_name: &str, id: &str) -> JsonApiIncluded {
+        let mut map = serde_json::Map::new();
+        map.insert("type".to_string(), Value::String(type_name.to_string()));
+        map.insert("id".to_string(), Value::String(id.to_string()));
+        JsonApiIncluded::from(Value::Object(map))
+    }
+
+    #[test]
+    fn deduplicate_included_resources() {
+        let resources = vec![
+            included("people", "1"),
+            included("people", "2"),
+            included("people", "1"),
+            included("tags", "1")
+        ];
+
+        let expected = vec![
+            included("people", "1"),
+            included("people", "2"),
+            included("tags", "1")
+        ];
+
+        assert_eq!(expected, deduplicate(resources, &[]));
+    }
+
+    #[test]
+    fn deduplicate_removes_primary_data() {
+        let resources = vec![included("articles", "1"), included("people", "1")];
+        let primary = vec![JsonApiResourceIdentifier::new("articles", "1")];
+
+        assert_eq!(vec![included("people", "1")], deduplicate(resources, &primary));
+    }
+
+    #[test]
+    fn self_link_keeps_other_links() {
+        let mut container = JsonApiContainer::new(());
+        container.links = Some(JsonApiLinks {
+            next: Some("http://localhost/foos?page%5Bnumber%5D=2".to_string()),
+            ..Default::default()
+        });
+
+        let links = container
+            .with_self_link("http://localhost/foos".to_string())
+            .links
+            .expect("expected links");
+
+        assert_eq!(Some("http://localhost/foos".to_string()), links.self_link);
+        assert_eq!(Some("http://localhost/foos?page%5Bnumber%5D=2".to_string()), links.next);
+    }
+
+    #[test]
+    fn unserializable_included_resource_is_a_server_error() {
+        let mut container = JsonApiContainer::new(());
+        container.included = vec![JsonApiIncluded(Err("key must be a string".to_string()))];
+
+        let response = JsonApiResponse::ok(&container);
+
+        assert_eq!(500, response.status);
+        assert!(response.body.unwrap_or_default().contains("key must be a string"));
+    }
+
+    #[test]
+    fn set_self_link_of_included_resource() {
+        let mut resource = included("people", "1");
+        resource.set_self_link("http://localhost/");
+
+        assert_eq!(
+            Some("http://localhost/people/1"),
+            resource.value().and_then(|v| v.pointer("/links/self")).and_then(Value::as_str)
+        );
+    }
 }
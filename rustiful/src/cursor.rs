@@ -0,0 +1,255 @@
+extern crate serde_json;
+
+use errors::QueryStringParseError;
+use filter::FilterOperator;
+use params::SortOrder;
+use resource::JsonApiResource;
+use serde::de::DeserializeOwned;
+use serde::ser::Serialize;
+use std::fmt::Write;
+use std::str;
+use std::str::FromStr;
+use to_json::ToJson;
+
+/// A single value in a `Cursor`.
+pub type CursorValue = serde_json::Value;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+/// The direction to page in from a cursor.
+pub enum CursorDirection {
+    /// `page[after]`, i.e the resources that come after the cursor in the sort order.
+    After,
+    /// `page[before]`, i.e the resources that come before the cursor in the sort order.
+    Before
+}
+
+impl CursorDirection {
+    /// The operator that selects the values on the side of a cursor that this direction points to.
+    ///
+    /// Paging forwards over an ascending sort field selects the greater values (`Gt`), as does
+    /// paging backwards over a descending sort field. Otherwise the smaller values (`Lt`) are
+    /// selected.
+    pub fn operator(&self, order: SortOrder) -> FilterOperator {
+        match (*self, order) {
+            (CursorDirection::After, SortOrder::Asc) |
+            (CursorDirection::Before, SortOrder::Desc) => FilterOperator::Gt,
+            _ => FilterOperator::Lt
+        }
+    }
+
+    fn key(&self) -> &'static str {
+        match *self {
+            CursorDirection::After => "page[after]",
+            CursorDirection::Before => "page[before]"
+        }
+    }
+}
+
+/// A sort field that can be used to create a `Cursor`. This is implemented for the `sort` enum
+/// that is generated when deriving `JsonApi`.
+pub trait CursorField<T: JsonApiResource> {
+    /// The value of this sort field on `resource`.
+    fn cursor_value(&self, resource: &T) -> CursorValue;
+
+    /// The sort order of this sort field.
+    fn cursor_order(&self) -> SortOrder;
+
+    /// Whether `value` can be converted to the type of this field. A `Null` value is accepted by
+    /// optional fields.
+    fn is_cursor_value(&self, value: &CursorValue) -> bool;
+}
+
+#[derive(Debug, PartialEq, Clone)]
+/// One key of the keyset of a cursor, see `Cursor::keys`.
+pub struct CursorKey<S> {
+    /// The sort field of this key, or `None` for the id of the resource, which is the last key.
+    pub field: Option<S>,
+    /// The value of the key in the cursor, which can be converted to the type of the field with
+    /// `Cursor::from_value`. This is `Null` if the value of an optional field is `None`.
+    pub value: CursorValue,
+    /// `Gt` or `Lt`, i.e the comparison that selects the resources in the direction of the page.
+    pub operator: FilterOperator
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// An opaque position in a sorted collection, used in the `page[after]` and `page[before]` query
+/// parameters.
+///
+/// A cursor holds the values of all sort fields of a resource, followed by the id of the
+/// resource so that resources with the same sort values can be told apart.
+pub struct Cursor(Vec<CursorValue>);
+
+impl Cursor {
+    /// Creates a cursor pointing at `resource`, for the sort fields in `sort`.
+    pub fn new<T, S>(resource: &T, sort: &[S]) -> Self
+    where
+        T: ToJson + JsonApiResource,
+        S: CursorField<T>
+    {
+        let mut values: Vec<_> = sort.iter().map(|s| s.cursor_value(resource)).collect();
+        values.push(CursorValue::String(resource.id()));
+        Cursor(values)
+    }
+
+    /// Creates the cursors of the first and the last resource in `resources`, or `None` if
+    /// `resources` is empty. See `JsonApiPage::with_cursors`.
+    pub fn bounds<T, S>(resources: &[T], sort: &[S]) -> Option<(Self, Self)>
+    where
+        T: ToJson + JsonApiResource,
+        S: CursorField<T>
+    {
+        match (resources.first(), resources.last()) {
+            (Some(first), Some(last)) => Some((Cursor::new(first, sort), Cursor::new(last, sort))),
+            _ => None
+        }
+    }
+
+    /// Converts `value` to a `CursorValue`, or `CursorValue::Null` if it can't be converted.
+    pub fn to_value<V: Serialize>(value: &V) -> CursorValue {
+        serde_json::to_value(value).unwrap_or(CursorValue::Null)
+    }
+
+    /// Converts a `CursorValue` to `V`, if possible.
+    pub fn from_value<V: DeserializeOwned>(value: &CursorValue) -> Option<V> {
+        serde_json::from_value(value.clone()).ok()
+    }
+
+    /// The values of the sort fields, followed by the id.
+    pub fn values(&self) -> &[CursorValue] {
+        &self.0
+    }
+
+    /// The keys of this cursor in sort order, i.e one key per sort field in `sort`, followed by
+    /// the id of the resource.
+    ///
+    /// The resources of the page in `direction` are the ones that compare with `operator` to the
+    /// value of a key, and are equal to the values of all keys before it. For the keys `a`, `b`
+    /// and `id` that is `(a > x) OR (a = x AND b > y) OR (a = x AND b = y AND id > z)`, where the
+    /// operators depend on the sort order and the direction. For this to select every resource
+    /// exactly once, the resources must be sorted by `id` (ascending) after the sort fields.
+    ///
+    /// A `Null` value stands for an optional field that is `None`, and it's up to the service to
+    /// compare it, e.g by ordering nulls first like `Option` does.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidPageValue` if this cursor wasn't created for the sort fields in `sort`.
+    pub fn keys<T, S>(
+        &self,
+        sort: &[S],
+        direction: CursorDirection
+    ) -> Result<Vec<CursorKey<S>>, QueryStringParseError>
+    where
+        T: JsonApiResource,
+        S: CursorField<T> + Clone
+    {
+        let invalid = || QueryStringParseError::InvalidPageValue(direction.key().to_string());
+
+        if self.0.len() != sort.len() + 1 {
+            return Err(invalid());
+        }
+
+        let mut keys = Vec::with_capacity(self.0.len());
+        for (s, value) in sort.iter().zip(&self.0) {
+            if !s.is_cursor_value(value) {
+                return Err(invalid());
+            }
+
+            keys.push(CursorKey {
+                field: Some(s.clone()),
+                value: value.clone(),
+                operator: direction.operator(s.cursor_order())
+            });
+        }
+
+        self.id::<T>(direction)?;
+        keys.push(CursorKey {
+            field: None,
+            value: self.0[sort.len()].clone(),
+            operator: direction.operator(SortOrder::Asc)
+        });
+
+        Ok(keys)
+    }
+
+    /// The id of the resource that this cursor points at.
+    ///
+    /// # Errors
+    ///
+    /// `InvalidPageValue` if the id can't be converted to `T::JsonApiIdType`.
+    pub fn id<T: JsonApiResource>(
+        &self,
+        direction: CursorDirection
+    ) -> Result<T::JsonApiIdType, QueryStringParseError> {
+        self.0
+            .last()
+            .and_then(CursorValue::as_str)
+            .and_then(|id| T::JsonApiIdType::from_str(id).ok())
+            .ok_or_else(|| QueryStringParseError::InvalidPageValue(direction.key().to_string()))
+    }
+
+    /// Encodes this cursor as an opaque string, which can be used in a query string.
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_string(&self.0).unwrap_or_else(|_| "[]".to_string());
+
+        let mut encoded = String::with_capacity(json.len() * 2);
+        for byte in json.as_bytes() {
+            let _ = write!(encoded, "{:02x}", byte);
+        }
+
+        encoded
+    }
+}
+
+/// Decodes a cursor that was encoded with `Cursor::encode`.
+impl FromStr for Cursor {
+    type Err = ();
+
+    fn from_str(encoded: &str) -> Result<Self, Self::Err> {
+        if encoded.len() % 2 != 0 {
+            return Err(());
+        }
+
+        let bytes: Option<Vec<u8>> = encoded
+            .as_bytes()
+            .chunks(2)
+            .map(|pair| {
+                str::from_utf8(pair)
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+            })
+            .collect();
+
+        match bytes {
+            Some(bytes) => serde_json::from_slice(&bytes).map(Cursor).map_err(|_| ()),
+            None => Err(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_and_decode_cursor() {
+        let cursor = Cursor(vec![CursorValue::from(5), CursorValue::from("foo")]);
+        assert_eq!(Ok(cursor.clone()), Cursor::from_str(&cursor.encode()));
+    }
+
+    #[test]
+    fn decode_invalid_cursor() {
+        assert_eq!(Err(()), Cursor::from_str("abc"));
+        assert_eq!(Err(()), Cursor::from_str("zz"));
+    }
+
+    #[test]
+    fn operator_for_direction() {
+        let after = CursorDirection::After;
+        let before = CursorDirection::Before;
+        assert_eq!(FilterOperator::Gt, after.operator(SortOrder::Asc));
+        assert_eq!(FilterOperator::Lt, after.operator(SortOrder::Desc));
+        assert_eq!(FilterOperator::Lt, before.operator(SortOrder::Asc));
+        assert_eq!(FilterOperator::Gt, before.operator(SortOrder::Desc));
+    }
+}
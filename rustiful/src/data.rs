@@ -1,8 +1,20 @@
+use links::JsonApiLinks;
+use links::resource_url;
+use meta::JsonApiMeta;
 use params::JsonApiParams;
 use resource::JsonApiResource;
 use std::marker::PhantomData;
 use to_json::ToJson;
 
+/// The params of a resource, i.e
+/// `JsonApiParams<T::FieldSetField, T::SortField, T::IncludeField, T::FilterField>`.
+type ResourceParams<T> = JsonApiParams<
+    <T as JsonApiResource>::FieldSetField,
+    <T as JsonApiResource>::SortField,
+    <T as JsonApiResource>::IncludeField,
+    <T as JsonApiResource>::FilterField
+>;
+
 #[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
 /// The JSONAPI representation of a resource.
 pub struct JsonApiData<T>
@@ -18,7 +30,18 @@ where
     /// The type name of the JSONAPI resource, equivalent to the resource name.
     _type: PhantomData<T>,
     /// The attribute type of the JSONAPI resource.
-    pub attributes: T::Attrs
+    pub attributes: T::Attrs,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The relationships of the JSONAPI resource. This is `None` when converting a resource that
+    /// has no fields with the `JsonApiRelationship` attribute.
+    pub relationships: Option<T::Relationships>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The links of the JSONAPI resource, i.e the `self` link of the resource when it is sent in a
+    /// response.
+    pub links: Option<JsonApiLinks>,
+    #[serde(default, skip_serializing_if = "JsonApiMeta::is_empty")]
+    /// Meta information about the JSONAPI resource.
+    pub meta: JsonApiMeta
 }
 
 impl<T> JsonApiData<T>
@@ -30,10 +53,33 @@ where
         JsonApiData {
             id: id.map(|i| i.into()),
             _type: PhantomData,
-            attributes: attrs
+            attributes: attrs,
+            relationships: None,
+            links: None,
+            meta: JsonApiMeta::new()
         }
     }
 
+    /// Sets the relationships of this resource.
+    pub fn with_relationships(mut self, relationships: T::Relationships) -> JsonApiData<T> {
+        self.relationships = Some(relationships);
+        self
+    }
+
+    /// Sets the `self` link of this resource to `{base_url}/{type}/{id}`, e.g
+    /// `http://localhost:3000/my-resources/1`. Nothing is set if there is no id present.
+    pub fn with_self_link(mut self, base_url: &str) -> JsonApiData<T> {
+        self.set_self_links(base_url);
+        self
+    }
+
+    /// Adds meta information about this resource. Any existing members with the same names are
+    /// replaced. A struct can be converted to a `JsonApiMeta` with `to_meta`.
+    pub fn with_meta(mut self, meta: JsonApiMeta) -> JsonApiData<T> {
+        self.meta.extend(meta);
+        self
+    }
+
     /// Check if there is an id present.
     pub fn has_id(&self) -> bool {
         self.id.is_some()
@@ -49,7 +95,43 @@ where
         JsonApiData {
             id: self.id.clone(),
             _type: self._type.clone(),
-            attributes: self.attributes.clone()
+            attributes: self.attributes.clone(),
+            relationships: self.relationships.clone(),
+            links: self.links.clone(),
+            meta: self.meta.clone()
+        }
+    }
+}
+
+/// Sets the `self` links of resource objects, relative to a base URL. This is implemented for the
+/// types that can be the primary data of a `JsonApiContainer`.
+pub trait SelfLinks {
+    fn set_self_links(&mut self, base_url: &str);
+}
+
+impl<T> SelfLinks for JsonApiData<T>
+where
+    T: ToJson,
+    T::Attrs: Clone
+{
+    fn set_self_links(&mut self, base_url: &str) {
+        if let Some(ref id) = self.id {
+            let url = resource_url(base_url, T::TYPE_NAME, id);
+            let mut links = self.links.take().unwrap_or_default();
+            links.self_link = Some(url);
+            self.links = Some(links);
+        }
+    }
+}
+
+impl<T> SelfLinks for Vec<JsonApiData<T>>
+where
+    T: ToJson,
+    T::Attrs: Clone
+{
+    fn set_self_links(&mut self, base_url: &str) {
+        for data in self.iter_mut() {
+            data.set_self_links(base_url);
         }
     }
 }
@@ -63,6 +145,9 @@ where
 /// * `model` - The resource to convert to its JSONAPI representation.
 /// * `params` - Filters out fields that should not be serialized when sending to the client.
 ///
+/// Any fields with the `JsonApiRelationship` attribute are converted to resource linkage in the
+/// `relationships` object.
+///
 /// # Example
 ///
 /// Given a resource that implements `ToJson` (this is automatically implemented when
@@ -118,19 +203,28 @@ where
 /// let resource_list: JsonApiData<MyResource> = (resource, &Default::default()).into();
 /// # }
 /// ```
-impl<'a, T> From<(T, &'a JsonApiParams<T::FilterField, T::SortField>)> for JsonApiData<T>
+impl<'a, T> From<(T, &'a ResourceParams<T>)> for JsonApiData<T>
 where
     T: ToJson + JsonApiResource,
-    T::Attrs: From<(T, &'a JsonApiParams<T::FilterField, T::SortField>)>
+    T::Attrs: From<(T, &'a ResourceParams<T>)>,
+    T::Relationships: for<'b> From<(&'b T, &'a ResourceParams<T>)>
 {
-    fn from((model, params): (T, &'a JsonApiParams<T::FilterField, T::SortField>)) -> Self {
-        JsonApiData::new(Some(model.id()), T::Attrs::from((model, params)))
+    fn from((model, params): (T, &'a ResourceParams<T>)) -> Self {
+        let relationships = if T::RELATIONSHIP_NAMES.is_empty() {
+            None
+        } else {
+            Some(T::Relationships::from((&model, params)))
+        };
+
+        let mut data = JsonApiData::new(Some(model.id()), T::Attrs::from((model, params)));
+        data.relationships = relationships;
+        data
     }
 }
 
 /// Converts `Self` into `T`. See the implementations to see what the conversions are intended for.
-pub trait IntoJson<T, F, S> {
-    fn into_json<'a>(self, params: &'a JsonApiParams<F, S>) -> T;
+pub trait IntoJson<T, F, S, I, Fl> {
+    fn into_json<'a>(self, params: &'a JsonApiParams<F, S, I, Fl>) -> T;
 }
 
 /// Converts `T` into `JsonApiData<T>` for any `T` that implements `ToJson`.
@@ -191,14 +285,16 @@ pub trait IntoJson<T, F, S> {
 /// let resource_list = resource.into_json(&Default::default());
 /// # }
 /// ```
-impl<T> IntoJson<JsonApiData<T>, T::FilterField, T::SortField> for T
+impl<T> IntoJson<JsonApiData<T>, T::FieldSetField, T::SortField, T::IncludeField, T::FilterField>
+    for T
 where
     T: ToJson + JsonApiResource,
-    T::Attrs: for<'b> From<(T, &'b JsonApiParams<T::FilterField, T::SortField>)>
+    T::Attrs: for<'b> From<(T, &'b ResourceParams<T>)>,
+    T::Relationships: for<'b, 'c> From<(&'c T, &'b ResourceParams<T>)>
 {
     fn into_json<'a>(
         self,
-        params: &'a JsonApiParams<T::FilterField, T::SortField>
+        params: &'a ResourceParams<T>
     ) -> JsonApiData<T> {
         (self, params).into()
     }
@@ -263,14 +359,17 @@ where
 /// let resource_list: Vec<JsonApiData<MyResource>> = vec![resource].into_json(&Default::default());
 /// # }
 /// ```
-impl<T> IntoJson<Vec<JsonApiData<T>>, T::FilterField, T::SortField> for Vec<T>
+impl<T>
+    IntoJson<Vec<JsonApiData<T>>, T::FieldSetField, T::SortField, T::IncludeField, T::FilterField>
+    for Vec<T>
 where
     T: ToJson + JsonApiResource,
-    T::Attrs: for<'b> From<(T, &'b JsonApiParams<T::FilterField, T::SortField>)>
+    T::Attrs: for<'b> From<(T, &'b ResourceParams<T>)>,
+    T::Relationships: for<'b, 'c> From<(&'c T, &'b ResourceParams<T>)>
 {
     fn into_json<'a>(
         self,
-        params: &'a JsonApiParams<T::FilterField, T::SortField>
+        params: &'a ResourceParams<T>
     ) -> Vec<JsonApiData<T>> {
         self.into_iter().map(|i| (i, params).into()).collect()
     }
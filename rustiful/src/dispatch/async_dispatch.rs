@@ -0,0 +1,312 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_document;
+use super::parse_id;
+use super::parse_params;
+use async_service::AsyncJsonDelete;
+use async_service::AsyncJsonGet;
+use async_service::AsyncJsonIndex;
+use async_service::AsyncJsonPatch;
+use async_service::AsyncJsonPost;
+use async_service::JsonApiFuture;
+use container::JsonApiContainer;
+use data::JsonApiData;
+use errors::RequestError;
+use links::resource_url;
+use page::Page;
+use page::PageParams;
+use service::HttpStatus;
+use std::error::Error;
+use std::future::Future;
+use std::future::ready;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::task::Context;
+use std::task::Poll;
+use to_json::ToJson;
+
+/// The response of an async dispatcher, such as `AsyncGetDispatcher`.
+pub type JsonApiResponseFuture = Pin<Box<Future<Output = JsonApiResponse> + Send>>;
+
+impl From<JsonApiResponse> for JsonApiResponseFuture {
+    fn from(response: JsonApiResponse) -> Self {
+        Box::pin(ready(response))
+    }
+}
+
+/// Renders the result of an async service trait with `render` once it's ready.
+struct Render<T, E, S, F> {
+    future: JsonApiFuture<T, E, S>,
+    render: Option<F>
+}
+
+/// None of the fields are pinned, the future is boxed instead.
+impl<T, E, S, F> Unpin for Render<T, E, S, F> {}
+
+impl<T, E, S, F> Future for Render<T, E, S, F>
+where
+    F: FnOnce(Result<T, (E, S)>) -> JsonApiResponse
+{
+    type Output = JsonApiResponse;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<JsonApiResponse> {
+        let this = self.get_mut();
+
+        match this.future.as_mut().poll(cx) {
+            Poll::Ready(result) => {
+                let render = this.render.take().expect("Render polled after completion!");
+                Poll::Ready(render(result))
+            }
+            Poll::Pending => Poll::Pending
+        }
+    }
+}
+
+fn render<T, E, S, F>(future: JsonApiFuture<T, E, S>, render: F) -> JsonApiResponseFuture
+where
+    T: 'static,
+    E: 'static,
+    S: 'static,
+    F: FnOnce(Result<T, (E, S)>) -> JsonApiResponse + Send + 'static
+{
+    Box::pin(Render {
+        future: future,
+        render: Some(render)
+    })
+}
+
+/// Dispatches `GET /{resource}/{id}` to `AsyncJsonGet::find`. The request is parsed in the same
+/// way as in `GetDispatcher`.
+pub trait AsyncGetDispatcher
+where
+    Self: AsyncJsonGet + 'static
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponseFuture
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::AsyncContext, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let base_url = req.base_url.clone();
+        let url = req.document_url();
+
+        render(<Self as AsyncJsonGet>::find(id, params, ctx), move |result| match result {
+            Ok(Some(result)) => {
+                let container = result.with_resource_links(&base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Ok(None) => JsonApiResponse::error(&RequestError::NotFound, 404),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        })
+    }
+}
+
+impl<T: AsyncJsonGet + 'static> AsyncGetDispatcher for T {}
+
+/// Dispatches `GET /{resource}` to `AsyncJsonIndex::find_all`. The request is parsed in the same
+/// way as in `IndexDispatcher`.
+pub trait AsyncIndexDispatcher
+where
+    Self: AsyncJsonIndex + 'static
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponseFuture
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::AsyncContext, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let mut params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let page = params.page_mut().take();
+        let page = match Page::resolve(page, Self::DEFAULT_PAGE_SIZE, Self::MAX_PAGE_SIZE) {
+            Ok(page) => page,
+            Err(e) => return JsonApiResponse::error(&e, 400).into()
+        };
+        *params.page_mut() = page;
+
+        let page = params.page().cloned();
+        let base_url = req.base_url.clone();
+        let url = req.document_url();
+
+        render(<Self as AsyncJsonIndex>::find_all(params, ctx), move |result| match result {
+            Ok(result) => {
+                let container = result
+                    .into_container(&url, page.as_ref())
+                    .with_resource_links(&base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        })
+    }
+}
+
+impl<T: AsyncJsonIndex + 'static> AsyncIndexDispatcher for T {}
+
+/// Dispatches `POST /{resource}` to `AsyncJsonPost::create`. The request is parsed in the same
+/// way as in `PostDispatcher`.
+pub trait AsyncPostDispatcher
+where
+    Self: AsyncJsonPost + 'static
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponseFuture
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::AsyncContext, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiData<Self>>>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let base_url = req.base_url.clone();
+        // Without a client-generated id, the client can only learn the id from the response.
+        let has_client_id = json.data.id.is_some();
+
+        render(<Self as AsyncJsonPost>::create(json.data, params, ctx), move |result| {
+            match result {
+                Ok(Some(container)) => {
+                    // The self link of the document of a created resource is the resource itself.
+                    let url = container
+                        .data
+                        .id
+                        .as_ref()
+                        .map(|id| resource_url(&base_url, Self::TYPE_NAME, id));
+                    let container = container.with_resource_links(&base_url);
+
+                    let container = match url {
+                        Some(ref url) => container.with_self_link(url.clone()),
+                        None => container
+                    };
+
+                    JsonApiResponse::created(&container, url)
+                }
+                Ok(None) if has_client_id => JsonApiResponse::no_content(),
+                Ok(None) => JsonApiResponse::error(&RequestError::MissingCreatedResource, 500),
+                Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+            }
+        })
+    }
+}
+
+impl<T: AsyncJsonPost + 'static> AsyncPostDispatcher for T {}
+
+/// Dispatches `PATCH /{resource}/{id}` to `AsyncJsonPatch::update`. The request is parsed in the
+/// same way as in `PatchDispatcher`.
+pub trait AsyncPatchDispatcher
+where
+    Self: AsyncJsonPatch + 'static
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponseFuture
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::AsyncContext, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiData<Self>>>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let base_url = req.base_url.clone();
+        let url = req.document_url();
+        let future = <Self as AsyncJsonPatch>::update(id, json.data, params, ctx);
+
+        render(future, move |result| match result {
+            Ok(container) => {
+                let container = container.with_resource_links(&base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        })
+    }
+}
+
+impl<T: AsyncJsonPatch + 'static> AsyncPatchDispatcher for T {}
+
+/// Dispatches `DELETE /{resource}/{id}` to `AsyncJsonDelete::delete`. The request is parsed in
+/// the same way as in `DeleteDispatcher`.
+pub trait AsyncDeleteDispatcher
+where
+    Self: AsyncJsonDelete + 'static
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponseFuture
+    where
+        F: FnOnce() -> Result<Self::AsyncContext, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response.into()
+        };
+
+        render(<Self as AsyncJsonDelete>::delete(id, ctx), |result| match result {
+            Ok(_) => JsonApiResponse::no_content(),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        })
+    }
+}
+
+impl<T: AsyncJsonDelete + 'static> AsyncDeleteDispatcher for T {}
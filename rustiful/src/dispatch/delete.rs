@@ -0,0 +1,39 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_id;
+use service::HttpStatus;
+use service::JsonDelete;
+use std::error::Error;
+use std::str::FromStr;
+
+/// Dispatches `DELETE /{resource}/{id}` to `JsonDelete::delete`.
+pub trait DeleteDispatcher
+where
+    Self: JsonDelete
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::delete(id, ctx) {
+            Ok(_) => JsonApiResponse::no_content(),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonDelete> DeleteDispatcher for T {}
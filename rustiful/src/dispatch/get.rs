@@ -0,0 +1,52 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_id;
+use super::parse_params;
+use errors::RequestError;
+use service::HttpStatus;
+use service::JsonGet;
+use std::error::Error;
+use std::str::FromStr;
+use to_json::ToJson;
+
+/// Dispatches `GET /{resource}/{id}` to `JsonGet::find`.
+pub trait GetDispatcher
+where
+    Self: JsonGet
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::find(id, &params, ctx) {
+            Ok(Some(result)) => {
+                let container = result.with_resource_links(&req.base_url);
+                JsonApiResponse::ok(&container.with_self_link(req.document_url()))
+            }
+            Ok(None) => JsonApiResponse::error(&RequestError::NotFound, 404),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonGet> GetDispatcher for T {}
@@ -0,0 +1,55 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_params;
+use page::Page;
+use page::PageParams;
+use service::HttpStatus;
+use service::JsonIndex;
+use std::error::Error;
+use to_json::ToJson;
+
+/// Dispatches `GET /{resource}` to `JsonIndex::find_all`.
+pub trait IndexDispatcher
+where
+    Self: JsonIndex
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let mut params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let page = params.page_mut().take();
+        let page = match Page::resolve(page, Self::DEFAULT_PAGE_SIZE, Self::MAX_PAGE_SIZE) {
+            Ok(page) => page,
+            Err(e) => return JsonApiResponse::error(&e, 400)
+        };
+        *params.page_mut() = page;
+
+        let url = req.document_url();
+
+        match Self::find_all(&params, ctx) {
+            Ok(result) => {
+                let container = result
+                    .into_container(&url, params.page())
+                    .with_resource_links(&req.base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonIndex> IndexDispatcher for T {}
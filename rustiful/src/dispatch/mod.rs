@@ -0,0 +1,102 @@
+//! The framework-agnostic handling of requests to the endpoints of a resource.
+//!
+//! Each endpoint has a dispatcher trait, such as `GetDispatcher`, with a blanket impl for all types
+//! that implement the corresponding service trait. A dispatcher takes a `JsonApiRequest`, and
+//! creates the context of the service trait, parses the id, the query string and the request
+//! document, calls the service trait and renders its result as a `JsonApiResponse`.
+//!
+//! The context is created by a closure that is passed to the dispatcher, so that it can be created
+//! from the request type of any framework. The closure is always called before the id and the
+//! query string are parsed. For requests without a body, i.e `GET` and `DELETE /{resource}/{id}`,
+//! it's the first thing a dispatcher does. For requests with a body, i.e `POST`, `PATCH`, the
+//! `DELETE` of relationship members and Atomic Operations, the request document is parsed first,
+//! so that a malformed body is rejected without creating a context. The async dispatchers, such as
+//! `AsyncGetDispatcher`, follow the same order.
+
+extern crate serde_json;
+
+mod request;
+pub use self::request::*;
+
+mod response;
+pub use self::response::*;
+
+mod negotiation;
+pub use self::negotiation::*;
+
+mod operations;
+pub use self::operations::*;
+
+mod get;
+mod post;
+mod index;
+mod patch;
+mod delete;
+mod relationship;
+mod related;
+mod async_dispatch;
+
+pub use self::async_dispatch::*;
+pub use self::delete::*;
+pub use self::get::*;
+pub use self::index::*;
+pub use self::patch::*;
+pub use self::post::*;
+pub use self::related::*;
+pub use self::relationship::*;
+
+use errors::BodyParseError;
+use errors::ContextError;
+use errors::IdParseError;
+use errors::QueryStringParseError;
+use errors::RequestError;
+use resource::JsonApiResource;
+use serde::de::DeserializeOwned;
+use service::HttpStatus;
+use std::error::Error;
+use std::str::FromStr;
+
+static JSON_API_MEDIA_TYPE: &'static str = "application/vnd.api+json";
+
+/// Creates the context of a request with `context`, or renders its error.
+fn create_context<C, F, E, S>(context: F) -> Result<C, JsonApiResponse>
+where
+    F: FnOnce() -> Result<C, (E, S)>,
+    E: Error + Send,
+    S: HttpStatus
+{
+    context().map_err(|(e, status)| {
+        JsonApiResponse::error(&ContextError(e), status.status_code())
+    })
+}
+
+/// The `id` parameter of the route. A request without one was routed to the wrong dispatcher by
+/// the adapter, which is a `500 Internal Server Error` rather than a client error.
+fn parse_id<T>(req: &JsonApiRequest) -> Result<T::JsonApiIdType, JsonApiResponse>
+where
+    T: JsonApiResource,
+    <T::JsonApiIdType as FromStr>::Err: Error
+{
+    let id = match req.param("id") {
+        Some(id) => id,
+        None => return Err(JsonApiResponse::error(&RequestError::MissingRouteParam, 500))
+    };
+
+    T::JsonApiIdType::from_str(id).map_err(|e| JsonApiResponse::error(&IdParseError(e), 400))
+}
+
+fn parse_params<P>(req: &JsonApiRequest) -> Result<P, JsonApiResponse>
+where
+    P: FromStr<Err = QueryStringParseError>
+{
+    P::from_str(&req.query).map_err(|e| JsonApiResponse::error(&e, 400))
+}
+
+/// The request document. An empty body is rejected with a `400 Bad Request`.
+fn parse_document<D: DeserializeOwned>(req: &JsonApiRequest) -> Result<D, JsonApiResponse> {
+    if req.body.is_empty() {
+        return Err(JsonApiResponse::error(&RequestError::NoBody, 400));
+    }
+
+    serde_json::from_slice(&req.body).map_err(|e| JsonApiResponse::error(&BodyParseError(e), 400))
+}
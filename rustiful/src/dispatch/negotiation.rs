@@ -0,0 +1,339 @@
+use super::JSON_API_MEDIA_TYPE;
+use super::JsonApiRequest;
+use errors::RequestError;
+
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+/// The `ext` and `profile` media type parameters that apply to a request, i.e the extensions and
+/// profiles that were both requested by the client and declared by the server.
+///
+/// Each adapter negotiates this with `ContentNegotiation` and stores it in
+/// `JsonApiRequest::media_type`, so a dispatcher can look up the extensions that apply. The same
+/// parameters are sent in the `Content-Type` of the response, see
+/// `JsonApiResponse::with_media_type`.
+pub struct JsonApiMediaType {
+    /// The URIs of the extensions that apply.
+    pub ext: Vec<String>,
+    /// The URIs of the profiles that apply.
+    pub profile: Vec<String>
+}
+
+impl JsonApiMediaType {
+    /// The full media type, e.g `application/vnd.api+json; ext="https://example.com/ext"`.
+    pub fn to_header_value(&self) -> String {
+        let mut value = JSON_API_MEDIA_TYPE.to_string();
+
+        if !self.ext.is_empty() {
+            value.push_str(&format!("; ext=\"{}\"", self.ext.join(" ")));
+        }
+
+        if !self.profile.is_empty() {
+            value.push_str(&format!("; profile=\"{}\"", self.profile.join(" ")));
+        }
+
+        value
+    }
+
+    /// Whether neither extensions nor profiles apply, i.e whether the media type has no
+    /// parameters.
+    pub fn is_empty(&self) -> bool {
+        self.ext.is_empty() && self.profile.is_empty()
+    }
+
+    /// Parses a single media type. Returns `None` if it isn't the JSONAPI media type, and
+    /// `Some(Err(()))` if it has any parameters other than `ext` and `profile`. The `q` parameter
+    /// of an `Accept` header, and any parameters after it, should be removed beforehand.
+    fn parse(media_type: &str) -> Option<Result<Self, ()>> {
+        let mut parts = split_unquoted(media_type, ';').into_iter();
+
+        match parts.next() {
+            Some(essence) if essence.trim().to_lowercase() == JSON_API_MEDIA_TYPE => (),
+            _ => return None
+        }
+
+        let mut result = JsonApiMediaType::default();
+
+        for param in parts {
+            let mut pair = param.splitn(2, '=');
+            let name = pair.next().unwrap_or("").trim().to_lowercase();
+            let uris = pair.next()
+                .unwrap_or("")
+                .trim()
+                .trim_matches('"')
+                .split_whitespace()
+                .map(String::from);
+
+            match name.as_str() {
+                "ext" => result.ext.extend(uris),
+                "profile" => result.profile.extend(uris),
+                _ => return Some(Err(()))
+            }
+        }
+
+        Some(Ok(result))
+    }
+}
+
+/// Enforces the content negotiation rules of the JSONAPI spec, including the `ext` and `profile`
+/// media type parameters. Every adapter negotiates each request with this before it's routed.
+///
+/// * A request with a body must have the `Content-Type` `application/vnd.api+json`. The only media
+/// type parameters allowed are `ext`, with extensions that are supported, and `profile`. Otherwise
+/// `415 Unsupported Media Type` is returned.
+/// * If the `Accept` header contains the JSONAPI media type, at least one of its instances must
+/// follow the same rules. Otherwise `406 Not Acceptable` is returned.
+///
+/// The requested extensions and the requested profiles that are supported are sent in the
+/// `Content-Type` of the response. See `JsonApiMediaType`.
+#[derive(Debug, Default, Clone)]
+pub struct ContentNegotiation {
+    extensions: Vec<String>,
+    profiles: Vec<String>
+}
+
+impl ContentNegotiation {
+    /// Constructs a `ContentNegotiation` that supports no extensions or profiles.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Declares support for the extension with the URI `uri`.
+    pub fn add_extension<S: Into<String>>(&mut self, uri: S) {
+        self.extensions.push(uri.into());
+    }
+
+    /// Declares support for the profile with the URI `uri`.
+    pub fn add_profile<S: Into<String>>(&mut self, uri: S) {
+        self.profiles.push(uri.into());
+    }
+
+    /// The extensions and profiles that apply to `req`. If the request doesn't follow the rules
+    /// above, this is the error and the status of the response instead, i.e `415` or `406`.
+    ///
+    /// This can be called before the body of `req` has been read, since a body is also detected
+    /// by the `Content-Length` and `Transfer-Encoding` headers.
+    pub fn negotiate(
+        &self,
+        req: &JsonApiRequest
+    ) -> Result<JsonApiMediaType, (RequestError, u16)> {
+        let mut applied = self.content_type(req).map_err(|e| (e, 415))?;
+        let accept = self.accept(req).map_err(|e| (e, 406))?;
+
+        for ext in accept.ext {
+            if !applied.ext.contains(&ext) {
+                applied.ext.push(ext);
+            }
+        }
+
+        for profile in accept.profile {
+            if !applied.profile.contains(&profile) {
+                applied.profile.push(profile);
+            }
+        }
+
+        // Unsupported profiles are ignored, as opposed to unsupported extensions.
+        applied.profile.retain(|profile| self.profiles.contains(profile));
+
+        Ok(applied)
+    }
+
+    fn supports_extensions(&self, media_type: &JsonApiMediaType) -> bool {
+        media_type.ext.iter().all(|ext| self.extensions.contains(ext))
+    }
+
+    /// The parameters of the JSONAPI media type in the `Content-Type` of the request, if any.
+    fn content_type(&self, req: &JsonApiRequest) -> Result<JsonApiMediaType, RequestError> {
+        let content_type = match header(req, "Content-Type") {
+            Some(content_type) => content_type,
+            None if has_body(req) => return Err(RequestError::UnsupportedMediaType),
+            None => return Ok(JsonApiMediaType::default())
+        };
+
+        match JsonApiMediaType::parse(&content_type) {
+            Some(Ok(ref media_type)) if self.supports_extensions(media_type) => {
+                Ok(media_type.clone())
+            }
+            Some(_) => Err(RequestError::UnsupportedMediaType),
+            // A `GET` or a `HEAD` request doesn't have a body, so its `Content-Type` is ignored.
+            None => match req.method.as_str() {
+                "GET" | "HEAD" => Ok(JsonApiMediaType::default()),
+                _ => Err(RequestError::UnsupportedMediaType)
+            }
+        }
+    }
+
+    /// The parameters of the first acceptable JSONAPI media type in the `Accept` header, if any.
+    fn accept(&self, req: &JsonApiRequest) -> Result<JsonApiMediaType, RequestError> {
+        let accept = match header(req, "Accept") {
+            Some(accept) => accept,
+            None => return Ok(JsonApiMediaType::default())
+        };
+
+        let json_api: Vec<_> = split_unquoted(&accept, ',')
+            .into_iter()
+            .filter_map(|media_type| JsonApiMediaType::parse(without_quality(media_type)))
+            .collect();
+
+        if json_api.is_empty() {
+            return Ok(JsonApiMediaType::default());
+        }
+
+        json_api
+            .into_iter()
+            .filter_map(Result::ok)
+            .find(|media_type| self.supports_extensions(media_type))
+            .ok_or(RequestError::NotAcceptable)
+    }
+}
+
+/// The value of the header `name`, with multiple lines joined by commas.
+fn header(req: &JsonApiRequest, name: &str) -> Option<String> {
+    let lines: Vec<_> = req.headers
+        .iter()
+        .filter(|&&(ref n, _)| n.eq_ignore_ascii_case(name))
+        .map(|&(_, ref value)| value.as_str())
+        .collect();
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(lines.join(","))
+    }
+}
+
+/// Whether the request has a body, even if it hasn't been read yet. A chunked body has a
+/// `Transfer-Encoding` instead of a `Content-Length`.
+fn has_body(req: &JsonApiRequest) -> bool {
+    let content_length = req.header("Content-Length")
+        .and_then(|length| length.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+
+    !req.body.is_empty() || content_length > 0 || req.header("Transfer-Encoding").is_some()
+}
+
+/// Splits `value` at each `separator` that isn't inside a quoted string.
+fn split_unquoted(value: &str, separator: char) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut quoted = false;
+    let mut start = 0;
+
+    for (i, c) in value.char_indices() {
+        if c == '"' {
+            quoted = !quoted;
+        } else if c == separator && !quoted {
+            parts.push(&value[start..i]);
+            start = i + 1;
+        }
+    }
+
+    parts.push(&value[start..]);
+    parts
+}
+
+/// Removes the `q` parameter from a media type in an `Accept` header, along with any accept
+/// extensions that follow it.
+fn without_quality(media_type: &str) -> &str {
+    let mut offset = 0;
+
+    for param in split_unquoted(media_type, ';') {
+        if offset > 0 && param.trim().to_lowercase().starts_with("q=") {
+            return &media_type[..offset - 1];
+        }
+
+        offset += param.len() + 1;
+    }
+
+    media_type
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn media_type(ext: &[&str], profile: &[&str]) -> JsonApiMediaType {
+        JsonApiMediaType {
+            ext: ext.iter().map(|s| s.to_string()).collect(),
+            profile: profile.iter().map(|s| s.to_string()).collect()
+        }
+    }
+
+    fn negotiation() -> ContentNegotiation {
+        let mut negotiation = ContentNegotiation::new();
+        negotiation.add_extension("https://a.org/ext");
+        negotiation.add_profile("https://b.org/profile");
+        negotiation
+    }
+
+    #[test]
+    fn parse_media_type_with_ext_and_profile() {
+        let parsed = JsonApiMediaType::parse(
+            r#"application/vnd.api+json; ext="https://a.org/ext https://b.org/ext"; profile=p"#
+        );
+        let expected = media_type(&["https://a.org/ext", "https://b.org/ext"], &["p"]);
+        assert_eq!(Some(Ok(expected)), parsed);
+    }
+
+    #[test]
+    fn parse_media_type_with_other_parameters() {
+        let parsed = JsonApiMediaType::parse("application/vnd.api+json; charset=utf-8");
+        assert_eq!(Some(Err(())), parsed);
+    }
+
+    #[test]
+    fn parse_other_media_type() {
+        assert_eq!(None, JsonApiMediaType::parse("application/json"));
+    }
+
+    #[test]
+    fn media_type_to_header_value() {
+        let value = media_type(&["https://a.org/ext"], &["https://b.org/profile"]);
+        assert_eq!(
+            r#"application/vnd.api+json; ext="https://a.org/ext"; profile="https://b.org/profile""#,
+            value.to_header_value()
+        );
+    }
+
+    #[test]
+    fn remove_quality_from_media_type() {
+        assert_eq!(
+            r#"application/vnd.api+json; ext="a;q=1""#,
+            without_quality(r#"application/vnd.api+json; ext="a;q=1"; q=0.5; foo"#)
+        );
+        assert_eq!("application/json", without_quality("application/json"));
+    }
+
+    #[test]
+    fn negotiate_extensions_and_profiles() {
+        let request = JsonApiRequest::new("POST", "/foos")
+            .with_header("Content-Type", r#"application/vnd.api+json; ext="https://a.org/ext""#)
+            .with_header(
+                "Accept",
+                r#"application/vnd.api+json; profile="https://b.org/profile https://c.org/p""#
+            );
+        let expected = media_type(&["https://a.org/ext"], &["https://b.org/profile"]);
+
+        assert_eq!(Some(expected), negotiation().negotiate(&request).ok());
+    }
+
+    #[test]
+    fn negotiate_unsupported_extension() {
+        let request = JsonApiRequest::new("POST", "/foos")
+            .with_header("Content-Type", r#"application/vnd.api+json; ext="https://c.org/ext""#);
+        assert_eq!(Some(415), negotiation().negotiate(&request).err().map(|e| e.1));
+
+        let request = JsonApiRequest::new("GET", "/foos")
+            .with_header("Accept", r#"application/vnd.api+json; ext="https://c.org/ext""#);
+        assert_eq!(Some(406), negotiation().negotiate(&request).err().map(|e| e.1));
+    }
+
+    #[test]
+    fn negotiate_body_without_content_type() {
+        let chunked =
+            JsonApiRequest::new("POST", "/foos").with_header("Transfer-Encoding", "chunked");
+        let sized = JsonApiRequest::new("POST", "/foos").with_header("Content-Length", "2");
+        let empty = JsonApiRequest::new("DELETE", "/foos/1").with_header("Content-Length", "0");
+
+        assert_eq!(Some(415), negotiation().negotiate(&chunked).err().map(|e| e.1));
+        assert_eq!(Some(415), negotiation().negotiate(&sized).err().map(|e| e.1));
+        assert_eq!(Some(JsonApiMediaType::default()), negotiation().negotiate(&empty).ok());
+    }
+}
@@ -0,0 +1,366 @@
+extern crate serde_json;
+
+use self::serde_json::Value;
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use container::JsonApiContainer;
+use data::JsonApiData;
+use error::JsonApiError;
+use error::JsonApiErrorArray;
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
+use errors::ContextError;
+use errors::IdParseError;
+use errors::LocalIdError;
+use errors::RequestError;
+use meta::JsonApiMeta;
+use operations::ATOMIC_EXTENSION;
+use operations::JsonApiLocalIds;
+use operations::JsonApiOperation;
+use operations::JsonApiOperationCode;
+use operations::JsonApiOperationResult;
+use operations::JsonApiOperationResults;
+use operations::JsonApiOperations;
+use operations::JsonApiTransaction;
+use resource::JsonApiResource;
+use service::HttpStatus;
+use service::JsonDelete;
+use service::JsonPatch;
+use service::JsonPost;
+use std::collections::HashMap;
+use std::error::Error;
+use std::str::FromStr;
+use to_json::ToJson;
+
+type OperationFailure = (Vec<JsonApiError>, u16);
+
+type OperationOutcome = Result<JsonApiOperationResult, OperationFailure>;
+
+type Operation<C> =
+    Box<Fn(JsonApiOperation, &C, &mut JsonApiLocalIds) -> OperationOutcome + Send + Sync>;
+
+/// The resource types and operations of an Atomic Operations endpoint. Register it with the
+/// `jsonapi_operations` method of an adapter, such as `JsonApiRouterBuilder::jsonapi_operations`.
+///
+/// Each operation is dispatched to the `JsonPost`, `JsonPatch` or `JsonDelete` impl of its
+/// resource type, so all of these impls must have the same `Context` type `C`. One context is
+/// created per request, and all operations run inside its `JsonApiTransaction::transaction`, so
+/// that a request is applied either in full or not at all. Each operation is passed a clone of
+/// the context that `transaction` hands to its closure.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut operations = OperationsHandler::<Context>::new();
+/// operations.add::<Article>();
+/// operations.update::<Article>();
+/// operations.remove::<Article>();
+/// operations.add::<Person>();
+///
+/// router.jsonapi_operations(operations);
+/// ```
+#[allow(missing_debug_implementations)]
+pub struct OperationsHandler<C> {
+    operations: HashMap<(String, JsonApiOperationCode), Operation<C>>
+}
+
+impl<C: Clone + 'static> OperationsHandler<C> {
+    pub fn new() -> Self {
+        OperationsHandler {
+            operations: HashMap::new()
+        }
+    }
+
+    /// Handles `add` operations on `T` with `JsonPost::create`. If the resource has a local id,
+    /// later operations in the same request can refer to the created resource with it.
+    pub fn add<T>(&mut self)
+    where
+        T: JsonPost<Context = C> + 'static
+    {
+        self.insert::<T>(JsonApiOperationCode::Add, Box::new(add::<T, C>));
+    }
+
+    /// Handles `update` operations on `T` with `JsonPatch::update`.
+    pub fn update<T>(&mut self)
+    where
+        T: JsonPatch<Context = C> + 'static,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.insert::<T>(JsonApiOperationCode::Update, Box::new(update::<T, C>));
+    }
+
+    /// Handles `remove` operations on `T` with `JsonDelete::delete`.
+    pub fn remove<T>(&mut self)
+    where
+        T: JsonDelete<Context = C> + 'static,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.insert::<T>(JsonApiOperationCode::Remove, Box::new(remove::<T, C>));
+    }
+
+    fn insert<T: JsonApiResource>(&mut self, code: JsonApiOperationCode, operation: Operation<C>) {
+        self.operations
+            .insert((T::RESOURCE_NAME.to_string(), code), operation);
+    }
+
+    fn apply(
+        &self,
+        operation: JsonApiOperation,
+        ctx: &C,
+        lids: &mut JsonApiLocalIds
+    ) -> OperationOutcome {
+        let key = match operation.type_name() {
+            Some(type_name) => (type_name.to_string(), operation.op),
+            None => return Err(invalid_operation())
+        };
+
+        // Operations on relationships aren't supported yet.
+        if operation.reference.as_ref().map_or(false, |r| r.relationship.is_some()) {
+            return Err(invalid_operation());
+        }
+
+        match self.operations.get(&key) {
+            Some(handler) => handler(operation, ctx, lids),
+            None => Err(invalid_operation())
+        }
+    }
+
+    /// Dispatches `POST /operations`, i.e applies all operations of the request in a transaction.
+    /// The response has the results of all operations, or the error of the first operation that
+    /// failed.
+    ///
+    /// The Atomic Operations extension must apply to the request, see
+    /// `JsonApiRequest::media_type`. Otherwise the response is `415 Unsupported Media Type`.
+    ///
+    /// The errors of a failed operation point at it, e.g `/atomic:operations/1`. An error that
+    /// points at the primary data of the operation, e.g `/data/attributes/title`, points at
+    /// `/atomic:operations/1/data/attributes/title` instead.
+    pub fn dispatch<F, E, S>(&self, req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        C: JsonApiTransaction,
+        F: FnOnce() -> Result<C, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus
+    {
+        if !req.media_type.ext.iter().any(|ext| ext == ATOMIC_EXTENSION) {
+            return JsonApiResponse::error(&RequestError::UnsupportedMediaType, 415);
+        }
+
+        if req.body.is_empty() {
+            return JsonApiResponse::error(&RequestError::NoBody, 400);
+        }
+
+        let document = match serde_json::from_slice::<JsonApiOperations>(&req.body) {
+            Ok(document) => document,
+            Err(e) => {
+                let error = JsonApiError::new(&e, 400)
+                    .with_source(JsonApiErrorSource::pointer("/atomic:operations"));
+                let errors = JsonApiErrorArray { errors: vec![error] };
+                return JsonApiResponse::with_document(400, &errors);
+            }
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let mut lids = JsonApiLocalIds::new();
+        let result = ctx.transaction(|ctx| {
+            document
+                .operations
+                .into_iter()
+                .enumerate()
+                .map(|(index, operation)| {
+                    self.apply(operation, ctx, &mut lids)
+                        .map_err(|failure| at_operation(index, failure))
+                })
+                .collect::<Result<Vec<_>, _>>()
+        });
+
+        match result {
+            Ok(Ok(ref results)) if results.iter().all(|r| r.data.is_none()) => {
+                JsonApiResponse::no_content()
+            }
+            Ok(Ok(results)) => JsonApiResponse::ok(&JsonApiOperationResults { results: results }),
+            Ok(Err((errors, status))) => {
+                JsonApiResponse::with_document(status, &JsonApiErrorArray { errors: errors })
+            }
+            Err(e) => JsonApiResponse::error(&ContextError(e), 500)
+        }
+    }
+}
+
+impl<C: Clone + 'static> Default for OperationsHandler<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn add<T, C>(operation: JsonApiOperation, ctx: &C, lids: &mut JsonApiLocalIds) -> OperationOutcome
+where
+    T: JsonPost<Context = C>,
+    C: Clone
+{
+    let mut data = operation.data.ok_or_else(invalid_operation)?;
+    let lid = data.get("lid").and_then(Value::as_str).map(String::from);
+
+    lids.resolve_relationships(&mut data)
+        .map_err(unknown_local_id)?;
+
+    let json: JsonApiData<T> = serde_json::from_value(data).map_err(bad_request)?;
+    let client_id = json.id.clone();
+    let params = T::Params::from_str("").map_err(bad_request)?;
+
+    let result = match T::create(json, &params, ctx.clone()) {
+        Ok(result) => result,
+        Err((e, status)) => return Err(service_failure(&e, status))
+    };
+
+    let id = result.as_ref().and_then(|r| r.data.id.clone()).or(client_id);
+    if let (Some(lid), Some(id)) = (lid, id) {
+        lids.insert(T::TYPE_NAME, lid, id);
+    }
+
+    operation_result(result)
+}
+
+fn update<T, C>(
+    operation: JsonApiOperation,
+    ctx: &C,
+    lids: &mut JsonApiLocalIds
+) -> OperationOutcome
+where
+    T: JsonPatch<Context = C>,
+    C: Clone,
+    <T::JsonApiIdType as FromStr>::Err: Error
+{
+    let id = target_id(&operation, lids)?;
+    let mut data = operation.data.ok_or_else(invalid_operation)?;
+
+    lids.resolve_relationships(&mut data)
+        .map_err(unknown_local_id)?;
+
+    if let Value::Object(ref mut data) = data {
+        data.remove("lid");
+        data.insert("id".to_string(), Value::String(id.clone()));
+    }
+
+    let json: JsonApiData<T> = serde_json::from_value(data).map_err(bad_request)?;
+    let params = T::Params::from_str("").map_err(bad_request)?;
+    let id = T::JsonApiIdType::from_str(&id).map_err(|e| bad_request(IdParseError(e)))?;
+
+    match T::update(id, json, &params, ctx.clone()) {
+        Ok(result) => operation_result(Some(result)),
+        Err((e, status)) => Err(service_failure(&e, status))
+    }
+}
+
+fn remove<T, C>(
+    operation: JsonApiOperation,
+    ctx: &C,
+    lids: &mut JsonApiLocalIds
+) -> OperationOutcome
+where
+    T: JsonDelete<Context = C>,
+    C: Clone,
+    <T::JsonApiIdType as FromStr>::Err: Error
+{
+    let id = target_id(&operation, lids)?;
+    let id = T::JsonApiIdType::from_str(&id).map_err(|e| bad_request(IdParseError(e)))?;
+
+    match T::delete(id, ctx.clone()) {
+        Ok(_) => Ok(JsonApiOperationResult::default()),
+        Err((e, status)) => Err(service_failure(&e, status))
+    }
+}
+
+/// The id of the resource that `operation` targets, from either its `ref` or its `data`.
+fn target_id(
+    operation: &JsonApiOperation,
+    lids: &JsonApiLocalIds
+) -> Result<String, OperationFailure> {
+    let (type_name, id, lid) = match (&operation.reference, &operation.data) {
+        (&Some(ref reference), _) => (
+            Some(reference.type_name.as_str()),
+            reference.id.as_ref().map(String::as_str),
+            reference.lid.as_ref().map(String::as_str)
+        ),
+        (&None, &Some(ref data)) => (
+            data.get("type").and_then(Value::as_str),
+            data.get("id").and_then(Value::as_str),
+            data.get("lid").and_then(Value::as_str)
+        ),
+        (&None, &None) => (None, None, None)
+    };
+
+    match (type_name, id, lid) {
+        (_, Some(id), _) => Ok(id.to_string()),
+        (Some(type_name), None, Some(lid)) => lids.get(type_name, lid)
+            .map(String::from)
+            .ok_or_else(|| unknown_local_id(lid.to_string())),
+        _ => Err(invalid_operation())
+    }
+}
+
+/// The result of an operation with the primary data and the top-level meta of `document`. Any
+/// included resources are left out, since operation results can't have any.
+fn operation_result<T: ToJson>(
+    document: Option<JsonApiContainer<JsonApiData<T>>>
+) -> OperationOutcome {
+    let (data, meta) = match document {
+        Some(document) => {
+            let data = serde_json::to_value(&document.data)
+                .map_err(|e| (vec![JsonApiError::new(&e, 500)], 500))?;
+            (Some(data), document.meta)
+        }
+        None => (None, JsonApiMeta::new())
+    };
+
+    Ok(JsonApiOperationResult {
+        data: data,
+        meta: meta
+    })
+}
+
+fn service_failure<E, S>(error: &E, status: S) -> OperationFailure
+where
+    E: JsonApiErrorDetails,
+    S: HttpStatus
+{
+    let errors = JsonApiErrorArray::from_error(error, status.status_code());
+    let status = errors.status().unwrap_or_else(|| status.status_code());
+    (errors.errors, status)
+}
+
+/// Makes the errors of the operation at `index` point at it. See `OperationsHandler::dispatch`.
+fn at_operation(index: usize, (mut errors, status): OperationFailure) -> OperationFailure {
+    let operation = format!("/atomic:operations/{}", index);
+
+    for error in &mut errors {
+        match error.source {
+            Some(ref mut source) => {
+                if let Some(ref mut pointer) = source.pointer {
+                    pointer.insert_str(0, &operation);
+                }
+            }
+            None => error.source = Some(JsonApiErrorSource::pointer(operation.clone()))
+        }
+    }
+
+    (errors, status)
+}
+
+fn unknown_local_id(lid: String) -> OperationFailure {
+    (vec![JsonApiError::from_error(&LocalIdError(lid), 400)], 400)
+}
+
+fn invalid_operation() -> OperationFailure {
+    let e = RequestError::InvalidOperation;
+    (vec![JsonApiError::new(&e, 400)], 400)
+}
+
+fn bad_request<E: Error>(e: E) -> OperationFailure {
+    (vec![JsonApiError::new(&e, 400)], 400)
+}
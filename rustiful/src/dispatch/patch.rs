@@ -0,0 +1,58 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_document;
+use super::parse_id;
+use super::parse_params;
+use container::JsonApiContainer;
+use data::JsonApiData;
+use service::HttpStatus;
+use service::JsonPatch;
+use std::error::Error;
+use std::str::FromStr;
+use to_json::ToJson;
+
+/// Dispatches `PATCH /{resource}/{id}` to `JsonPatch::update`.
+pub trait PatchDispatcher
+where
+    Self: JsonPatch
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiData<Self>>>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::update(id, json.data, &params, ctx) {
+            Ok(container) => {
+                let container = container.with_resource_links(&req.base_url);
+                JsonApiResponse::ok(&container.with_self_link(req.document_url()))
+            }
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonPatch> PatchDispatcher for T {}
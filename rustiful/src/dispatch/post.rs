@@ -0,0 +1,69 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_document;
+use super::parse_params;
+use container::JsonApiContainer;
+use data::JsonApiData;
+use errors::RequestError;
+use links::resource_url;
+use service::HttpStatus;
+use service::JsonPost;
+use std::error::Error;
+use to_json::ToJson;
+
+/// Dispatches `POST /{resource}` to `JsonPost::create`.
+pub trait PostDispatcher
+where
+    Self: JsonPost
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        Self: ToJson,
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiData<Self>>>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let params = match parse_params::<Self::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        // Without a client-generated id, the client can only learn the id from the response.
+        let has_client_id = json.data.id.is_some();
+
+        match Self::create(json.data, &params, ctx) {
+            Ok(Some(container)) => {
+                // The self link of the document of a created resource is the resource itself.
+                let url = container
+                    .data
+                    .id
+                    .as_ref()
+                    .map(|id| resource_url(&req.base_url, Self::TYPE_NAME, id));
+                let container = container.with_resource_links(&req.base_url);
+
+                let container = match url {
+                    Some(ref url) => container.with_self_link(url.clone()),
+                    None => container
+                };
+
+                JsonApiResponse::created(&container, url)
+            }
+            Ok(None) if has_client_id => JsonApiResponse::no_content(),
+            Ok(None) => JsonApiResponse::error(&RequestError::MissingCreatedResource, 500),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonPost> PostDispatcher for T {}
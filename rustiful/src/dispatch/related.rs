@@ -0,0 +1,86 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_id;
+use super::parse_params;
+use container::JsonApiContainer;
+use errors::RequestError;
+use page::Page;
+use page::PageParams;
+use related::JsonApiRelated;
+use resource::JsonApiResource;
+use service::HttpStatus;
+use service::JsonGetRelated;
+use std::error::Error;
+use std::str::FromStr;
+use to_json::ToJson;
+
+/// Dispatches `GET /{resource}/{id}/{relationship}` to `JsonGetRelated::find_related`. Since
+/// the related resource type depends on the relationship, an adapter routes each relationship of
+/// the resource separately.
+pub trait GetRelatedDispatcher<R>
+where
+    Self: JsonGetRelated<R>,
+    R: JsonApiResource + ToJson
+{
+    fn dispatch<F, E, S>(
+        req: &JsonApiRequest,
+        relationship: Self::RelationshipField,
+        context: F
+    ) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let mut params = match parse_params::<R::Params>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let page = params.page_mut().take();
+        let page = match Page::resolve(page, Self::DEFAULT_PAGE_SIZE, Self::MAX_PAGE_SIZE) {
+            Ok(page) => page,
+            Err(e) => return JsonApiResponse::error(&e, 400)
+        };
+        *params.page_mut() = page;
+
+        let url = req.document_url();
+
+        match Self::find_related(id, relationship, &params, ctx) {
+            Ok(Some(JsonApiRelated::ToOne(Some(container)))) => {
+                let container = container.with_resource_links(&req.base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Ok(Some(JsonApiRelated::ToOne(None))) => {
+                let container: JsonApiContainer<Option<()>> = JsonApiContainer::new(None);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Ok(Some(JsonApiRelated::ToMany(page))) => {
+                let container = page.into_container(&url, params.page())
+                    .with_resource_links(&req.base_url);
+                JsonApiResponse::ok(&container.with_self_link(url))
+            }
+            Ok(None) => JsonApiResponse::error(&RequestError::NotFound, 404),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T, R> GetRelatedDispatcher<R> for T
+where
+    T: JsonGetRelated<R>,
+    R: JsonApiResource + ToJson
+{
+}
@@ -0,0 +1,252 @@
+use super::JsonApiRequest;
+use super::JsonApiResponse;
+use super::create_context;
+use super::parse_document;
+use super::parse_id;
+use container::JsonApiContainer;
+use errors::RequestError;
+use relationship::JsonApiLinkage;
+use relationship::JsonApiRelationshipField;
+use relationship::JsonApiResourceIdentifier;
+use resource::JsonApiResource;
+use service::HttpStatus;
+use service::JsonDeleteRelationship;
+use service::JsonGetRelationship;
+use service::JsonPatchRelationship;
+use service::JsonPostRelationship;
+use std::error::Error;
+use std::str::FromStr;
+
+/// Dispatches `GET /{resource}/{id}/relationships/{relationship}` to
+/// `JsonGetRelationship::find_relationship`.
+pub trait GetRelationshipDispatcher
+where
+    Self: JsonGetRelationship
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let relationship = match relationship_field::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::find_relationship(id, relationship, ctx) {
+            Ok(Some(linkage)) => linkage_response(req, linkage),
+            Ok(None) => JsonApiResponse::error(&RequestError::NotFound, 404),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonGetRelationship> GetRelationshipDispatcher for T {}
+
+/// Dispatches `PATCH /{resource}/{id}/relationships/{relationship}` to
+/// `JsonPatchRelationship::update_relationship`.
+pub trait PatchRelationshipDispatcher
+where
+    Self: JsonPatchRelationship
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiLinkage>>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let relationship = match relationship_field::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        if relationship.is_to_many() != json.data.is_to_many() {
+            return JsonApiResponse::error(&RequestError::InvalidLinkage, 400);
+        }
+
+        if let Err(response) = check_types(&relationship, &json.data) {
+            return response;
+        }
+
+        match Self::update_relationship(id, relationship, json.data, ctx) {
+            Ok(linkage) => linkage_response(req, linkage),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonPatchRelationship> PatchRelationshipDispatcher for T {}
+
+/// Dispatches `POST /{resource}/{id}/relationships/{relationship}` to
+/// `JsonPostRelationship::add_to_relationship`.
+pub trait PostRelationshipDispatcher
+where
+    Self: JsonPostRelationship
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiLinkage>>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let relationship = match relationship_field::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let members = match to_many_members(&relationship, json.data) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::add_to_relationship(id, relationship, members, ctx) {
+            Ok(linkage) => linkage_response(req, linkage),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonPostRelationship> PostRelationshipDispatcher for T {}
+
+/// Dispatches `DELETE /{resource}/{id}/relationships/{relationship}` to
+/// `JsonDeleteRelationship::remove_from_relationship`.
+pub trait DeleteRelationshipDispatcher
+where
+    Self: JsonDeleteRelationship
+{
+    fn dispatch<F, E, S>(req: &JsonApiRequest, context: F) -> JsonApiResponse
+    where
+        F: FnOnce() -> Result<Self::Context, (E, S)>,
+        E: Error + Send,
+        S: HttpStatus,
+        <Self::JsonApiIdType as FromStr>::Err: Error
+    {
+        let json = match parse_document::<JsonApiContainer<JsonApiLinkage>>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let ctx = match create_context(context) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let id = match parse_id::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let relationship = match relationship_field::<Self>(req) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        let members = match to_many_members(&relationship, json.data) {
+            Ok(result) => result,
+            Err(response) => return response
+        };
+
+        match Self::remove_from_relationship(id, relationship, members, ctx) {
+            Ok(linkage) => linkage_response(req, linkage),
+            Err((e, status)) => JsonApiResponse::error(&e, status.status_code())
+        }
+    }
+}
+
+impl<T: JsonDeleteRelationship> DeleteRelationshipDispatcher for T {}
+
+/// The relationship in the URL of the request. A relationship that doesn't exist is treated in
+/// the same way as a resource that doesn't exist, and a missing `relationship` parameter in the
+/// same way as a missing `id` in `parse_id`.
+fn relationship_field<T: JsonApiResource>(
+    req: &JsonApiRequest
+) -> Result<T::RelationshipField, JsonApiResponse> {
+    let name = match req.param("relationship") {
+        Some(name) => name,
+        None => return Err(JsonApiResponse::error(&RequestError::MissingRouteParam, 500))
+    };
+
+    T::RelationshipField::from_str(name)
+        .map_err(|_| JsonApiResponse::error(&RequestError::NotFound, 404))
+}
+
+/// Checks that all resource identifiers in `linkage` have the type of `relationship`.
+fn check_types<R: JsonApiRelationshipField>(
+    relationship: &R,
+    linkage: &JsonApiLinkage
+) -> Result<(), JsonApiResponse> {
+    let type_name = relationship.type_name();
+
+    if linkage.identifiers().iter().all(|i| i.type_name == type_name) {
+        Ok(())
+    } else {
+        Err(JsonApiResponse::error(&RequestError::LinkageTypeMismatch, 409))
+    }
+}
+
+/// The members to add to or to remove from a to-many relationship.
+fn to_many_members<R: JsonApiRelationshipField>(
+    relationship: &R,
+    linkage: JsonApiLinkage
+) -> Result<Vec<JsonApiResourceIdentifier>, JsonApiResponse> {
+    if !relationship.is_to_many() {
+        return Err(JsonApiResponse::error(&RequestError::NotToMany, 403));
+    }
+
+    if let Err(e) = check_types(relationship, &linkage) {
+        return Err(e);
+    }
+
+    match linkage {
+        JsonApiLinkage::ToMany(members) => Ok(members),
+        JsonApiLinkage::ToOne(_) => Err(JsonApiResponse::error(&RequestError::InvalidLinkage, 400))
+    }
+}
+
+fn linkage_response(req: &JsonApiRequest, linkage: JsonApiLinkage) -> JsonApiResponse {
+    JsonApiResponse::ok(&JsonApiContainer::new(linkage).with_self_link(req.document_url()))
+}
@@ -0,0 +1,118 @@
+use super::JsonApiMediaType;
+use std::collections::HashMap;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// A request to a JSONAPI endpoint, independent of any web framework.
+///
+/// This is what the dispatchers (such as `GetDispatcher`) take instead of the request type of a
+/// framework. An adapter, such as `rustiful::iron`, routes the request to the right dispatcher and
+/// converts the request of its framework to a `JsonApiRequest`. This can also be used to call an
+/// endpoint without any HTTP stack at all, e.g in a unit test.
+///
+/// # Example
+///
+/// ```
+/// # extern crate rustiful;
+/// # use rustiful::JsonApiRequest;
+/// #
+/// # fn main() {
+/// let request = JsonApiRequest::new("GET", "/articles/1")
+///     .with_param("id", "1")
+///     .with_query("fields[articles]=title")
+///     .with_base_url("https://example.com/api");
+///
+/// assert_eq!(
+///     "https://example.com/api/articles/1?fields[articles]=title",
+///     request.document_url()
+/// );
+/// # }
+/// ```
+pub struct JsonApiRequest {
+    /// The HTTP method, e.g `GET`.
+    pub method: String,
+    /// The path of the request URL, relative to `base_url`, e.g `/articles/1`.
+    pub path: String,
+    /// The parameters in the path of the request, i.e `id` and `relationship`.
+    pub params: HashMap<String, String>,
+    /// The query string of the request URL, without the leading `?`.
+    pub query: String,
+    /// The headers of the request, in the order that they were received.
+    pub headers: Vec<(String, String)>,
+    /// The body of the request. This is empty if the request has no body.
+    pub body: Vec<u8>,
+    /// The base URL of any links in the response, e.g `https://example.com/api`.
+    pub base_url: String,
+    /// The extensions and profiles that apply to the request. This is set by the adapter once the
+    /// request has passed `ContentNegotiation`.
+    pub media_type: JsonApiMediaType
+}
+
+impl JsonApiRequest {
+    pub fn new<M: Into<String>, P: Into<String>>(method: M, path: P) -> Self {
+        JsonApiRequest {
+            method: method.into(),
+            path: path.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_param<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.params.insert(name.into(), value.into());
+        self
+    }
+
+    pub fn with_query<S: Into<String>>(mut self, query: S) -> Self {
+        self.query = query.into();
+        self
+    }
+
+    pub fn with_header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_body<B: Into<Vec<u8>>>(mut self, body: B) -> Self {
+        self.body = body.into();
+        self
+    }
+
+    pub fn with_base_url<S: Into<String>>(mut self, base_url: S) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    pub fn with_media_type(mut self, media_type: JsonApiMediaType) -> Self {
+        self.media_type = media_type;
+        self
+    }
+
+    /// The value of the path parameter `name`, if any.
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params.get(name).map(|value| value.as_str())
+    }
+
+    /// The value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|&&(ref n, _)| n.to_lowercase() == name)
+            .map(|&(_, ref value)| value.as_str())
+    }
+
+    /// The URL of the request, i.e the URL of the document in the response.
+    pub fn document_url(&self) -> String {
+        let mut url = format!(
+            "{}/{}",
+            self.base_url.trim_right_matches('/'),
+            self.path.trim_left_matches('/')
+        );
+
+        if !self.query.is_empty() {
+            url.push('?');
+            url.push_str(&self.query);
+        }
+
+        url
+    }
+}
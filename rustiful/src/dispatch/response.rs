@@ -0,0 +1,206 @@
+extern crate serde_json;
+
+use super::JSON_API_MEDIA_TYPE;
+use super::JsonApiMediaType;
+use error::JsonApiErrorArray;
+use error::JsonApiErrorDetails;
+use serde::Serialize;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// A response from a JSONAPI endpoint, independent of any web framework.
+///
+/// This is what the dispatchers (such as `GetDispatcher`) return. An adapter, such as
+/// `rustiful::iron`, converts it to the response type of its framework. Every response with a
+/// body has the `Content-Type` header `application/vnd.api+json`, and responses without a body,
+/// such as `204 No Content`, have no `Content-Type`.
+pub struct JsonApiResponse {
+    /// The numeric HTTP status code, e.g `200`.
+    pub status: u16,
+    /// The headers of the response, such as `Content-Type` and `Location`.
+    pub headers: Vec<(String, String)>,
+    /// The serialized JSONAPI document, or `None` if the response has no body.
+    pub body: Option<String>
+}
+
+impl JsonApiResponse {
+    /// A response without a body, and without a `Content-Type`.
+    pub fn new(status: u16) -> Self {
+        JsonApiResponse {
+            status: status,
+            headers: vec![],
+            body: None
+        }
+    }
+
+    /// A response with the serialized `document` as its body. If `document` can't be serialized,
+    /// this is a `500 Internal Server Error` response with the serialization error instead.
+    pub fn with_document<T: Serialize>(status: u16, document: &T) -> Self {
+        let (status, body) = match serde_json::to_string(document) {
+            Ok(serialized) => (status, serialized),
+            Err(e) => {
+                let errors = JsonApiErrorArray::new(&e, 500);
+                (500, serde_json::to_string(&errors).unwrap_or_default())
+            }
+        };
+
+        let mut response =
+            JsonApiResponse::new(status).with_header("Content-Type", JSON_API_MEDIA_TYPE);
+        response.body = Some(body);
+        response
+    }
+
+    /// A `200 OK` response with the serialized `document`.
+    pub fn ok<T: Serialize>(document: &T) -> Self {
+        JsonApiResponse::with_document(200, document)
+    }
+
+    /// A `201 Created` response with the serialized `document`, and with a `Location` header
+    /// pointing at the created resource if its URL is known.
+    pub fn created<T: Serialize>(document: &T, location: Option<String>) -> Self {
+        let response = JsonApiResponse::with_document(201, document);
+
+        match location {
+            Some(location) if response.status == 201 => response.with_header("Location", location),
+            _ => response
+        }
+    }
+
+    /// A `204 No Content` response.
+    pub fn no_content() -> Self {
+        JsonApiResponse::new(204)
+    }
+
+    /// A response with the error objects of `error`. The status is the overall status of the
+    /// error objects if there is one (see `JsonApiErrorArray::status`), and `status` otherwise.
+    pub fn error<E: JsonApiErrorDetails>(error: &E, status: u16) -> Self {
+        let errors = JsonApiErrorArray::from_error(error, status);
+        let status = errors.status().unwrap_or(status);
+        JsonApiResponse::with_document(status, &errors)
+    }
+
+    pub fn with_header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends the extensions and profiles that apply to the request in the `Content-Type` of this
+    /// response, i.e the media type parameters negotiated by `ContentNegotiation`. A response
+    /// without the plain JSONAPI media type is returned as is.
+    pub fn with_media_type(mut self, media_type: &JsonApiMediaType) -> Self {
+        if media_type.is_empty() {
+            return self;
+        }
+
+        for &mut (ref name, ref mut value) in &mut self.headers {
+            if name.eq_ignore_ascii_case("Content-Type") && value == JSON_API_MEDIA_TYPE {
+                *value = media_type.to_header_value();
+            }
+        }
+
+        self
+    }
+
+    /// The value of the first header named `name`, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        let name = name.to_lowercase();
+        self.headers
+            .iter()
+            .find(|&&(ref n, _)| n.to_lowercase() == name)
+            .map(|&(_, ref value)| value.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use container::JsonApiContainer;
+    use errors::QueryStringParseError;
+    use std::collections::HashMap;
+
+    // A hacky version of `JsonApiData<T>`, since the attributes field requires a type that
+    // implements `ToJson`
+    #[derive(Serialize, Deserialize)]
+    struct Data {
+        pub id: String,
+        #[serde(rename = "type")]
+        // The type name of the JSONAPI resource, equivalent to the resource name.
+        pub lower_case_type: String,
+        pub attributes: HashMap<String, String>
+    }
+
+    fn data() -> Data {
+        let mut attributes = HashMap::new();
+        attributes.insert("foo".to_string(), "bar".to_string());
+        Data {
+            id: "foo".to_string(),
+            lower_case_type: "foos".to_string(),
+            attributes: attributes
+        }
+    }
+
+    #[test]
+    fn ok_response_object() {
+        let response = JsonApiResponse::ok(&JsonApiContainer::new(data()));
+        let expected = r#"{"data":{"id":"foo","type":"foos","attributes":{"foo":"bar"}}}"#;
+
+        assert_eq!(200, response.status);
+        assert_eq!(Some("application/vnd.api+json"), response.header("content-type"));
+        assert_eq!(Some(expected.to_string()), response.body);
+    }
+
+    #[test]
+    fn ok_response_list() {
+        let response = JsonApiResponse::ok(&JsonApiContainer::new(vec![data()]));
+        let expected = r#"{"data":[{"id":"foo","type":"foos","attributes":{"foo":"bar"}}]}"#;
+
+        assert_eq!(200, response.status);
+        assert_eq!(Some(expected.to_string()), response.body);
+    }
+
+    #[test]
+    fn created_response() {
+        let location = "http://localhost:3000/foos/foo".to_string();
+        let response = JsonApiResponse::created(&JsonApiContainer::new(data()), Some(location));
+
+        assert_eq!(201, response.status);
+        assert_eq!(Some("http://localhost:3000/foos/foo"), response.header("Location"));
+    }
+
+    #[test]
+    fn no_content_response() {
+        let response = JsonApiResponse::no_content();
+
+        assert_eq!(204, response.status);
+        assert_eq!(None, response.body);
+        assert_eq!(None, response.header("Content-Type"));
+    }
+
+    #[test]
+    fn response_with_media_type() {
+        let media_type = JsonApiMediaType {
+            ext: vec!["https://jsonapi.org/ext/atomic".to_string()],
+            profile: Vec::new()
+        };
+        let response = JsonApiResponse::ok(&JsonApiContainer::new(data()));
+        let response = response.with_media_type(&media_type);
+
+        assert_eq!(
+            Some(r#"application/vnd.api+json; ext="https://jsonapi.org/ext/atomic""#),
+            response.header("Content-Type")
+        );
+    }
+
+    #[test]
+    fn error_response() {
+        let error = QueryStringParseError::InvalidPageValue("page[size]".to_string());
+        let response = JsonApiResponse::error(&error, 400);
+        let expected = concat!(
+            r#"{"errors":[{"title":"page[size]","status":"400","#,
+            r#""detail":"Query string parse error: Invalid value for page[size]","#,
+            r#""source":{"parameter":"page[size]"}}]}"#
+        );
+
+        assert_eq!(400, response.status);
+        assert_eq!(Some(expected.to_string()), response.body);
+    }
+}
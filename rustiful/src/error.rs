@@ -1,4 +1,10 @@
+use meta::JsonApiMeta;
+use service::HttpStatus;
 use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+static MULTIPLE_ERRORS: &'static str = "Multiple errors";
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct JsonApiErrorArray {
@@ -11,13 +17,54 @@ impl JsonApiErrorArray {
             errors: vec![JsonApiError::new(error, status)]
         }
     }
+
+    /// Like `new`, with the `id`, `code`, `source`, `links` and `meta` members of the error
+    /// object supplied by `error`. If `error` stands for several errors, such as `JsonApiErrors`,
+    /// then the array has one error object for each of them. See `JsonApiErrorDetails`.
+    pub fn from_error<T: JsonApiErrorDetails>(error: &T, status: u16) -> JsonApiErrorArray {
+        JsonApiErrorArray {
+            errors: error.error_objects(status)
+        }
+    }
+
+    /// The status code of a response with all of these errors. This is the status of the errors
+    /// if they all have the same one, `400` if they're all client errors and `500` otherwise.
+    /// `None` if there are no errors.
+    pub fn status(&self) -> Option<u16> {
+        let statuses: Vec<u16> = self.errors
+            .iter()
+            .map(|error| error.status.parse().unwrap_or(500))
+            .collect();
+
+        match statuses.first() {
+            None => None,
+            Some(&first) if statuses.iter().all(|&status| status == first) => Some(first),
+            Some(_) if statuses.iter().all(|&status| status >= 400 && status < 500) => Some(400),
+            Some(_) => Some(500)
+        }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct JsonApiError {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A unique identifier for this occurrence of the error.
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Links to further details about the error.
+    pub links: Option<JsonApiErrorLinks>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// An application-specific error code.
+    pub code: Option<String>,
     pub title: String,
     pub status: String,
-    pub detail: String
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The part of the request document or the query parameter that caused the error.
+    pub source: Option<JsonApiErrorSource>,
+    #[serde(default, skip_serializing_if = "JsonApiMeta::is_empty")]
+    /// Meta information about the error.
+    pub meta: JsonApiMeta
 }
 
 impl JsonApiError {
@@ -25,7 +72,328 @@ impl JsonApiError {
         JsonApiError {
             title: error.description().to_string(),
             status: status.to_string(),
-            detail: format!("{}", error)
+            detail: format!("{}", error),
+            ..Default::default()
+        }
+    }
+
+    /// Like `new`, with the `id`, `code`, `source`, `links` and `meta` members supplied by
+    /// `error`. See `JsonApiErrorDetails`.
+    pub fn from_error<T: JsonApiErrorDetails>(error: &T, status: u16) -> JsonApiError {
+        let mut json = JsonApiError::new(error, status);
+        json.id = error.id();
+        json.code = error.code();
+        json.source = error.error_source();
+        json.links = error.about().map(|about| JsonApiErrorLinks { about: Some(about) });
+        json.meta = error.meta();
+        json
+    }
+
+    /// Adds meta information about this error. Any existing members with the same names are
+    /// replaced. A struct can be converted to a `JsonApiMeta` with `to_meta`.
+    pub fn with_meta(mut self, meta: JsonApiMeta) -> JsonApiError {
+        self.meta.extend(meta);
+        self
+    }
+
+    /// Sets the `source` of this error.
+    pub fn with_source(mut self, source: JsonApiErrorSource) -> JsonApiError {
+        self.source = Some(source);
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The `links` member of an error object.
+pub struct JsonApiErrorLinks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A link to further details about this particular occurrence of the error.
+    pub about: Option<String>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The `source` member of an error object, i.e what in the request caused the error.
+pub struct JsonApiErrorSource {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// A JSON pointer to the value in the request document that caused the error, e.g
+    /// `/data/attributes/title`.
+    pub pointer: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The query parameter that caused the error, e.g `sort`.
+    pub parameter: Option<String>
+}
+
+impl JsonApiErrorSource {
+    /// A source that points at the value `pointer` in the request document.
+    pub fn pointer<S: Into<String>>(pointer: S) -> Self {
+        JsonApiErrorSource {
+            pointer: Some(pointer.into()),
+            ..Default::default()
+        }
+    }
+
+    /// A source that refers to the query parameter `parameter`.
+    pub fn parameter<S: Into<String>>(parameter: S) -> Self {
+        JsonApiErrorSource {
+            parameter: Some(parameter.into()),
+            ..Default::default()
+        }
+    }
+}
+
+/// The members of a JSONAPI error object that aren't covered by `std::error::Error`.
+///
+/// This has to be implemented for the `Error` type of the `JsonGet`, `JsonIndex`, `JsonPost`,
+/// `JsonPatch` and `JsonDelete` impls (and the other service traits). All methods have default
+/// implementations, so an empty impl is enough for an error type without any details. The
+/// `title` and `detail` members are still taken from `Error::description` and `Display`.
+///
+/// # Example
+///
+/// ```
+/// # use rustiful::JsonApiErrorDetails;
+/// # use rustiful::JsonApiErrorSource;
+/// # use std::error::Error;
+/// # use std::fmt::Display;
+/// # use std::fmt::Formatter;
+/// #
+/// #[derive(Debug)]
+/// struct MissingTitle;
+///
+/// # impl Error for MissingTitle {
+/// #     fn description(&self) -> &str {
+/// #         "Missing title"
+/// #     }
+/// # }
+/// #
+/// # impl Display for MissingTitle {
+/// #     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+/// #         write!(f, "An article must have a title")
+/// #     }
+/// # }
+/// #
+/// impl JsonApiErrorDetails for MissingTitle {
+///     fn code(&self) -> Option<String> {
+///         Some("missing_title".to_string())
+///     }
+///
+///     fn error_source(&self) -> Option<JsonApiErrorSource> {
+///         Some(JsonApiErrorSource::pointer("/data/attributes/title"))
+///     }
+/// }
+/// ```
+pub trait JsonApiErrorDetails: Error {
+    /// A unique identifier for this occurrence of the error.
+    fn id(&self) -> Option<String> {
+        None
+    }
+
+    /// An application-specific error code.
+    fn code(&self) -> Option<String> {
+        None
+    }
+
+    /// The part of the request document or the query parameter that caused the error.
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        None
+    }
+
+    /// A link to further details about this particular occurrence of the error.
+    fn about(&self) -> Option<String> {
+        None
+    }
+
+    /// Meta information about the error.
+    fn meta(&self) -> JsonApiMeta {
+        JsonApiMeta::new()
+    }
+
+    /// The error objects that this error is rendered as, with the status code `status`. By
+    /// default this is a single error object, but an error that stands for several errors, such as
+    /// `JsonApiErrors`, can return one for each of them along with their own status codes.
+    fn error_objects(&self, status: u16) -> Vec<JsonApiError>
+    where
+        Self: Sized
+    {
+        vec![JsonApiError::from_error(self, status)]
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// Several errors that are returned at once, e.g all failed validations of a resource. Use this as
+/// the `Error` type of a service trait to return more than one error in a response.
+///
+/// The response has one error object for each error, and its status code is chosen with
+/// `JsonApiErrorArray::status`, regardless of the status that is returned along with the errors.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let mut errors = JsonApiErrors::new();
+///
+/// if json.attributes.title.is_none() {
+///     errors.push(&MissingAttribute("title"), 422);
+/// }
+///
+/// if json.attributes.body.is_none() {
+///     errors.push(&MissingAttribute("body"), 422);
+/// }
+///
+/// if !errors.is_empty() {
+///     return Err(errors.into());
+/// }
+/// ```
+pub struct JsonApiErrors {
+    errors: Vec<JsonApiError>
+}
+
+impl JsonApiErrors {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `error` with the status code `status`. If `error` stands for several errors itself,
+    /// then all of them are added.
+    pub fn push<T: JsonApiErrorDetails>(&mut self, error: &T, status: u16) {
+        self.errors.extend(error.error_objects(status));
+    }
+
+    /// The error objects of the errors that were added so far.
+    pub fn errors(&self) -> &[JsonApiError] {
+        &self.errors
+    }
+
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl Error for JsonApiErrors {
+    fn description(&self) -> &str {
+        match self.errors.len() {
+            1 => self.errors[0].title.as_str(),
+            _ => MULTIPLE_ERRORS
         }
     }
 }
+
+impl Display for JsonApiErrors {
+    fn fmt(&self, f: &mut Formatter) -> ::std::fmt::Result {
+        let details: Vec<&str> = self.errors.iter().map(|e| e.detail.as_str()).collect();
+        write!(f, "{}", details.join("; "))
+    }
+}
+
+impl JsonApiErrorDetails for JsonApiErrors {
+    fn error_objects(&self, _: u16) -> Vec<JsonApiError> {
+        self.errors.clone()
+    }
+}
+
+/// Pairs the errors with their overall status, see `JsonApiErrorArray::status`. This allows
+/// returning `Err(errors.into())` from a service trait that has `JsonApiErrors` as its error type,
+/// whatever the `Handler::Status` of the resource is.
+impl<S: HttpStatus> From<JsonApiErrors> for (JsonApiErrors, S) {
+    fn from(errors: JsonApiErrors) -> Self {
+        let status = JsonApiErrorArray::from_error(&errors, 500).status().unwrap_or(500);
+        (errors, S::from_status_code(status))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use super::*;
+    use errors::RequestError;
+    use std::fmt::Display;
+    use std::fmt::Formatter;
+
+    #[derive(Debug)]
+    struct DetailedError;
+
+    impl Error for DetailedError {
+        fn description(&self) -> &str {
+            "Detailed"
+        }
+    }
+
+    impl Display for DetailedError {
+        fn fmt(&self, f: &mut Formatter) -> ::std::fmt::Result {
+            write!(f, "A detailed error")
+        }
+    }
+
+    impl JsonApiErrorDetails for DetailedError {
+        fn id(&self) -> Option<String> {
+            Some("1".to_string())
+        }
+
+        fn code(&self) -> Option<String> {
+            Some("detailed".to_string())
+        }
+
+        fn error_source(&self) -> Option<JsonApiErrorSource> {
+            Some(JsonApiErrorSource::pointer("/data/attributes/title"))
+        }
+
+        fn about(&self) -> Option<String> {
+            Some("https://example.com/errors/detailed".to_string())
+        }
+    }
+
+    #[test]
+    fn serialize_error_with_details() {
+        let error = JsonApiError::from_error(&DetailedError, 422);
+        let expected = concat!(
+            r#"{"id":"1","links":{"about":"https://example.com/errors/detailed"},"#,
+            r#""code":"detailed","title":"Detailed","status":"422","detail":"A detailed error","#,
+            r#""source":{"pointer":"/data/attributes/title"}}"#
+        );
+
+        assert_eq!(expected, serde_json::to_string(&error).unwrap());
+    }
+
+    #[test]
+    fn serialize_error_without_details() {
+        let error = JsonApiError::from_error(&RequestError::NoBody, 400);
+        let expected = r#"{"title":"No body","status":"400","detail":"No body"}"#;
+
+        assert_eq!(expected, serde_json::to_string(&error).unwrap());
+    }
+
+    #[test]
+    fn multiple_errors_to_error_array() {
+        let mut errors = JsonApiErrors::new();
+        errors.push(&DetailedError, 422);
+        errors.push(&RequestError::NoBody, 400);
+
+        let array = JsonApiErrorArray::from_error(&errors, 500);
+
+        assert_eq!(2, array.errors.len());
+        assert_eq!("422", array.errors[0].status);
+        assert_eq!("400", array.errors[1].status);
+        assert_eq!(Some(400), array.status());
+        assert_eq!("Multiple errors", errors.description());
+        assert_eq!("A detailed error; No body", errors.to_string());
+    }
+
+    #[test]
+    fn error_array_status() {
+        let array = |statuses: &[u16]| JsonApiErrorArray {
+            errors: statuses
+                .iter()
+                .map(|&status| JsonApiError::new(&RequestError::NoBody, status))
+                .collect()
+        };
+
+        assert_eq!(None, array(&[]).status());
+        assert_eq!(Some(422), array(&[422, 422]).status());
+        assert_eq!(Some(400), array(&[422, 409]).status());
+        assert_eq!(Some(500), array(&[422, 503]).status());
+    }
+}
@@ -0,0 +1,59 @@
+extern crate serde_json;
+
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
+use self::serde_json::error::Category;
+use std::error::Error;
+use std::fmt::*;
+
+static INVALID_BODY: &'static str = "Can't parse body to the struct";
+
+#[derive(Debug)]
+/// A new-type wrapper for the error of deserializing a request document.
+///
+/// This is used whenever the body of a `JsonApiRequest` isn't valid JSON, or can't be
+/// deserialized to the document that the endpoint expects.
+pub struct BodyParseError(pub serde_json::Error);
+
+impl Error for BodyParseError {
+    fn description(&self) -> &str {
+        INVALID_BODY
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        Some(&self.0)
+    }
+}
+
+impl Display for BodyParseError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}", INVALID_BODY)
+    }
+}
+
+/// A request document that is valid JSON, but can't be deserialized, points at its primary data.
+impl JsonApiErrorDetails for BodyParseError {
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        match self.0.classify() {
+            Category::Data => Some(JsonApiErrorSource::pointer("/data")),
+            _ => None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn body_parse_error_source() {
+        let syntax = serde_json::from_str::<Vec<String>>("[").unwrap_err();
+        assert_eq!(None, BodyParseError(syntax).error_source());
+
+        let data = serde_json::from_str::<Vec<String>>("{}").unwrap_err();
+        assert_eq!(
+            Some(JsonApiErrorSource::pointer("/data")),
+            BodyParseError(data).error_source()
+        );
+    }
+}
@@ -0,0 +1,40 @@
+use error::JsonApiErrorDetails;
+use std::error::Error;
+use std::fmt::*;
+
+#[derive(Debug)]
+/// A new-type wrapper for the errors of a request context, i.e `FromRequest::Error` and
+/// `JsonApiTransaction::Error`.
+///
+/// Unlike the errors of the service traits, these don't have to implement `JsonApiErrorDetails`,
+/// so that errors from e.g a connection pool can be used as is. This wrapper renders them without
+/// any details.
+pub struct ContextError<E: Error>(pub E);
+
+impl<E> Error for ContextError<E>
+where
+    E: Error
+{
+    fn description(&self) -> &str {
+        self.0.description()
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        self.0.cause()
+    }
+}
+
+impl<E> Display for ContextError<E>
+where
+    E: Error
+{
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<E> JsonApiErrorDetails for ContextError<E>
+where
+    E: Error
+{
+}
@@ -0,0 +1,149 @@
+use error::JsonApiError;
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
+use service::HttpStatus;
+use std::error::Error;
+use std::fmt::*;
+use validate::ValidationError;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// An error when converting a resource from a `JsonApiData`, i.e the `Error` of the generated
+/// `TryFrom` impls, `JsonApiBuilder::build` and `JsonPatch::patch`.
+///
+/// Each variant is rendered with a `source.pointer` to the offending part of the request document.
+/// Invalid ids and relationships have the status `400`, and the other variants have the status
+/// `422`.
+pub enum ConversionError {
+    /// The attribute with this name was never set on the builder.
+    MissingAttribute(String),
+    /// The relationship with this name was never set on the builder.
+    MissingRelationship(String),
+    /// The id of the resource can't be parsed. This contains the reason why.
+    InvalidId(String),
+    /// The relationship with this name is invalid, e.g if the type of the related resource is
+    /// wrong, its id can't be parsed or it's `null` when it can't be. This contains the name of the
+    /// relationship and the reason why it's invalid.
+    InvalidRelationship(String, String),
+    /// The attributes that failed the validations declared with `#[jsonapi(validate(...))]`.
+    Validation(Vec<ValidationError>)
+}
+
+impl ConversionError {
+    /// The status code of the error objects of this error.
+    pub fn status(&self) -> u16 {
+        match *self {
+            ConversionError::InvalidId(_) | ConversionError::InvalidRelationship(..) => 400,
+            _ => 422
+        }
+    }
+}
+
+impl Error for ConversionError {
+    fn description(&self) -> &str {
+        match *self {
+            ConversionError::MissingAttribute(_) => "Missing attribute",
+            ConversionError::MissingRelationship(_) => "Missing relationship",
+            ConversionError::InvalidId(_) => "Invalid id",
+            ConversionError::InvalidRelationship(..) => "Invalid relationship",
+            ConversionError::Validation(ref errors) if errors.len() == 1 => errors[0].description(),
+            ConversionError::Validation(_) => "Invalid attributes"
+        }
+    }
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        match *self {
+            ConversionError::MissingAttribute(ref name) => {
+                write!(f, "{} must be initialized", name)
+            }
+            ConversionError::MissingRelationship(ref name) => {
+                write!(f, "Relationship {} must be initialized", name)
+            }
+            ConversionError::InvalidId(ref message) => write!(f, "{}", message),
+            ConversionError::InvalidRelationship(_, ref message) => write!(f, "{}", message),
+            ConversionError::Validation(ref errors) => {
+                let messages: Vec<&str> = errors.iter().map(|e| e.message.as_str()).collect();
+                write!(f, "{}", messages.join("; "))
+            }
+        }
+    }
+}
+
+/// Failed validations are rendered as one error object for each `ValidationError`. The status
+/// that is passed to `error_objects` is ignored in favour of `ConversionError::status`.
+impl JsonApiErrorDetails for ConversionError {
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        let pointer = match *self {
+            ConversionError::MissingAttribute(ref name) => format!("/data/attributes/{}", name),
+            ConversionError::MissingRelationship(ref name) |
+            ConversionError::InvalidRelationship(ref name, _) => {
+                format!("/data/relationships/{}", name)
+            }
+            ConversionError::InvalidId(_) => "/data/id".to_string(),
+            ConversionError::Validation(_) => "/data/attributes".to_string()
+        };
+
+        Some(JsonApiErrorSource::pointer(pointer))
+    }
+
+    fn error_objects(&self, _: u16) -> Vec<JsonApiError> {
+        match *self {
+            ConversionError::Validation(ref errors) => {
+                errors
+                    .iter()
+                    .map(|error| JsonApiError::from_error(error, self.status()))
+                    .collect()
+            }
+            _ => vec![JsonApiError::from_error(self, self.status())]
+        }
+    }
+}
+
+/// Pairs the error with its status, see `ConversionError::status`. This allows using `?` on
+/// `try_into()` and `patch()` in a service trait that has `ConversionError` as its error type.
+impl<S: HttpStatus> From<ConversionError> for (ConversionError, S) {
+    fn from(error: ConversionError) -> Self {
+        let status = S::from_status_code(error.status());
+        (error, status)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn conversion_error_to_error_objects() {
+        let error = ConversionError::InvalidRelationship(
+            "author".to_string(),
+            "Invalid type name 'tags', expected 'authors'".to_string()
+        );
+        let objects = error.error_objects(500);
+
+        assert_eq!(1, objects.len());
+        assert_eq!("Invalid relationship", objects[0].title);
+        assert_eq!("400", objects[0].status);
+        assert_eq!(
+            Some(JsonApiErrorSource::pointer("/data/relationships/author")),
+            objects[0].source
+        );
+    }
+
+    #[test]
+    fn validation_errors_to_error_objects() {
+        let error = ConversionError::Validation(vec![
+            ValidationError::new("title", "length", "title is too long"),
+            ValidationError::new("contact", "email", "contact must be a valid email address")
+        ]);
+        let objects = error.error_objects(500);
+
+        assert_eq!(2, objects.len());
+        assert!(objects.iter().all(|o| o.status == "422"));
+        assert_eq!(
+            Some(JsonApiErrorSource::pointer("/data/attributes/contact")),
+            objects[1].source
+        );
+        assert_eq!("Invalid attributes", error.description());
+    }
+}
@@ -1,3 +1,4 @@
+use error::JsonApiErrorDetails;
 use std::error::Error;
 use std::fmt::*;
 
@@ -29,3 +30,11 @@ where
         write!(f, "Error parsing id: {}", self.0)
     }
 }
+
+/// The id is taken from the URL rather than from the request document or the query string, so
+/// there's no `source`.
+impl<E> JsonApiErrorDetails for IdParseError<E>
+where
+    E: Error
+{
+}
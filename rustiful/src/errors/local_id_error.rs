@@ -0,0 +1,33 @@
+use error::JsonApiErrorDetails;
+use std::error::Error;
+use std::fmt::*;
+
+static UNKNOWN_LOCAL_ID: &'static str = "Unknown local id";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A new-type wrapper for a local id that doesn't refer to a resource.
+///
+/// This is used whenever an Atomic Operations operation refers to a local id, `lid`, that wasn't
+/// assigned by an earlier `add` operation of the same request.
+pub struct LocalIdError(pub String);
+
+impl Error for LocalIdError {
+    fn description(&self) -> &str {
+        UNKNOWN_LOCAL_ID
+    }
+
+    fn cause(&self) -> Option<&Error> {
+        None
+    }
+}
+
+impl Display for LocalIdError {
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        write!(f, "No resource was added with the local id '{}'", self.0)
+    }
+}
+
+/// The operation that refers to the local id is only known to the dispatcher, which sets the
+/// `source`.
+impl JsonApiErrorDetails for LocalIdError {
+}
@@ -1,7 +1,15 @@
 pub mod request_error;
 pub mod query_string_parse_error;
 pub mod id_parse_error;
+pub mod context_error;
+pub mod conversion_error;
+pub mod body_parse_error;
+pub mod local_id_error;
 
+pub use self::body_parse_error::*;
+pub use self::context_error::*;
+pub use self::conversion_error::*;
 pub use self::id_parse_error::*;
+pub use self::local_id_error::*;
 pub use self::query_string_parse_error::*;
 pub use self::request_error::*;
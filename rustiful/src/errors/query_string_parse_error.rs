@@ -1,3 +1,5 @@
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
 use std::error::Error;
 use std::fmt::*;
 
@@ -12,6 +14,10 @@ pub enum QueryStringParseError {
     /// `fields` value does not match field name
     InvalidFieldValue(String),
 
+    /// `fields[*]` type is neither the type of the resource nor the type of a resource that can be
+    /// included along with it
+    InvalidFieldsetType(String),
+
     /// `sort` value does not match field name
     InvalidSortValue(String),
 
@@ -21,6 +27,30 @@ pub enum QueryStringParseError {
     /// Multiple `sort` query param keys, e.g `sort=foo&sort=bar`
     DuplicateSortKey(String),
 
+    /// `include` value does not match a relationship path
+    InvalidIncludeValue(String),
+
+    /// Multiple `include` query param keys, e.g `include=foo&include=bar`
+    DuplicateIncludeKey(String),
+
+    /// `filter[*]` key is in an invalid format or does not match a filterable field name
+    InvalidFilterKey(String),
+
+    /// `filter[*]` value cannot be parsed to the type of the field, e.g `filter[published]=abc`
+    InvalidFilterValue(String),
+
+    /// `filter[*][*]` operator is unknown or not supported by the type of the field, e.g
+    /// `filter[published][lt]=true`. Holds the field and the operator.
+    InvalidFilterOperator(String, String),
+
+    /// `page[*]` key is not `number`, `size`, `offset`, `limit`, `after` or `before`, or mixes
+    /// pagination strategies
+    InvalidPageKey(String),
+
+    /// `page[*]` value is not a positive number or a valid cursor, e.g `page[size]=abc` or
+    /// `page[number]=0`
+    InvalidPageValue(String),
+
     /// Currently unsupported functionality when parsing the query param, notably relationships
     UnImplementedError
 }
@@ -33,11 +63,25 @@ impl Display for QueryStringParseError {
         match *self {
             InvalidFieldsetKey(ref desc) => write!(f, "{} Invalid key: {}", msg, desc),
             InvalidFieldValue(ref desc) => write!(f, "{} Invalid value: {}", msg, desc),
+            InvalidFieldsetType(ref desc) => write!(f, "{} Invalid fieldset type: {}", msg, desc),
             InvalidSortValue(ref desc) => write!(f, "{} Invalid value: {}", msg, desc),
             EmptyFieldsetValue(ref desc) => {
                 write!(f, "{} No values specified for fields[{}]", msg, desc)
             }
             DuplicateSortKey(ref desc) => write!(f, "{} Duplicate sort param key: {}", msg, desc),
+            InvalidIncludeValue(ref desc) => write!(f, "{} Invalid include path: {}", msg, desc),
+            DuplicateIncludeKey(ref desc) => {
+                write!(f, "{} Duplicate include param key: {}", msg, desc)
+            }
+            InvalidFilterKey(ref desc) => write!(f, "{} Invalid filter key: {}", msg, desc),
+            InvalidFilterValue(ref desc) => {
+                write!(f, "{} Invalid value for filter[{}]", msg, desc)
+            }
+            InvalidFilterOperator(ref field, ref operator) => {
+                write!(f, "{} Invalid filter operator: {}[{}]", msg, field, operator)
+            }
+            InvalidPageKey(ref desc) => write!(f, "{} Invalid page key: {}", msg, desc),
+            InvalidPageValue(ref desc) => write!(f, "{} Invalid value for {}", msg, desc),
             UnImplementedError => write!(f, "{} Unimplemented!", msg)
         }
     }
@@ -53,15 +97,51 @@ impl Error for QueryStringParseError {
     }
 }
 
+impl JsonApiErrorDetails for QueryStringParseError {
+    /// The query parameter that couldn't be parsed, e.g `sort` or `page[size]`.
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        use self::QueryStringParseError::*;
+
+        let parameter = match *self {
+            InvalidSortValue(_) | DuplicateSortKey(_) => "sort".to_string(),
+            InvalidIncludeValue(_) | DuplicateIncludeKey(_) => "include".to_string(),
+            InvalidFieldsetKey(ref key) => key.clone(),
+            InvalidFieldValue(_) => "fields".to_string(),
+            EmptyFieldsetValue(ref model) | InvalidFieldsetType(ref model) => {
+                format!("fields[{}]", model)
+            }
+            InvalidFilterKey(ref key) if key.starts_with("filter") => key.clone(),
+            InvalidFilterKey(ref field) | InvalidFilterValue(ref field) => {
+                format!("filter[{}]", field)
+            }
+            InvalidFilterOperator(ref field, ref operator) => {
+                format!("filter[{}][{}]", field, operator)
+            }
+            InvalidPageKey(ref key) | InvalidPageValue(ref key) => key.clone(),
+            UnImplementedError => return None
+        };
+
+        Some(JsonApiErrorSource::parameter(parameter))
+    }
+}
+
 fn description(error: &QueryStringParseError) -> &str {
     use self::QueryStringParseError::*;
 
     match *error {
         InvalidFieldValue(ref desc) => desc,
+        InvalidFieldsetType(ref desc) => desc,
         InvalidSortValue(ref desc) => desc,
         InvalidFieldsetKey(ref desc) => desc,
         EmptyFieldsetValue(ref desc) => desc,
         DuplicateSortKey(ref desc) => desc,
+        InvalidIncludeValue(ref desc) => desc,
+        DuplicateIncludeKey(ref desc) => desc,
+        InvalidFilterKey(ref desc) => desc,
+        InvalidFilterValue(ref desc) => desc,
+        InvalidFilterOperator(_, ref operator) => operator,
+        InvalidPageKey(ref desc) => desc,
+        InvalidPageValue(ref desc) => desc,
         UnImplementedError => UNIMPLEMENTED
     }
 }
@@ -1,24 +1,98 @@
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
 use std::error::Error;
 use std::fmt::*;
 
 static NO_BODY: &'static str = "No body";
 static NOT_FOUND: &'static str = "Not found";
+static NOT_TO_MANY: &'static str = "Not a to-many relationship";
+static INVALID_LINKAGE: &'static str = "Invalid resource linkage";
+static LINKAGE_TYPE_MISMATCH: &'static str = "Resource linkage type mismatch";
+static UNSUPPORTED_MEDIA_TYPE: &'static str = "Unsupported media type";
+static NOT_ACCEPTABLE: &'static str = "Not acceptable";
+static INVALID_OPERATION: &'static str = "Invalid operation";
+static BODY_TOO_LARGE: &'static str = "Body too large";
+static UNREADABLE_BODY: &'static str = "Unreadable body";
+static MISSING_ROUTE_PARAM: &'static str = "Missing route parameter";
+static METHOD_NOT_ALLOWED: &'static str = "Method not allowed";
+static MISSING_CREATED_RESOURCE: &'static str = "Missing created resource";
 
 #[derive(Debug, Copy, Clone)]
 /// Wraps request related errors
 ///
-/// This is a container for HTTP related errors. Currently there are only variants for not `POST`ing
-/// or `PUT`ing a body, or if a resource cannot be found.
+/// This is a container for HTTP related errors. Currently there are variants for not `POST`ing
+/// or `PUT`ing a body, if a resource cannot be found, for invalid requests to relationship
+/// endpoints, for requests that fail content negotiation, for invalid Atomic Operations, for
+/// request bodies that are too large or can't be read, for requests that were routed to a
+/// dispatcher without the route parameters it expects, for methods that a path doesn't support,
+/// and for created resources that weren't returned although the server generated their id.
 pub enum RequestError {
     NoBody,
-    NotFound
+    NotFound,
+    /// Members were added to or removed from a to-one relationship.
+    NotToMany,
+    /// The resource linkage doesn't match the cardinality of the relationship.
+    InvalidLinkage,
+    /// A resource identifier in the resource linkage doesn't have the type of the relationship.
+    LinkageTypeMismatch,
+    /// The request body doesn't have the JSONAPI media type, or has media type parameters.
+    UnsupportedMediaType,
+    /// All JSONAPI media types in the `Accept` header have media type parameters.
+    NotAcceptable,
+    /// An Atomic Operations operation is missing a member or targets a resource type that
+    /// doesn't support the operation. An unknown local id is a `LocalIdError`.
+    InvalidOperation,
+    /// The request body is larger than the maximum body length of the server.
+    BodyTooLarge,
+    /// The request body couldn't be read, e.g because the connection was closed.
+    UnreadableBody,
+    /// The `id` or `relationship` parameter of the route is missing from the `JsonApiRequest`,
+    /// i.e an adapter routed the request to the wrong dispatcher.
+    MissingRouteParam,
+    /// A route matches the path of the request, but none of the routes of the path matches its
+    /// method.
+    MethodNotAllowed,
+    /// `JsonPost::create` returned `Ok(None)` for a resource without a client-generated id, so
+    /// the id that the server generated can't be sent to the client.
+    MissingCreatedResource
 }
 
 impl Display for RequestError {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match *self {
             RequestError::NoBody => write!(f, "{}", self.description()),
-            RequestError::NotFound => write!(f, "{}", self.description())
+            RequestError::NotFound => write!(f, "{}", self.description()),
+            RequestError::NotToMany => {
+                write!(f, "Members can only be added to or removed from a to-many relationship")
+            }
+            RequestError::InvalidLinkage => {
+                write!(f, "The resource linkage doesn't match the relationship")
+            }
+            RequestError::LinkageTypeMismatch => {
+                write!(f, "The resource linkage has the wrong type for the relationship")
+            }
+            RequestError::UnsupportedMediaType => {
+                write!(f, "The request body must have the JSONAPI media type without parameters")
+            }
+            RequestError::NotAcceptable => {
+                write!(f, "The JSONAPI media type is only accepted with media type parameters")
+            }
+            RequestError::InvalidOperation => {
+                write!(f, "The operation is incomplete or isn't supported for its resource type")
+            }
+            RequestError::BodyTooLarge => {
+                write!(f, "The request body is larger than the maximum body length")
+            }
+            RequestError::UnreadableBody => write!(f, "The request body couldn't be read"),
+            RequestError::MissingRouteParam => {
+                write!(f, "The request was routed without a parameter that the endpoint expects")
+            }
+            RequestError::MethodNotAllowed => {
+                write!(f, "The method of the request isn't supported for its path")
+            }
+            RequestError::MissingCreatedResource => {
+                write!(f, "The created resource has a server-generated id, but wasn't returned")
+            }
         }
     }
 }
@@ -27,13 +101,47 @@ impl Error for RequestError {
     fn description(&self) -> &str {
         match *self {
             RequestError::NoBody => NO_BODY,
-            RequestError::NotFound => NOT_FOUND
+            RequestError::NotFound => NOT_FOUND,
+            RequestError::NotToMany => NOT_TO_MANY,
+            RequestError::InvalidLinkage => INVALID_LINKAGE,
+            RequestError::LinkageTypeMismatch => LINKAGE_TYPE_MISMATCH,
+            RequestError::UnsupportedMediaType => UNSUPPORTED_MEDIA_TYPE,
+            RequestError::NotAcceptable => NOT_ACCEPTABLE,
+            RequestError::InvalidOperation => INVALID_OPERATION,
+            RequestError::BodyTooLarge => BODY_TOO_LARGE,
+            RequestError::UnreadableBody => UNREADABLE_BODY,
+            RequestError::MissingRouteParam => MISSING_ROUTE_PARAM,
+            RequestError::MethodNotAllowed => METHOD_NOT_ALLOWED,
+            RequestError::MissingCreatedResource => MISSING_CREATED_RESOURCE
         }
     }
 
     fn cause(&self) -> Option<&Error> {
         match *self {
-            RequestError::NoBody | RequestError::NotFound => None
+            RequestError::NoBody |
+            RequestError::NotFound |
+            RequestError::NotToMany |
+            RequestError::InvalidLinkage |
+            RequestError::LinkageTypeMismatch |
+            RequestError::UnsupportedMediaType |
+            RequestError::NotAcceptable |
+            RequestError::InvalidOperation |
+            RequestError::BodyTooLarge |
+            RequestError::UnreadableBody |
+            RequestError::MissingRouteParam |
+            RequestError::MethodNotAllowed |
+            RequestError::MissingCreatedResource => None
+        }
+    }
+}
+
+impl JsonApiErrorDetails for RequestError {
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        match *self {
+            RequestError::InvalidLinkage | RequestError::LinkageTypeMismatch => {
+                Some(JsonApiErrorSource::pointer("/data"))
+            }
+            _ => None
         }
     }
 }
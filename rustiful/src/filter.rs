@@ -0,0 +1,194 @@
+#[cfg(feature = "uuid")]
+extern crate uuid;
+
+use errors::QueryStringParseError;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+/// The comparison operator of a `filter[*]` query parameter, e.g `gt` in `filter[created][gt]=1`.
+/// A filter without an operator, such as `filter[created]=1`, is equivalent to `eq`.
+pub enum FilterOperator {
+    /// `filter[field]=value` or `filter[field][eq]=value`
+    Eq,
+    /// `filter[field][ne]=value`
+    Ne,
+    /// `filter[field][lt]=value`
+    Lt,
+    /// `filter[field][gt]=value`
+    Gt,
+    /// `filter[field][in]=a,b,c`
+    In,
+    /// `filter[field][like]=value`
+    Like
+}
+
+impl FromStr for FilterOperator {
+    type Err = ();
+
+    fn from_str(operator: &str) -> Result<Self, Self::Err> {
+        match operator {
+            "eq" => Ok(FilterOperator::Eq),
+            "ne" => Ok(FilterOperator::Ne),
+            "lt" => Ok(FilterOperator::Lt),
+            "gt" => Ok(FilterOperator::Gt),
+            "in" => Ok(FilterOperator::In),
+            "like" => Ok(FilterOperator::Like),
+            _ => Err(())
+        }
+    }
+}
+
+/// A type that can be filtered on with the `filter[*]` query parameter.
+///
+/// By default only `eq`, `ne` and `in` are supported. Attributes of the types that rustiful
+/// implements this for can be filtered on automatically. To filter on an attribute of any other
+/// type, implement this trait for the type and annotate the attribute with `#[jsonapi(filter)]`.
+pub trait Filterable: FromStr {
+    /// Whether `operator` can be used on a field of this type.
+    fn supports(operator: FilterOperator) -> bool {
+        match operator {
+            FilterOperator::Eq | FilterOperator::Ne | FilterOperator::In => true,
+            _ => false
+        }
+    }
+}
+
+/// Implements `Filterable` for types that can be compared with `lt` and `gt`.
+macro_rules! ordered_filterable {
+    ($($ty:ty),*) => {
+        $(
+            impl Filterable for $ty {
+                fn supports(operator: FilterOperator) -> bool {
+                    operator != FilterOperator::Like
+                }
+            }
+        )*
+    }
+}
+
+ordered_filterable!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize, f32, f64, char);
+
+impl Filterable for bool {}
+
+impl Filterable for String {
+    fn supports(_: FilterOperator) -> bool {
+        true
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl Filterable for self::uuid::Uuid {}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// The operator and the typed value of a `filter[*]` query parameter.
+pub enum FilterValue<T> {
+    Eq(T),
+    Ne(T),
+    Lt(T),
+    Gt(T),
+    In(Vec<T>),
+    Like(T)
+}
+
+impl<T> FilterValue<T> {
+    pub fn operator(&self) -> FilterOperator {
+        match *self {
+            FilterValue::Eq(_) => FilterOperator::Eq,
+            FilterValue::Ne(_) => FilterOperator::Ne,
+            FilterValue::Lt(_) => FilterOperator::Lt,
+            FilterValue::Gt(_) => FilterOperator::Gt,
+            FilterValue::In(_) => FilterOperator::In,
+            FilterValue::Like(_) => FilterOperator::Like
+        }
+    }
+}
+
+impl<T: Filterable> FilterValue<T> {
+    /// Parses the value of `filter[field][operator]`. An empty `operator` is equivalent to `eq`,
+    /// and the value of an `in` filter is a comma-separated list.
+    ///
+    /// # Errors
+    ///
+    /// * `InvalidFilterOperator` if `operator` isn't a known operator, or if it isn't supported by
+    /// `T`.
+    /// * `InvalidFilterValue` if `value` (or any part of it, for `in`) cannot be parsed to `T`.
+    pub fn parse(field: &str, operator: &str, value: &str) -> Result<Self, QueryStringParseError> {
+        let op = if operator.is_empty() {
+            FilterOperator::Eq
+        } else {
+            match FilterOperator::from_str(operator) {
+                Ok(op) if T::supports(op) => op,
+                _ => {
+                    return Err(QueryStringParseError::InvalidFilterOperator(
+                        field.to_string(),
+                        operator.to_string()
+                    ))
+                }
+            }
+        };
+
+        let parse = |v: &str| {
+            T::from_str(v).map_err(|_| QueryStringParseError::InvalidFilterValue(field.to_string()))
+        };
+
+        match op {
+            FilterOperator::Eq => parse(value).map(FilterValue::Eq),
+            FilterOperator::Ne => parse(value).map(FilterValue::Ne),
+            FilterOperator::Lt => parse(value).map(FilterValue::Lt),
+            FilterOperator::Gt => parse(value).map(FilterValue::Gt),
+            FilterOperator::Like => parse(value).map(FilterValue::Like),
+            FilterOperator::In => {
+                let values: Vec<_> = value.split(',').filter(|&v| !v.is_empty()).collect();
+
+                if values.is_empty() {
+                    return Err(QueryStringParseError::InvalidFilterValue(field.to_string()));
+                }
+
+                values
+                    .into_iter()
+                    .map(parse)
+                    .collect::<Result<Vec<_>, _>>()
+                    .map(FilterValue::In)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_filter_without_operator() {
+        assert_eq!(Ok(FilterValue::Eq(true)), FilterValue::parse("foo", "", "true"));
+    }
+
+    #[test]
+    fn parse_in_filter() {
+        assert_eq!(Ok(FilterValue::In(vec![1, 2])), FilterValue::parse("foo", "in", "1,2"));
+    }
+
+    #[test]
+    fn parse_filter_fails_on_unsupported_operator() {
+        assert_eq!(
+            Err(QueryStringParseError::InvalidFilterOperator("foo".to_string(), "lt".to_string())),
+            FilterValue::<bool>::parse("foo", "lt", "true")
+        );
+    }
+
+    #[test]
+    fn parse_filter_fails_on_unknown_operator() {
+        assert_eq!(
+            Err(QueryStringParseError::InvalidFilterOperator("foo".to_string(), "abc".to_string())),
+            FilterValue::<String>::parse("foo", "abc", "bar")
+        );
+    }
+
+    #[test]
+    fn parse_in_filter_fails_on_invalid_value() {
+        assert_eq!(
+            Err(QueryStringParseError::InvalidFilterValue("foo".to_string())),
+            FilterValue::<i32>::parse("foo", "in", "1,abc")
+        );
+    }
+}
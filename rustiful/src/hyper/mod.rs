@@ -0,0 +1,83 @@
+//! A `hyper` 0.12 adapter, as an alternative to the Iron adapter in `rustiful::iron`.
+//!
+//! `JsonApiServiceBuilder` registers the endpoints of resources in the same way as
+//! `JsonApiRouterBuilder`, and builds a `JsonApiService` which routes each request to the right
+//! dispatcher (see `rustiful::GetDispatcher` et cetera), once its content has been negotiated with
+//! `rustiful::ContentNegotiation`. Async resources are registered with `jsonapi_get_async` et
+//! cetera, and their futures are polled on the executor of hyper.
+
+mod from_request;
+pub use self::from_request::*;
+
+mod service;
+pub use self::service::*;
+
+extern crate hyper;
+
+use self::hyper::Body;
+use self::hyper::Request;
+use self::hyper::Response;
+use self::hyper::StatusCode;
+use dispatch::JsonApiResponse;
+use routes::RequestHead;
+use service::HttpStatus;
+
+impl HttpStatus for StatusCode {
+    fn status_code(&self) -> u16 {
+        self.as_u16()
+    }
+
+    fn from_status_code(code: u16) -> Self {
+        StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+/// A hyper request without its body, which has been read into the `JsonApiRequest` by then.
+impl RequestHead for Request<()> {
+    fn method(&self) -> &str {
+        self.method().as_str()
+    }
+
+    fn path(&self) -> &str {
+        self.uri().path()
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.uri().query()
+    }
+
+    fn origin(&self) -> Option<String> {
+        let uri = self.uri();
+
+        match (uri.scheme_part(), uri.authority_part()) {
+            (Some(scheme), Some(authority)) => Some(format!("{}://{}", scheme, authority)),
+            _ => None
+        }
+    }
+
+    fn headers(&self) -> Vec<(&str, &str)> {
+        self.headers()
+            .iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|value| (name.as_str(), value)))
+            .collect()
+    }
+}
+
+fn hyper_response(response: JsonApiResponse) -> Response<Body> {
+    let mut builder = Response::builder();
+    builder.status(
+        StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    );
+
+    for &(ref name, ref value) in &response.headers {
+        builder.header(name.as_str(), value.as_str());
+    }
+
+    let body = response.body.map_or_else(Body::empty, Body::from);
+
+    builder.body(body).unwrap_or_else(|_| {
+        let mut result = Response::new(Body::empty());
+        *result.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        result
+    })
+}
@@ -0,0 +1,492 @@
+extern crate futures;
+extern crate hyper;
+
+use self::futures::Async;
+use self::futures::future;
+use self::futures::future::FutureResult;
+use self::futures::task;
+use self::futures::task::Task;
+use self::hyper::Body;
+use self::hyper::Method;
+use self::hyper::Request;
+use self::hyper::Response;
+use self::hyper::rt::Future;
+use self::hyper::rt::Stream;
+use self::hyper::service::NewService;
+use self::hyper::service::Service;
+use super::from_request::FromRequest;
+use super::hyper_response;
+use dispatch::*;
+use errors::QueryStringParseError;
+use errors::RequestError;
+use operations::ATOMIC_EXTENSION;
+use operations::JsonApiTransaction;
+use params::JsonApiFieldSetField;
+use params::SortOrder;
+use relationship::JsonApiRelationshipField;
+use resource::JsonApiResource;
+use routes::Routes;
+use routes::base_url;
+use routes::json_api_request;
+use service::JsonGetRelated;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use std::task::Wake;
+use std::task::Waker;
+use to_json::ToJson;
+
+/// Constructs a hyper `Service` for resources implementing any of the `JsonGet`, `JsonPost`,
+/// `JsonIndex`, `JsonPatch` or `JsonDelete` traits, or their async variants such as
+/// `AsyncJsonGet`.
+///
+/// This has the same registration methods as `JsonApiRouterBuilder`, and registers the same
+/// routes. The context of the service traits is created with `rustiful::hyper::FromRequest`
+/// instead of `rustiful::iron::FromRequest`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// extern crate hyper;
+///
+/// use hyper::Server;
+/// use rustiful::hyper::JsonApiServiceBuilder;
+///
+/// let mut builder = JsonApiServiceBuilder::default();
+/// builder.jsonapi_get::<MyResource>();
+/// builder.jsonapi_index::<MyResource>();
+///
+/// let addr = ([127, 0, 0, 1], 3000).into();
+/// let server = Server::bind(&addr).serve(builder.build());
+/// hyper::rt::run(server.map_err(|e| eprintln!("server error: {}", e)));
+/// ```
+#[allow(missing_debug_implementations)] // The dispatch closures don't implement Debug...
+pub struct JsonApiServiceBuilder {
+    routes: Routes<Request<()>, JsonApiResponseFuture>,
+    max_body_length: usize,
+    base_url: Option<String>,
+    negotiation: ContentNegotiation
+}
+
+/// This `Default` implementation sets the maximum body length to 10MB.
+impl Default for JsonApiServiceBuilder {
+    fn default() -> Self {
+        Self::new(10 * 1024 * 1024)
+    }
+}
+
+impl JsonApiServiceBuilder {
+    /// Constructs a new `JsonApiServiceBuilder` with a maximum body length in bytes.
+    pub fn new(max_body_length: usize) -> Self {
+        JsonApiServiceBuilder {
+            routes: Routes::default(),
+            max_body_length: max_body_length,
+            base_url: None,
+            negotiation: ContentNegotiation::new()
+        }
+    }
+
+    /// Sets the max body length for any incoming JSON document. This is specified in bytes.
+    /// Requests with a larger body get a `413 Payload Too Large` response.
+    pub fn set_max_body_length(&mut self, max_body_length: usize) {
+        self.max_body_length = max_body_length;
+    }
+
+    /// Sets the base URL of the `self` links in any responses, such as `https://example.com/api`.
+    ///
+    /// If no base URL is set, the scheme, host and port of each request are used instead. Set
+    /// this when the server is behind a proxy, or when the service is mounted under a prefix.
+    pub fn set_base_url<S: Into<String>>(&mut self, base_url: S) {
+        self.base_url = Some(base_url.into());
+    }
+
+    /// Declares support for the JSONAPI extension with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_extension`.
+    pub fn add_extension<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_extension(uri);
+    }
+
+    /// Declares support for the JSONAPI profile with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_profile`.
+    pub fn add_profile<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_profile(uri);
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `JsonIndex`.
+    pub fn jsonapi_index<T>(&mut self)
+    where
+        T: 'static,
+        T: IndexDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::GET, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `JsonGet`.
+    pub fn jsonapi_get<T>(&mut self)
+    where
+        T: 'static,
+        T: GetDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::GET, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `JsonDelete`.
+    pub fn jsonapi_delete<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::DELETE, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `JsonPost`.
+    pub fn jsonapi_post<T>(&mut self)
+    where
+        T: 'static,
+        T: PostDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::POST, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `JsonPatch`.
+    pub fn jsonapi_patch<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::PATCH, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonGetRelationship`.
+    pub fn jsonapi_get_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: GetRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::GET, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPatchRelationship`.
+    pub fn jsonapi_patch_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::PATCH, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPostRelationship`.
+    pub fn jsonapi_post_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PostRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::POST, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonDeleteRelationship`.
+    pub fn jsonapi_delete_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::DELETE, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the routes `GET /my-resources/{id}/{relationship}` for a struct that implements
+    /// `JsonGetRelated<R>`, for each relationship that refers to `R`. Call this once for each
+    /// type of related resource.
+    pub fn jsonapi_get_related<T, R>(&mut self)
+    where
+        T: 'static,
+        T: GetRelatedDispatcher<R>,
+        T::RelationshipField: Copy + Send + Sync + 'static,
+        <T as JsonGetRelated<R>>::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error,
+        R: JsonApiResource + ToJson + 'static
+    {
+        let relationships = T::RelationshipField::all()
+            .into_iter()
+            .filter(|r| r.type_name() == R::RESOURCE_NAME);
+
+        for relationship in relationships {
+            let path = format!("/{}/:id/{}", T::RESOURCE_NAME, relationship.name());
+            self.route(Method::GET, path, move |req, r| {
+                let context =
+                    || <<T as JsonGetRelated<R>>::Context as FromRequest>::from_request(r);
+                <T as GetRelatedDispatcher<R>>::dispatch(req, relationship, context)
+            });
+        }
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `AsyncJsonIndex`.
+    pub fn jsonapi_index_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncIndexDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::GET, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `AsyncJsonGet`.
+    pub fn jsonapi_get_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncGetDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::GET, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `AsyncJsonDelete`.
+    pub fn jsonapi_delete_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncDeleteDispatcher,
+        T::AsyncContext: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::DELETE, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `AsyncJsonPost`.
+    pub fn jsonapi_post_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPostDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::POST, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `AsyncJsonPatch`.
+    pub fn jsonapi_patch_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPatchDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::PATCH, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /operations` for the Atomic Operations extension, see
+    /// `JsonApiRouterBuilder::jsonapi_operations`.
+    pub fn jsonapi_operations<C>(&mut self, operations: OperationsHandler<C>)
+    where
+        C: FromRequest + JsonApiTransaction + Clone + 'static
+    {
+        self.add_extension(ATOMIC_EXTENSION);
+        self.route(Method::POST, "/operations".to_string(), move |req, r| {
+            operations.dispatch(req, || <C as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Constructs a `JsonApiService` with the routes that were previously specified in
+    /// `jsonapi_get`, `jsonapi_post` et cetera. The service can be served directly with
+    /// `hyper::Server::serve`, since it also implements `NewService`.
+    pub fn build(self) -> JsonApiService {
+        JsonApiService {
+            inner: Arc::new(self)
+        }
+    }
+
+    fn route<F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        F: Fn(&JsonApiRequest, &Request<()>) -> JsonApiResponse + Send + Sync + 'static
+    {
+        self.route_async(method, path, move |req, r| dispatch(req, r).into());
+    }
+
+    fn route_async<F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        F: Fn(&JsonApiRequest, &Request<()>) -> JsonApiResponseFuture + Send + Sync + 'static
+    {
+        self.routes.add(method.as_str(), &path, dispatch);
+    }
+
+    /// Dispatches the request, along with the extensions and profiles that apply to it. These
+    /// are sent in the `Content-Type` once the response is ready.
+    fn respond(
+        &self,
+        req: &Request<()>,
+        body: Option<Vec<u8>>
+    ) -> (JsonApiResponseFuture, JsonApiMediaType) {
+        let body = match body {
+            Some(body) => body,
+            None => {
+                let response = JsonApiResponse::error(&RequestError::BodyTooLarge, 413);
+                return (response.into(), JsonApiMediaType::default());
+            }
+        };
+
+        let request = json_api_request(req, body, base_url(req, &self.base_url));
+        match self.negotiation.negotiate(&request) {
+            Ok(media_type) => {
+                let request = request.with_media_type(media_type.clone());
+                (self.routes.dispatch(request, req), media_type)
+            }
+            Err((e, status)) => {
+                (JsonApiResponse::error(&e, status).into(), JsonApiMediaType::default())
+            }
+        }
+    }
+}
+
+/// A hyper `Service` that routes requests to the endpoints of the registered resources. This is
+/// constructed with `JsonApiServiceBuilder`, and is cheap to clone.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)] // The dispatch closures don't implement Debug...
+pub struct JsonApiService {
+    inner: Arc<JsonApiServiceBuilder>
+}
+
+impl Service for JsonApiService {
+    type ReqBody = Body;
+    type ResBody = Body;
+    type Error = hyper::Error;
+    type Future = Box<Future<Item = Response<Body>, Error = hyper::Error> + Send>;
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let inner = self.inner.clone();
+        let max_body_length = inner.max_body_length;
+        let (parts, body) = req.into_parts();
+
+        // The body is `None` once it's larger than the max body length.
+        let body = body.fold(Some(Vec::new()), move |body, chunk| {
+            let body = body.and_then(|mut body: Vec<u8>| {
+                if body.len() + chunk.len() > max_body_length {
+                    None
+                } else {
+                    body.extend_from_slice(&chunk);
+                    Some(body)
+                }
+            });
+
+            future::ok::<_, hyper::Error>(body)
+        });
+
+        Box::new(body.and_then(move |body| {
+            let req = Request::from_parts(parts, ());
+            let (response, media_type) = inner.respond(&req, body);
+            Compat(response).map(move |response| {
+                hyper_response(response.with_media_type(&media_type))
+            })
+        }))
+    }
+}
+
+impl NewService for JsonApiService {
+    type ReqBody = Body;
+    type ResBody = Body;
+    type Error = hyper::Error;
+    type Service = JsonApiService;
+    type Future = FutureResult<JsonApiService, hyper::Error>;
+    type InitError = hyper::Error;
+
+    fn new_service(&self) -> Self::Future {
+        future::ok(self.clone())
+    }
+}
+
+/// Polls the `JsonApiResponseFuture` of a dispatcher on the executor of hyper, which runs
+/// futures 0.1 futures. The current task of that executor is notified whenever the future is
+/// woken.
+struct Compat(JsonApiResponseFuture);
+
+impl Future for Compat {
+    type Item = JsonApiResponse;
+    type Error = hyper::Error;
+
+    fn poll(&mut self) -> Result<Async<JsonApiResponse>, hyper::Error> {
+        let waker = Waker::from(Arc::new(Notify(task::current())));
+        let mut context = Context::from_waker(&waker);
+
+        match std::future::Future::poll(self.0.as_mut(), &mut context) {
+            Poll::Ready(response) => Ok(Async::Ready(response)),
+            Poll::Pending => Ok(Async::NotReady)
+        }
+    }
+}
+
+/// Wakes a futures 0.1 task.
+struct Notify(Task);
+
+impl Wake for Notify {
+    fn wake(self: Arc<Self>) {
+        self.0.notify();
+    }
+}
@@ -0,0 +1,49 @@
+extern crate iron;
+extern crate router;
+
+use self::iron::AfterMiddleware;
+use self::iron::prelude::*;
+use self::router::NoRoute;
+use super::iron_response;
+use route_path::RoutePath;
+use route_path::method_not_allowed;
+
+/// The methods and paths of the routes of a `JsonApiRouterBuilder`. Iron's `Router` answers a
+/// request without a route for its method with a `404 Not Found`, so this catches that error and
+/// answers `405 Method Not Allowed` with an `Allow` header if routes with other methods match the
+/// path of the request.
+#[derive(Default)]
+pub struct AllowedMethods {
+    routes: Vec<(String, RoutePath)>
+}
+
+impl AllowedMethods {
+    /// Adds the route for `method` and `path`, where `path` has a `:name` segment for each path
+    /// parameter, e.g `/foos/:id`.
+    pub fn add(&mut self, method: &str, path: &str) {
+        self.routes.push((method.to_string(), RoutePath::new(path)));
+    }
+}
+
+impl AfterMiddleware for AllowedMethods {
+    fn catch(&self, req: &mut Request, err: IronError) -> IronResult<Response> {
+        if !err.error.is::<NoRoute>() {
+            return Err(err);
+        }
+
+        let path = format!("/{}", req.url.path().join("/"));
+        let mut allowed: Vec<&str> = Vec::new();
+
+        for &(ref method, ref route) in &self.routes {
+            if route.matches(&path).is_some() && !allowed.contains(&method.as_str()) {
+                allowed.push(method);
+            }
+        }
+
+        if allowed.is_empty() {
+            return Err(err);
+        }
+
+        iron_response(method_not_allowed(&allowed))
+    }
+}
@@ -1,77 +1,175 @@
-mod handlers;
-
 mod from_request;
 pub use self::from_request::*;
 
 mod router_builder;
 pub use self::router_builder::*;
 
+mod negotiation;
+
+mod allowed_methods;
+
 extern crate iron;
 extern crate router;
 extern crate bodyparser;
+extern crate persistent;
 extern crate serde;
 extern crate serde_json;
 
-use self::iron::mime::Mime;
 use self::iron::prelude::*;
+use self::iron::typemap::Key;
+use self::persistent::Read;
+use self::serde_json::error::Category;
 use self::status::Status;
-use container::JsonApiContainer;
-use error::JsonApiErrorArray;
+use dispatch::JsonApiMediaType;
+use dispatch::JsonApiRequest;
+use dispatch::JsonApiResponse;
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
+use errors::RequestError;
 use iron::router::Router;
-use resource::JsonApiResource;
-use serde::Serialize;
-use service::Handler;
-use std::error::Error;
+use route_path::decode_param;
+use service::HttpStatus;
+use std::io;
+use std::usize;
+
+impl HttpStatus for Status {
+    fn status_code(&self) -> u16 {
+        self.to_u16()
+    }
 
-impl<T: JsonApiResource> Handler for T {
-    type Status = Status;
+    fn from_status_code(code: u16) -> Self {
+        Status::from_u16(code)
+    }
 }
 
 /// Status Codes
 pub mod status {
-    extern crate hyper;
-
-    pub use self::hyper::status::StatusClass;
-    pub use self::hyper::status::StatusCode as Status;
-    pub use self::hyper::status::StatusCode::*;
-}
+    extern crate iron;
 
-fn json_api_type() -> Mime {
-    "application/vnd.api+json".parse().unwrap()
+    pub use self::iron::status::*;
 }
 
 #[derive(Debug)]
-struct JsonErrorResponse<E: Error>(E, Status);
+struct JsonErrorResponse<E: JsonApiErrorDetails>(E, Status);
 
-impl<E: Error> From<JsonErrorResponse<E>> for IronResult<Response> {
+impl<E: JsonApiErrorDetails> From<JsonErrorResponse<E>> for IronResult<Response> {
     fn from(err: JsonErrorResponse<E>) -> IronResult<Response> {
-        match serde_json::to_string(&JsonApiErrorArray::new(&err.0, err.1.to_u16())) {
-            Ok(serialized) => Ok(Response::with((json_api_type(), err.1, serialized))),
-            Err(e) => Err(IronError::new(e, Status::InternalServerError))
+        iron_response(JsonApiResponse::error(&err.0, err.1.to_u16()))
+    }
+}
+
+/// Converts an Iron request to a `JsonApiRequest`, with the percent-decoded `id` and
+/// `relationship` parameters of the route.
+fn json_api_request(req: &mut Request) -> Result<JsonApiRequest, (RequestError, u16)> {
+    let body = read_body(req)?;
+    let base_url = base_url(req);
+    let path = req.url.path().join("/");
+
+    let mut request = JsonApiRequest::new(req.method.to_string(), format!("/{}", path))
+        .with_query(req.url.query().unwrap_or(""))
+        .with_body(body)
+        .with_base_url(base_url);
+
+    if let Some(router) = req.extensions.get::<Router>() {
+        for name in &["id", "relationship"] {
+            if let Some(value) = router.find(name) {
+                request = request.with_param(*name, decode_param(value));
+            }
         }
     }
+
+    for header in req.headers.iter() {
+        request = request.with_header(header.name(), header.value_string());
+    }
+
+    if let Some(media_type) = req.extensions.get::<JsonApiMediaType>() {
+        request = request.with_media_type(media_type.clone());
+    }
+
+    Ok(request)
 }
 
-#[derive(Debug)]
-struct JsonOkResponse<T: Serialize>(T);
-
-impl<T: Serialize> From<JsonOkResponse<T>> for IronResult<Response> {
-    fn from(data: JsonOkResponse<T>) -> IronResult<Response> {
-        let json = JsonApiContainer { data: data.0 };
-        match serde_json::to_string(&json) {
-            Ok(serialized) => Ok(Response::with((json_api_type(), Status::Ok, serialized))),
-            Err(e) => Err(IronError::new(e, Status::InternalServerError))
+/// Reads the body of `req`. A body larger than the max body length that is set with
+/// `JsonApiRouterBuilder::set_max_body_length` is a `413 Payload Too Large` error, like with the
+/// other adapters.
+fn read_body(req: &mut Request) -> Result<Vec<u8>, (RequestError, u16)> {
+    let max_body_length = req.get::<Read<bodyparser::MaxBodyLength>>()
+        .map(|max_body_length| *max_body_length)
+        .unwrap_or(usize::MAX);
+
+    let mut body = Vec::new();
+    let limit = (max_body_length as u64).saturating_add(1);
+    let mut reader = io::Read::take(&mut req.body, limit);
+
+    match io::Read::read_to_end(&mut reader, &mut body) {
+        Ok(_) if body.len() > max_body_length => Err((RequestError::BodyTooLarge, 413)),
+        Ok(_) => Ok(body),
+        Err(_) => Err((RequestError::UnreadableBody, 400))
+    }
+}
+
+fn iron_response(response: JsonApiResponse) -> IronResult<Response> {
+    let status = Status::from_u16(response.status);
+    let mut result = match response.body {
+        Some(body) => Response::with((status, body)),
+        None => Response::with(status)
+    };
+
+    for (name, value) in response.headers {
+        result.headers.set_raw(name, vec![value.into_bytes()]);
+    }
+
+    Ok(result)
+}
+
+/// Converts `req` to a `JsonApiRequest`, and converts the response of `dispatch` back to an Iron
+/// response. `dispatch` also gets the Iron request, so that it can create a `FromRequest` context.
+fn respond<F>(req: &mut Request, dispatch: F) -> IronResult<Response>
+where
+    F: FnOnce(&JsonApiRequest, &Request) -> JsonApiResponse
+{
+    match json_api_request(req) {
+        Ok(request) => iron_response(dispatch(&request, req)),
+        Err((e, status)) => iron_response(JsonApiResponse::error(&e, status))
+    }
+}
+
+/// A request document that is valid JSON, but can't be deserialized, points at its primary data.
+impl JsonApiErrorDetails for bodyparser::BodyError {
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        match self.cause {
+            bodyparser::BodyErrorCause::JsonError(ref e) if e.classify() == Category::Data => {
+                Some(JsonApiErrorSource::pointer("/data"))
+            }
+            _ => None
         }
     }
 }
 
-fn id<'a>(req: &'a Request) -> &'a str {
-    let router = req.extensions
-        .get::<Router>()
-        .expect("Expected to get a Router from the request extensions.");
-    router
-        .find("id")
-        .expect("No id param found in method that expects one!")
+/// The key of the base URL set with `JsonApiRouterBuilder::set_base_url`.
+#[derive(Debug, Copy, Clone)]
+struct BaseUrl;
+
+impl Key for BaseUrl {
+    type Value = String;
+}
+
+/// The base URL of any links in a response. This is the base URL set with
+/// `JsonApiRouterBuilder::set_base_url` if there is one, otherwise it is the scheme, host and port
+/// of the request URL.
+fn base_url(req: &mut Request) -> String {
+    if let Ok(base_url) = req.get::<Read<BaseUrl>>() {
+        return base_url.as_ref().clone();
+    }
+
+    let url = &req.url;
+    let default_port = if url.scheme() == "https" { 443 } else { 80 };
+
+    if url.port() == default_port {
+        format!("{}://{}", url.scheme(), url.host())
+    } else {
+        format!("{}://{}:{}", url.scheme(), url.host(), url.port())
+    }
 }
 
 #[cfg(test)]
@@ -84,10 +182,9 @@ mod tests {
     use self::iron_test::response;
     use super::*;
     use super::iron::headers::ContentType;
+    use error::JsonApiErrorArray;
     use errors::IdParseError;
     use errors::QueryStringParseError;
-    use serde::de::DeserializeOwned;
-    use std::collections::HashMap;
 
     // Use `QueryStringParseError` to be wrapped in `RepositoryError`, for convenience
     impl<'a> From<&'a QueryStringParseError> for Status {
@@ -96,61 +193,50 @@ mod tests {
         }
     }
 
-    // A hacky version of `JsonApiData<T>`, since the attributes field requires a type that
-    // implements `ToJson`
-    #[derive(Serialize, Deserialize)]
-    struct Data {
-        pub id: String,
-        #[serde(rename = "type")]
-        // The type name of the JSONAPI resource, equivalent to the resource name.
-        pub lower_case_type: String,
-        pub attributes: HashMap<String, String>
-    }
-
     #[test]
-    fn into_json_api_response_object() {
-        let mut attributes = HashMap::new();
-        attributes.insert("foo".to_string(), "bar".to_string());
-        let data = Data {
-            id: "foo".to_string(),
-            lower_case_type: "foos".to_string(),
-            attributes: attributes
-        };
-
-        let json = assert_response(JsonOkResponse(data).into(), Status::Ok);
-        let expected = r#"{"data":{"id":"foo","type":"foos","attributes":{"foo":"bar"}}}"#;
-        assert_json_success::<JsonApiContainer<Data>>(expected, &json);
+    fn from_query_parse_error_conversion() {
+        let error = QueryStringParseError::UnImplementedError;
+        let json = assert_response(
+            JsonErrorResponse(error, Status::BadRequest).into(),
+            Status::BadRequest
+        );
+        let expected = r#"{"errors":[{"title":"Unimplemented","status":"400","detail":"Query string parse error: Unimplemented!"}]}"#;
+        assert_json_error(expected, &json);
     }
 
     #[test]
-    fn into_json_api_response_list() {
-        let mut attributes = HashMap::new();
-        attributes.insert("foo".to_string(), "bar".to_string());
-        let data = Data {
-            id: "foo".to_string(),
-            lower_case_type: "foos".to_string(),
-            attributes: attributes
+    fn from_body_parser_error_conversion() {
+        let serde_fail = serde_json::from_str::<JsonApiErrorArray>("");
+        let error = BodyError {
+            detail: "test".to_string(),
+            cause: BodyErrorCause::JsonError(serde_fail.expect_err("unexpected ok!"))
         };
-
-        let json = assert_response(JsonOkResponse(vec![data]).into(), Status::Ok);
-        let expected = r#"{"data":[{"id":"foo","type":"foos","attributes":{"foo":"bar"}}]}"#;
-        assert_json_success::<JsonApiContainer<Vec<Data>>>(expected, &json);
+        let json = assert_response(
+            JsonErrorResponse(error, Status::BadRequest).into(),
+            Status::BadRequest
+        );
+        let expected = r#"{"errors":[{"title":"test","status":"400","detail":"test"}]}"#;
+        assert_json_error(expected, &json);
     }
 
     #[test]
-    fn from_query_parse_error_conversion() {
-        let error = QueryStringParseError::UnImplementedError;
+    fn from_query_parse_error_conversion_with_parameter() {
+        let error = QueryStringParseError::InvalidPageValue("page[size]".to_string());
         let json = assert_response(
             JsonErrorResponse(error, Status::BadRequest).into(),
             Status::BadRequest
         );
-        let expected = r#"{"errors":[{"title":"Unimplemented","status":"400","detail":"Query string parse error: Unimplemented!"}]}"#;
+        let expected = concat!(
+            r#"{"errors":[{"title":"page[size]","status":"400","#,
+            r#""detail":"Query string parse error: Invalid value for page[size]","#,
+            r#""source":{"parameter":"page[size]"}}]}"#
+        );
         assert_json_error(expected, &json);
     }
 
     #[test]
-    fn from_body_parser_error_conversion() {
-        let serde_fail = serde_json::from_str::<JsonApiErrorArray>("");
+    fn from_body_parser_data_error_conversion() {
+        let serde_fail = serde_json::from_str::<JsonApiErrorArray>("{}");
         let error = BodyError {
             detail: "test".to_string(),
             cause: BodyErrorCause::JsonError(serde_fail.expect_err("unexpected ok!"))
@@ -159,7 +245,7 @@ mod tests {
             JsonErrorResponse(error, Status::BadRequest).into(),
             Status::BadRequest
         );
-        let expected = r#"{"errors":[{"title":"test","status":"400","detail":"test"}]}"#;
+        let expected = r#"{"errors":[{"title":"test","status":"400","detail":"test","source":{"pointer":"/data"}}]}"#;
         assert_json_error(expected, &json);
     }
 
@@ -192,9 +278,4 @@ mod tests {
         serde_json::from_str::<JsonApiErrorArray>(result).expect("Cannot deserialize json!");
 
     }
-
-    fn assert_json_success<T: DeserializeOwned>(expected: &str, result: &str) {
-        assert_eq!(expected, result);
-        serde_json::from_str::<T>(result).expect("Cannot deserialize json!");
-    }
 }
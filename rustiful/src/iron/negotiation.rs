@@ -0,0 +1,69 @@
+extern crate iron;
+
+use self::iron::AfterMiddleware;
+use self::iron::BeforeMiddleware;
+use self::iron::prelude::*;
+use self::iron::typemap::Key;
+use super::JsonErrorResponse;
+use super::status::Status;
+use dispatch::ContentNegotiation;
+use dispatch::JsonApiMediaType;
+use dispatch::JsonApiRequest;
+use errors::RequestError;
+
+/// `ContentNegotiation` stores the `JsonApiMediaType` of a request in its extensions, so a handler
+/// can look up the extensions that apply with `req.extensions.get::<JsonApiMediaType>()`.
+impl Key for JsonApiMediaType {
+    type Value = JsonApiMediaType;
+}
+
+/// Negotiates the content of each request, see `ContentNegotiation::negotiate`. This is linked
+/// into the `Chain` built by `JsonApiRouterBuilder::build`.
+impl BeforeMiddleware for ContentNegotiation {
+    fn before(&self, req: &mut Request) -> IronResult<()> {
+        let mut request = JsonApiRequest::new(req.method.to_string(), "");
+
+        for header in req.headers.iter() {
+            request = request.with_header(header.name(), header.value_string());
+        }
+
+        match self.negotiate(&request) {
+            Ok(applied) => {
+                req.extensions.insert::<JsonApiMediaType>(applied);
+                Ok(())
+            }
+            Err((e, status)) => Err(json_api_error(e, Status::from_u16(status)))
+        }
+    }
+}
+
+/// Sends the `JsonApiMediaType` of the request in the `Content-Type` of the response.
+impl AfterMiddleware for ContentNegotiation {
+    fn after(&self, req: &mut Request, mut res: Response) -> IronResult<Response> {
+        let is_json_api = res.headers
+            .get_raw("Content-Type")
+            .and_then(|lines| lines.first())
+            .map_or(false, |line| line.as_slice() == b"application/vnd.api+json");
+
+        if let Some(applied) = req.extensions.get::<JsonApiMediaType>() {
+            if is_json_api && !applied.is_empty() {
+                let content_type = applied.to_header_value().into_bytes();
+                res.headers.set_raw("Content-Type", vec![content_type]);
+            }
+        }
+
+        Ok(res)
+    }
+}
+
+fn json_api_error(error: RequestError, status: Status) -> IronError {
+    let response: IronResult<Response> = JsonErrorResponse(error, status).into();
+
+    match response {
+        Ok(response) => IronError {
+            error: Box::new(error),
+            response: response
+        },
+        Err(e) => e
+    }
+}
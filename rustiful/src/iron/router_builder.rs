@@ -3,18 +3,28 @@ extern crate iron;
 extern crate persistent;
 extern crate router;
 
+use self::iron::Handler;
+use self::iron::method::Method;
 use self::iron::prelude::*;
 use self::persistent::Read;
 use self::router::Router;
+use super::BaseUrl;
+use super::allowed_methods::AllowedMethods;
 use super::from_request::FromRequest;
-use super::handlers::*;
-use super::status::*;
+use super::respond;
+use dispatch::*;
 use errors::QueryStringParseError;
+use operations::ATOMIC_EXTENSION;
+use operations::JsonApiTransaction;
+use params::JsonApiFieldSetField;
 use params::SortOrder;
-use service::Handler;
+use relationship::JsonApiRelationshipField;
+use resource::JsonApiResource;
+use service::JsonGetRelated;
 use std::error::Error;
 use std::str::FromStr;
 use std::convert::TryFrom;
+use to_json::ToJson;
 
 /// Constructs a builder for configuring routes for resources implementing any of the `JsonGet`,
 /// `JsonPost`, `JsonIndex`, `JsonPatch` or `JsonDelete` traits.
@@ -87,14 +97,16 @@ use std::convert::TryFrom;
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// impl JsonIndex for MyResource {
 /// #    type Context = MyCtx;
 ///     type Error = MyError;
 /// #
-/// #     fn find_all(params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+/// #     fn find_all(params: &Self::Params,
 /// #                 ctx: Self::Context)
-/// #            -> Result<Vec<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-/// #          Ok(vec![MyResource::default().into_json(params)])
+/// #            -> Result<rustiful::JsonApiPage<Self>, (Self::Error, Self::Status)> {
+/// #          Ok(vec![MyResource::default().into_json(params)].into())
 /// #      }
 /// }
 /// #
@@ -159,14 +171,16 @@ use std::convert::TryFrom;
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # impl JsonIndex for MyResource {
 /// #    type Context = MyCtx;
 /// #    type Error = MyError;
 /// #
-/// #     fn find_all(params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+/// #     fn find_all(params: &Self::Params,
 /// #                 ctx: Self::Context)
-/// #            -> Result<Vec<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-/// #          Ok(vec![MyResource::default().into_json(params)])
+/// #            -> Result<rustiful::JsonApiPage<Self>, (Self::Error, Self::Status)> {
+/// #          Ok(vec![MyResource::default().into_json(params)].into())
 /// #      }
 /// # }
 /// #
@@ -220,7 +234,10 @@ use std::convert::TryFrom;
 #[allow(missing_debug_implementations)] // The underlying Router doesn't implement Debug...
 pub struct JsonApiRouterBuilder {
     router: Router,
-    max_body_length: usize
+    allowed_methods: AllowedMethods,
+    max_body_length: usize,
+    base_url: Option<String>,
+    negotiation: ContentNegotiation
 }
 
 /// This `Default` implementation sets up an Iron `Router` and sets the default bodyparser size to
@@ -252,11 +269,15 @@ impl JsonApiRouterBuilder {
     pub fn new(router: Router, max_body_length: usize) -> Self {
         JsonApiRouterBuilder {
             router: router,
-            max_body_length: max_body_length
+            allowed_methods: AllowedMethods::default(),
+            max_body_length: max_body_length,
+            base_url: None,
+            negotiation: ContentNegotiation::new()
         }
     }
 
     /// Sets the max body length for any incoming JSON document. This is specified in bytes.
+    /// Requests with a larger body get a `413 Payload Too Large` response.
     ///
     /// # Example
     ///
@@ -274,6 +295,66 @@ impl JsonApiRouterBuilder {
         self.max_body_length = max_body_length;
     }
 
+    /// Sets the base URL of the `self` links in any responses, such as `https://example.com/api`.
+    /// A resource with the id `1` then has the link `https://example.com/api/my-resources/1`.
+    ///
+    /// If no base URL is set, the scheme, host and port of each request are used instead. Set
+    /// this when the server is behind a proxy, or when the router is mounted under a prefix.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rustiful;
+    /// # use rustiful::iron::JsonApiRouterBuilder;
+    /// #
+    /// # fn main() {
+    ///       let mut builder = JsonApiRouterBuilder::default();
+    ///       builder.set_base_url("https://example.com/api");
+    /// # }
+    /// ```
+    pub fn set_base_url<S: Into<String>>(&mut self, base_url: S) {
+        self.base_url = Some(base_url.into());
+    }
+
+    /// Declares support for the JSONAPI extension with the URI `uri`. Requests may then use this
+    /// extension in the `ext` media type parameter of their `Content-Type` and `Accept` headers,
+    /// and it is echoed in the `Content-Type` of the response. Requests with any other extension
+    /// are rejected. See `ContentNegotiation`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rustiful;
+    /// # use rustiful::iron::JsonApiRouterBuilder;
+    /// #
+    /// # fn main() {
+    ///       let mut builder = JsonApiRouterBuilder::default();
+    ///       builder.add_extension("https://jsonapi.org/ext/atomic");
+    /// # }
+    /// ```
+    pub fn add_extension<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_extension(uri);
+    }
+
+    /// Declares support for the JSONAPI profile with the URI `uri`. If a request asks for this
+    /// profile in the `profile` media type parameter, then it is echoed in the `Content-Type` of
+    /// the response. Unsupported profiles are ignored. See `ContentNegotiation`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # extern crate rustiful;
+    /// # use rustiful::iron::JsonApiRouterBuilder;
+    /// #
+    /// # fn main() {
+    ///       let mut builder = JsonApiRouterBuilder::default();
+    ///       builder.add_profile("https://jsonapi.org/profiles/ethanresnick/cursor-pagination");
+    /// # }
+    /// ```
+    pub fn add_profile<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_profile(uri);
+    }
+
     /// Setup a route for a struct that implements `JsonIndex` and `JsonApiResource`
     ///
     /// # Example
@@ -334,14 +415,16 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// impl JsonIndex for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
-    /// #     fn find_all(params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #     fn find_all(params: &Self::Params,
     /// #                 ctx: Self::Context)
-    /// #            -> Result<Vec<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-    /// #          Ok(vec![MyResource::default().into_json(params)])
+    /// #            -> Result<rustiful::JsonApiPage<Self>, (Self::Error, Self::Status)> {
+    /// #          Ok(vec![MyResource::default().into_json(params)].into())
     /// #      }
     /// }
     /// #
@@ -406,14 +489,16 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # impl JsonIndex for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
-    /// #     fn find_all(params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #     fn find_all(params: &Self::Params,
     /// #                 ctx: Self::Context)
-    /// #            -> Result<Vec<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-    /// #          Ok(vec![MyResource::default().into_json(params)])
+    /// #            -> Result<rustiful::JsonApiPage<Self>, (Self::Error, Self::Status)> {
+    /// #          Ok(vec![MyResource::default().into_json(params)].into())
     /// #      }
     /// # }
     /// #
@@ -426,15 +511,19 @@ impl JsonApiRouterBuilder {
     /// This resource will then have the route `GET /my-resources`.
     pub fn jsonapi_index<'a, T>(&mut self)
     where
-        T: Handler<Status = Status>,
-        T: IndexHandler,
+        T: IndexDispatcher,
         T::Context: FromRequest,
         T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
-        T::FilterField: for<'b> TryFrom<(&'b str, Vec<&'b str>), Error = QueryStringParseError>
+        T::FieldSetField: JsonApiFieldSetField
     {
-        self.router.get(
+        self.route(
+            Method::Get,
             format!("/{}", T::RESOURCE_NAME),
-            move |r: &mut Request| T::respond(r),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
             format!("index_{}", T::RESOURCE_NAME)
         );
     }
@@ -499,15 +588,18 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// impl JsonGet for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #     fn find(id: Self::JsonApiIdType,
-    /// #             params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #             params: &Self::Params,
     /// #             ctx: Self::Context)
-    /// #            -> Result<Option<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-    /// #          Ok(Some(MyResource::default().into_json(params)))
+    /// #            -> Result<Option<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>>,
+    /// #                      (Self::Error, Self::Status)> {
+    /// #          Ok(Some(MyResource::default().into_json(params).into()))
     /// #      }
     /// }
     /// #
@@ -572,15 +664,18 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # impl JsonGet for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #     fn find(id: Self::JsonApiIdType,
-    /// #             params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #             params: &Self::Params,
     /// #             ctx: Self::Context)
-    /// #            -> Result<Option<rustiful::JsonApiData<Self>>, (Self::Error, Status)> {
-    /// #          Ok(Some(MyResource::default().into_json(params)))
+    /// #            -> Result<Option<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>>,
+    /// #                      (Self::Error, Self::Status)> {
+    /// #          Ok(Some(MyResource::default().into_json(params).into()))
     /// #      }
     /// # }
     /// #
@@ -593,16 +688,20 @@ impl JsonApiRouterBuilder {
     /// This resource will then have the route `GET /my-resources/{id}`.
     pub fn jsonapi_get<T>(&mut self)
     where
-        T: Handler<Status = Status>,
-        T: GetHandler,
+        T: GetDispatcher,
         T::Context: FromRequest,
         T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
-        T::FilterField: for<'b> TryFrom<(&'b str, Vec<&'b str>), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
         <T::JsonApiIdType as FromStr>::Err: Error
     {
-        self.router.get(
+        self.route(
+            Method::Get,
             format!("/{}/:id", T::RESOURCE_NAME),
-            move |r: &mut Request| T::respond(r),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
             format!("get_{}", T::RESOURCE_NAME)
         );
     }
@@ -666,11 +765,14 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// impl JsonDelete for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
-    /// #    fn delete(id: Self::JsonApiIdType, ctx: Self::Context) -> Result<(), (Self::Error, Status)> {
+    /// #    fn delete(id: Self::JsonApiIdType, ctx: Self::Context)
+    /// #           -> Result<(), (Self::Error, Self::Status)> {
     /// #         Ok(())
     /// #    }
     /// }
@@ -735,11 +837,14 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # impl JsonDelete for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
-    /// #    fn delete(id: Self::JsonApiIdType, ctx: Self::Context) -> Result<(), (Self::Error, Status)> {
+    /// #    fn delete(id: Self::JsonApiIdType, ctx: Self::Context)
+    /// #           -> Result<(), (Self::Error, Self::Status)> {
     /// #         Ok(())
     /// #    }
     /// # }
@@ -753,14 +858,18 @@ impl JsonApiRouterBuilder {
     /// This resource will then have the route `DELETE /my-resources/{id}`.
     pub fn jsonapi_delete<T>(&mut self)
     where
-        T: Handler<Status = Status>,
-        T: DeleteHandler,
+        T: DeleteDispatcher,
         T::Context: FromRequest,
         <T::JsonApiIdType as FromStr>::Err: Error
     {
-        self.router.delete(
+        self.route(
+            Method::Delete,
             format!("/{}/:id", T::RESOURCE_NAME),
-            move |r: &mut Request| T::respond(r),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
             format!("delete_{}", T::RESOURCE_NAME)
         );
     }
@@ -825,15 +934,18 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// impl JsonPost for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #    fn create(json: rustiful::JsonApiData<Self>,
-    /// #         params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #         params: &Self::Params,
     /// #          ctx: Self::Context)
-    /// #          -> Result<rustiful::JsonApiData<Self>, (Self::Error, Status)> {
-    /// #         Ok(MyResource::default().into_json(params))
+    /// #          -> Result<Option<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>>,
+    /// #                    (Self::Error, Self::Status)> {
+    /// #         Ok(Some(MyResource::default().into_json(params).into()))
     /// #    }
     /// }
     /// #
@@ -897,21 +1009,24 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # impl JsonPost for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #    fn create(json: rustiful::JsonApiData<Self>,
-    /// #         params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #         params: &Self::Params,
     /// #          ctx: Self::Context)
-    /// #          -> Result<rustiful::JsonApiData<Self>, (Self::Error, Status)> {
+    /// #          -> Result<Option<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>>,
+    /// #                    (Self::Error, Self::Status)> {
     /// #         let resource = MyResource {
     /// #             id: "some_id".to_string(),
     /// #             foo: true,
     /// #             bar: "abc".to_string()
     /// #         };
     /// #
-    /// #         Ok(resource.into_json(params))
+    /// #         Ok(Some(resource.into_json(params).into()))
     /// #    }
     /// # }
     /// #
@@ -924,16 +1039,20 @@ impl JsonApiRouterBuilder {
     /// This resource will then have the route `POST /my-resources`.
     pub fn jsonapi_post<T>(&mut self)
     where
-        T: Handler<Status = Status>,
         T: 'static,
-        T: PostHandler,
+        T: PostDispatcher,
         T::Context: FromRequest,
         T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
-        T::FilterField: for<'b> TryFrom<(&'b str, Vec<&'b str>), Error = QueryStringParseError>
+        T::FieldSetField: JsonApiFieldSetField
     {
-        self.router.post(
+        self.route(
+            Method::Post,
             format!("/{}", T::RESOURCE_NAME),
-            move |r: &mut Request| T::respond(r),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
             format!("create_{}", T::RESOURCE_NAME)
         );
     }
@@ -997,22 +1116,25 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// impl JsonPatch for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #    fn update(id: Self::JsonApiIdType,
     /// #              json: rustiful::JsonApiData<Self>,
-    /// #              params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #              params: &Self::Params,
     /// #              ctx: Self::Context)
-    /// #              -> Result<rustiful::JsonApiData<Self>, (Self::Error, Status)> {
+    /// #              -> Result<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>,
+    /// #                        (Self::Error, Self::Status)> {
     /// #         let resource = MyResource {
     /// #             id: "some_id".to_string(),
     /// #             foo: true,
     /// #             bar: "abc".to_string()
     /// #         };
     /// #
-    /// #         Ok(resource.into_json(params))
+    /// #         Ok(resource.into_json(params).into())
     /// #    }
     /// }
     /// #
@@ -1076,22 +1198,25 @@ impl JsonApiRouterBuilder {
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # impl JsonPatch for MyResource {
     /// #    type Context = MyCtx;
     /// #    type Error = MyError;
     /// #
     /// #    fn update(id: Self::JsonApiIdType,
     /// #              json: rustiful::JsonApiData<Self>,
-    /// #              params: &rustiful::JsonApiParams<Self::FilterField, Self::SortField>,
+    /// #              params: &Self::Params,
     /// #              ctx: Self::Context)
-    /// #              -> Result<rustiful::JsonApiData<Self>, (Self::Error, Status)> {
+    /// #              -> Result<rustiful::JsonApiContainer<rustiful::JsonApiData<Self>>,
+    /// #                        (Self::Error, Self::Status)> {
     /// #         let resource = MyResource {
     /// #             id: "some_id".to_string(),
     /// #             foo: true,
     /// #             bar: "abc".to_string()
     /// #         };
     /// #
-    /// #         Ok(resource.into_json(params))
+    /// #         Ok(resource.into_json(params).into())
     /// #    }
     /// # }
     /// #
@@ -1104,25 +1229,191 @@ impl JsonApiRouterBuilder {
     /// This resource will then have the route `PATCH /my-resources/{id}`.
     pub fn jsonapi_patch<T>(&mut self)
     where
-        T: Handler<Status = Status>,
         T: 'static,
-        T: PatchHandler,
+        T: PatchDispatcher,
         T::Context: FromRequest,
         T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
-        T::FilterField: for<'b> TryFrom<(&'b str, Vec<&'b str>), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
         <T::JsonApiIdType as FromStr>::Err: Error
     {
-        self.router.patch(
+        self.route(
+            Method::Patch,
             format!("/{}/:id", T::RESOURCE_NAME),
-            move |r: &mut Request| T::respond(r),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
             format!("update_{}", T::RESOURCE_NAME)
         );
     }
 
+    /// Setup a route for a struct that implements `JsonGetRelationship` and `JsonApiResource`.
+    ///
+    /// The resource will then have the route `GET /my-resources/{id}/relationships/{relationship}`,
+    /// for each relationship in the generated `relationship` enum of the resource.
+    pub fn jsonapi_get_relationship<T>(&mut self)
+    where
+        T: GetRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(
+            Method::Get,
+            format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
+            format!("get_relationship_{}", T::RESOURCE_NAME)
+        );
+    }
+
+    /// Setup a route for a struct that implements `JsonPatchRelationship` and `JsonApiResource`.
+    ///
+    /// The resource will then have the route
+    /// `PATCH /my-resources/{id}/relationships/{relationship}`, for each relationship in the
+    /// generated `relationship` enum of the resource.
+    pub fn jsonapi_patch_relationship<T>(&mut self)
+    where
+        T: PatchRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(
+            Method::Patch,
+            format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
+            format!("update_relationship_{}", T::RESOURCE_NAME)
+        );
+    }
+
+    /// Setup a route for a struct that implements `JsonPostRelationship` and `JsonApiResource`.
+    ///
+    /// The resource will then have the route
+    /// `POST /my-resources/{id}/relationships/{relationship}`, for each to-many relationship in the
+    /// generated `relationship` enum of the resource.
+    pub fn jsonapi_post_relationship<T>(&mut self)
+    where
+        T: PostRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(
+            Method::Post,
+            format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
+            format!("add_relationship_{}", T::RESOURCE_NAME)
+        );
+    }
+
+    /// Setup a route for a struct that implements `JsonDeleteRelationship` and `JsonApiResource`.
+    ///
+    /// The resource will then have the route
+    /// `DELETE /my-resources/{id}/relationships/{relationship}`, for each to-many relationship in
+    /// the generated `relationship` enum of the resource.
+    pub fn jsonapi_delete_relationship<T>(&mut self)
+    where
+        T: DeleteRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(
+            Method::Delete,
+            format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME),
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+                })
+            },
+            format!("remove_relationship_{}", T::RESOURCE_NAME)
+        );
+    }
+
+    /// Setup routes for a struct that implements `JsonGetRelated<R>` and `JsonApiResource`.
+    ///
+    /// The resource will then have the route `GET /my-resources/{id}/{relationship}`, for each
+    /// relationship in the generated `relationship` enum of the resource that refers to `R`. Call
+    /// this once for each type of related resource.
+    pub fn jsonapi_get_related<T, R>(&mut self)
+    where
+        T: 'static,
+        T: GetRelatedDispatcher<R>,
+        T::RelationshipField: Copy + Send + Sync + 'static,
+        <T as JsonGetRelated<R>>::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error,
+        R: JsonApiResource + ToJson + 'static
+    {
+        let relationships = T::RelationshipField::all()
+            .into_iter()
+            .filter(|r| r.type_name() == R::RESOURCE_NAME);
+
+        for relationship in relationships {
+            self.route(
+                Method::Get,
+                format!("/{}/:id/{}", T::RESOURCE_NAME, relationship.name()),
+                move |r: &mut Request| {
+                    respond(r, |req, r| {
+                        let context = || {
+                            <<T as JsonGetRelated<R>>::Context as FromRequest>::from_request(r)
+                        };
+                        <T as GetRelatedDispatcher<R>>::dispatch(req, relationship, context)
+                    })
+                },
+                format!("get_related_{}_{}", T::RESOURCE_NAME, relationship.name())
+            );
+        }
+    }
+
+    /// Setup the route `POST /operations` for the Atomic Operations extension, which applies a
+    /// list of `add`, `update` and `remove` operations on any of the resource types registered in
+    /// `operations`. This also declares support for the extension, see `add_extension`.
+    ///
+    /// All operations of a request run inside `JsonApiTransaction::transaction` of the context,
+    /// so that either all of them are applied or none of them are.
+    pub fn jsonapi_operations<C>(&mut self, operations: OperationsHandler<C>)
+    where
+        C: FromRequest + JsonApiTransaction + Clone + 'static
+    {
+        self.add_extension(ATOMIC_EXTENSION);
+        self.route(
+            Method::Post,
+            "/operations",
+            move |r: &mut Request| {
+                respond(r, |req, r| {
+                    operations.dispatch(req, || <C as FromRequest>::from_request(r))
+                })
+            },
+            "operations"
+        );
+    }
+
+    /// Adds a route to the router, which is also checked for the `Allow` header when a request
+    /// with another method matches its path.
+    fn route<H, S, I>(&mut self, method: Method, path: S, handler: H, route_id: I)
+    where
+        H: Handler,
+        S: AsRef<str>,
+        I: AsRef<str>
+    {
+        self.allowed_methods.add(&method.to_string(), path.as_ref());
+        self.router.route(method, path, handler, route_id);
+    }
+
     /// Constructs an iron `Chain` with the routes that were previously specified in `jsonapi_get`,
-    /// `jsonapi_post` et cetera. This also sets up the body parser, which is a prerequisite for
-    /// being able to parse JSON documents when doing a `POST` or `PATCH`. The result of this method
-    /// can then be used in the Iron server constructor.
+    /// `jsonapi_post` et cetera. This also sets up the max body length, which limits the JSON
+    /// documents of a `POST` or `PATCH`, the `ContentNegotiation` middleware, and the
+    /// `405 Method Not Allowed` responses to requests with a method that no route of their path
+    /// has. The result of this method can then be used in the Iron server constructor.
     ///
     /// ```rust,no_run
     /// extern crate iron;
@@ -1140,6 +1431,14 @@ impl JsonApiRouterBuilder {
     pub fn build(self) -> Chain {
         let mut chain = iron::Chain::new(self.router);
         chain.link_before(Read::<bodyparser::MaxBodyLength>::one(self.max_body_length));
+        chain.link_before(self.negotiation.clone());
+        chain.link_after(self.allowed_methods);
+        chain.link_after(self.negotiation);
+
+        if let Some(base_url) = self.base_url {
+            chain.link_before(Read::<BaseUrl>::one(base_url));
+        }
+
         chain
     }
 }
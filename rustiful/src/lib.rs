@@ -14,6 +14,9 @@ pub use to_json::*;
 mod service;
 pub use service::*;
 
+mod async_service;
+pub use async_service::*;
+
 mod data;
 pub use data::*;
 
@@ -22,6 +25,7 @@ pub use params::*;
 
 mod errors;
 pub use errors::query_string_parse_error::QueryStringParseError;
+pub use errors::conversion_error::ConversionError;
 
 mod container;
 pub use container::*;
@@ -35,14 +39,59 @@ pub use builder::*;
 mod resource;
 pub use resource::*;
 
+mod relationship;
+pub use relationship::*;
+
+mod filter;
+pub use filter::*;
+
+mod links;
+pub use links::*;
+
+mod meta;
+pub use meta::*;
+
+mod page;
+pub use page::*;
+
+mod cursor;
+pub use cursor::*;
+
+mod related;
+pub use related::*;
+
+mod operations;
+pub use operations::*;
+
+mod dispatch;
+pub use dispatch::*;
+
+#[cfg(any(feature = "iron", feature = "hyper", feature = "tower"))]
+mod route_path;
+
+#[cfg(any(feature = "hyper", feature = "tower"))]
+mod routes;
+
 #[cfg(feature = "iron")]
 pub mod iron;
 
+#[cfg(feature = "hyper")]
+pub mod hyper;
+
+#[cfg(feature = "tower")]
+pub mod tower;
+
+#[cfg(feature = "rocket")]
+pub mod rocket;
+
 #[macro_use]
 extern crate serde_derive;
 
 pub mod json_option;
 
+pub mod validate;
+pub use validate::ValidationError;
+
 #[cfg(feature = "rustiful-derive")]
 #[allow(unused_imports)]
 #[macro_use]
@@ -0,0 +1,67 @@
+extern crate url;
+
+use self::url::percent_encoding::PATH_SEGMENT_ENCODE_SET;
+use self::url::percent_encoding::utf8_percent_encode;
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A JSONAPI links object. Only the links that are present are serialized.
+pub struct JsonApiLinks {
+    #[serde(rename = "self", default, skip_serializing_if = "Option::is_none")]
+    /// The URL of the document or the resource object that contains this links object.
+    pub self_link: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The first page of a paginated collection.
+    pub first: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The previous page of a paginated collection.
+    pub prev: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The next page of a paginated collection.
+    pub next: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The last page of a paginated collection.
+    pub last: Option<String>
+}
+
+impl JsonApiLinks {
+    /// Creates a links object that only contains a `self` link.
+    pub fn with_self(url: String) -> Self {
+        JsonApiLinks {
+            self_link: Some(url),
+            ..Default::default()
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.self_link.is_none() && self.first.is_none() && self.prev.is_none() &&
+            self.next.is_none() && self.last.is_none()
+    }
+}
+
+/// The URL of the resource with the type `type_name` and the id `id`, relative to `base_url`, i.e
+/// `{base_url}/{type_name}/{id}`. The type name and the id are percent-encoded as path segments.
+pub fn resource_url(base_url: &str, type_name: &str, id: &str) -> String {
+    format!(
+        "{}/{}/{}",
+        base_url.trim_right_matches('/'),
+        utf8_percent_encode(type_name, PATH_SEGMENT_ENCODE_SET),
+        utf8_percent_encode(id, PATH_SEGMENT_ENCODE_SET)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resource_url_with_base_url() {
+        let url = resource_url("http://localhost:3000/", "foos", "1");
+        assert_eq!("http://localhost:3000/foos/1", url);
+    }
+
+    #[test]
+    fn resource_url_encodes_reserved_characters_in_id() {
+        let url = resource_url("http://localhost:3000", "foos", "a b/c?d%e");
+        assert_eq!("http://localhost:3000/foos/a%20b%2Fc%3Fd%25e", url);
+    }
+}
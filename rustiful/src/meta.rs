@@ -0,0 +1,43 @@
+extern crate serde_json;
+
+use self::serde_json::Map;
+use self::serde_json::Value;
+use serde::ser::Error;
+use serde::ser::Serialize;
+
+/// A JSONAPI meta object, i.e non-standard meta information such as totals, timestamps or
+/// deprecation notices.
+pub type JsonApiMeta = Map<String, Value>;
+
+/// Converts `meta` to a `JsonApiMeta`, e.g to pass a struct to one of the `with_meta` methods.
+///
+/// Returns an error if `meta` cannot be serialized to JSON, or if it isn't serialized to a JSON
+/// object.
+pub fn to_meta<M: Serialize>(meta: &M) -> Result<JsonApiMeta, serde_json::Error> {
+    match serde_json::to_value(meta)? {
+        Value::Object(map) => Ok(map),
+        other => Err(Error::custom(format!("meta must be a JSON object, got {}", other)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct Deprecation {
+        deprecated: bool
+    }
+
+    #[test]
+    fn struct_to_meta() {
+        let meta = to_meta(&Deprecation { deprecated: true }).unwrap();
+        assert_eq!(Some(&Value::Bool(true)), meta.get("deprecated"));
+    }
+
+    #[test]
+    fn non_object_to_meta() {
+        let error = to_meta(&1).unwrap_err();
+        assert_eq!("meta must be a JSON object, got 1", error.to_string());
+    }
+}
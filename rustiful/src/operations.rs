@@ -0,0 +1,248 @@
+extern crate serde_json;
+
+use self::serde_json::Map;
+use self::serde_json::Value;
+use meta::JsonApiMeta;
+use std;
+use std::collections::HashMap;
+
+/// The URI of the JSONAPI Atomic Operations extension.
+pub static ATOMIC_EXTENSION: &'static str = "https://jsonapi.org/ext/atomic";
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// The `op` member of an operation.
+pub enum JsonApiOperationCode {
+    /// Creates a resource, see `JsonPost`.
+    Add,
+    /// Updates a resource, see `JsonPatch`.
+    Update,
+    /// Deletes a resource, see `JsonDelete`.
+    Remove
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// The `ref` member of an operation, i.e the resource that the operation targets. A resource
+/// that was added earlier in the same request is referenced with its local id, `lid`.
+pub struct JsonApiOperationRef {
+    #[serde(rename = "type")]
+    /// The type name of the targeted resource.
+    pub type_name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The id of the targeted resource.
+    pub id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The local id of the targeted resource.
+    pub lid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The targeted relationship. Operations on relationships are currently not supported.
+    pub relationship: Option<String>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// A single operation of an Atomic Operations request.
+pub struct JsonApiOperation {
+    /// The kind of operation.
+    pub op: JsonApiOperationCode,
+    #[serde(rename = "ref", default, skip_serializing_if = "Option::is_none")]
+    /// The resource that the operation targets. This can be left out if `data` identifies the
+    /// resource.
+    pub reference: Option<JsonApiOperationRef>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// The resource object of an `add` or an `update` operation. This is converted to a
+    /// `JsonApiData` of the targeted resource type once any local ids have been resolved.
+    pub data: Option<Value>
+}
+
+impl JsonApiOperation {
+    /// The type name of the targeted resource, from either `ref` or `data`.
+    pub fn type_name(&self) -> Option<&str> {
+        match self.reference {
+            Some(ref reference) => Some(&reference.type_name),
+            None => self.data
+                .as_ref()
+                .and_then(|data| data.get("type"))
+                .and_then(Value::as_str)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The document of an Atomic Operations request.
+pub struct JsonApiOperations {
+    #[serde(rename = "atomic:operations")]
+    pub operations: Vec<JsonApiOperation>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+/// The result of a single operation. `data` is the added or updated resource, and is empty for
+/// a `remove` operation.
+pub struct JsonApiOperationResult {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "JsonApiMeta::is_empty")]
+    pub meta: JsonApiMeta
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+/// The document of a response to an Atomic Operations request, with one result per operation.
+pub struct JsonApiOperationResults {
+    #[serde(rename = "atomic:results")]
+    pub results: Vec<JsonApiOperationResult>
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+/// The ids of the resources that were added with a local id, by type name and local id.
+pub struct JsonApiLocalIds(HashMap<(String, String), String>);
+
+impl JsonApiLocalIds {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records that the resource of type `type_name` with the local id `lid` has the id `id`.
+    pub fn insert<T: Into<String>, L: Into<String>, I: Into<String>>(
+        &mut self,
+        type_name: T,
+        lid: L,
+        id: I
+    ) {
+        self.0.insert((type_name.into(), lid.into()), id.into());
+    }
+
+    /// The id of the resource of type `type_name` with the local id `lid`.
+    pub fn get(&self, type_name: &str, lid: &str) -> Option<&str> {
+        self.0
+            .get(&(type_name.to_string(), lid.to_string()))
+            .map(String::as_str)
+    }
+
+    /// Replaces the `lid` of a resource identifier object with the `id` it refers to.
+    ///
+    /// # Errors
+    ///
+    /// The unknown local id, if there is no resource with that local id.
+    pub fn resolve_identifier(&self, identifier: &mut Map<String, Value>) -> Result<(), String> {
+        let lid = match identifier.get("lid").and_then(Value::as_str) {
+            Some(lid) => lid.to_string(),
+            None => return Ok(())
+        };
+
+        let id = {
+            let type_name = identifier.get("type").and_then(Value::as_str).unwrap_or("");
+            match self.get(type_name, &lid) {
+                Some(id) => id.to_string(),
+                None => return Err(lid)
+            }
+        };
+
+        identifier.remove("lid");
+        identifier.insert("id".to_string(), Value::String(id));
+        Ok(())
+    }
+
+    /// Replaces the local ids in the resource linkage of each relationship in `data` with the ids
+    /// they refer to.
+    ///
+    /// # Errors
+    ///
+    /// The first unknown local id.
+    pub fn resolve_relationships(&self, data: &mut Value) -> Result<(), String> {
+        let relationships = match data.get_mut("relationships") {
+            Some(&mut Value::Object(ref mut relationships)) => relationships,
+            _ => return Ok(())
+        };
+
+        for relationship in relationships.values_mut() {
+            match relationship.get_mut("data") {
+                Some(&mut Value::Object(ref mut identifier)) => {
+                    self.resolve_identifier(identifier)?
+                }
+                Some(&mut Value::Array(ref mut identifiers)) => {
+                    for identifier in identifiers {
+                        if let Value::Object(ref mut identifier) = *identifier {
+                            self.resolve_identifier(identifier)?;
+                        }
+                    }
+                }
+                _ => ()
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// A context that can run all operations of an Atomic Operations request in a single
+/// transaction, so that either all of them are applied or none of them are.
+///
+/// This is implemented for the `Context` type that the `JsonPost`, `JsonPatch` and `JsonDelete`
+/// impls of the resources in an operations endpoint have in common.
+pub trait JsonApiTransaction {
+    /// A user-defined error type, for errors that occur when beginning or ending a transaction.
+    type Error: std::error::Error + Send;
+
+    /// Runs `operations` in a transaction. The transaction must be committed if `operations`
+    /// returns `Ok`, and rolled back if it returns `Err`. The result of `operations` is returned
+    /// as is, unless the transaction itself fails.
+    ///
+    /// `operations` is handed the context that the operations run with, e.g a context that holds
+    /// the connection the transaction was begun on. Each operation gets a clone of it, so its
+    /// clones must take part in the same transaction.
+    fn transaction<T, E, F>(&self, operations: F) -> Result<Result<T, E>, Self::Error>
+    where
+        F: FnOnce(&Self) -> Result<T, E>;
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use super::*;
+
+    #[test]
+    fn deserialize_operations() {
+        let json = r#"{"atomic:operations": [
+            {"op": "add", "data": {"type": "foos", "lid": "a", "attributes": {}}},
+            {"op": "remove", "ref": {"type": "foos", "id": "1"}}
+        ]}"#;
+        let document: JsonApiOperations = serde_json::from_str(json).unwrap();
+
+        assert_eq!(JsonApiOperationCode::Add, document.operations[0].op);
+        assert_eq!(Some("foos"), document.operations[0].type_name());
+        assert_eq!(JsonApiOperationCode::Remove, document.operations[1].op);
+        assert_eq!(Some("1".to_string()), document.operations[1].reference.clone().unwrap().id);
+    }
+
+    #[test]
+    fn resolve_local_ids_in_relationships() {
+        let mut lids = JsonApiLocalIds::new();
+        lids.insert("people", "a", "1");
+
+        let mut data = serde_json::from_str(
+            r#"{"type": "articles", "relationships": {
+                "author": {"data": {"type": "people", "lid": "a"}},
+                "editors": {"data": [{"type": "people", "lid": "a"}, {"type": "people", "id": "2"}]}
+            }}"#
+        ).unwrap();
+        let expected: Value = serde_json::from_str(
+            r#"{"type": "articles", "relationships": {
+                "author": {"data": {"type": "people", "id": "1"}},
+                "editors": {"data": [{"type": "people", "id": "1"}, {"type": "people", "id": "2"}]}
+            }}"#
+        ).unwrap();
+
+        assert_eq!(Ok(()), lids.resolve_relationships(&mut data));
+        assert_eq!(expected, data);
+    }
+
+    #[test]
+    fn resolve_unknown_local_id() {
+        let lids = JsonApiLocalIds::new();
+        let mut data = serde_json::from_str(
+            r#"{"relationships": {"author": {"data": {"type": "people", "lid": "a"}}}}"#
+        ).unwrap();
+
+        assert_eq!(Err("a".to_string()), lids.resolve_relationships(&mut data));
+    }
+}
@@ -0,0 +1,572 @@
+extern crate serde_json;
+extern crate url;
+
+use self::serde_json::Value;
+use self::url::Url;
+use container::JsonApiContainer;
+use container::JsonApiIncluded;
+use cursor::Cursor;
+use data::JsonApiData;
+use errors::QueryStringParseError;
+use links::JsonApiLinks;
+use meta::JsonApiMeta;
+use params::JsonApiParams;
+use std::cmp;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use to_json::ToJson;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// The `page[*]` query parameters of a request.
+///
+/// The size of a page is `None` if it wasn't specified by the client and if the resource doesn't
+/// have a default page size, in which case the page is unbounded.
+pub enum Page {
+    /// `page[number]` and `page[size]`. Page numbers start at 1.
+    Number { number: u64, size: Option<u64> },
+    /// `page[offset]` and `page[limit]`. Offsets start at 0.
+    Offset { offset: u64, limit: Option<u64> },
+    /// `page[after]` or `page[before]`, and `page[size]`. The first page has neither cursor.
+    Cursor {
+        after: Option<Cursor>,
+        before: Option<Cursor>,
+        size: Option<u64>
+    }
+}
+
+impl Page {
+    /// Applies the default and maximum page size of a resource to the page requested by a client.
+    ///
+    /// If `page` is `None` the first page is used, as long as either `default_size` or `max_size`
+    /// is set. Any page size larger than `max_size` is reduced to `max_size`.
+    ///
+    /// Returns `InvalidPageValue` if the offset of the page, or the position of the next page,
+    /// doesn't fit in a `u64`.
+    pub fn resolve(
+        page: Option<Page>,
+        default_size: Option<u64>,
+        max_size: Option<u64>
+    ) -> Result<Option<Page>, QueryStringParseError> {
+        let default_size = default_size.or(max_size);
+        let resolve_size = |size: Option<u64>| match (size.or(default_size), max_size) {
+            (Some(size), Some(max)) => Some(cmp::min(size, max)),
+            (size, _) => size
+        };
+
+        let page = match page {
+            Some(Page::Number { number, size }) => Some(Page::Number {
+                number: number,
+                size: resolve_size(size)
+            }),
+            Some(Page::Offset { offset, limit }) => Some(Page::Offset {
+                offset: offset,
+                limit: resolve_size(limit)
+            }),
+            Some(Page::Cursor {
+                after,
+                before,
+                size
+            }) => Some(Page::Cursor {
+                after: after,
+                before: before,
+                size: resolve_size(size)
+            }),
+            None => resolve_size(None).map(|size| Page::Number {
+                number: 1,
+                size: Some(size)
+            })
+        };
+
+        match page {
+            Some(ref page) if !page.is_in_range() => {
+                let key = match *page {
+                    Page::Number { .. } => "page[number]",
+                    _ => "page[offset]"
+                };
+                Err(QueryStringParseError::InvalidPageValue(key.to_string()))
+            }
+            page => Ok(page)
+        }
+    }
+
+    /// Whether the offset of this page and the position of the next page fit in a `u64`.
+    fn is_in_range(&self) -> bool {
+        match *self {
+            Page::Number { number, size } => {
+                let offset = match size {
+                    Some(size) => number.saturating_sub(1).checked_mul(size),
+                    None => Some(0)
+                };
+                offset.is_some() && number.checked_add(1).is_some()
+            }
+            Page::Offset { offset, limit } => offset.checked_add(limit.unwrap_or(0)).is_some(),
+            Page::Cursor { .. } => true
+        }
+    }
+
+    /// The number of items on this page, if bounded.
+    pub fn size(&self) -> Option<u64> {
+        match *self {
+            Page::Number { size, .. } => size,
+            Page::Offset { limit, .. } => limit,
+            Page::Cursor { size, .. } => size
+        }
+    }
+
+    /// The number of items before this page, if the page is bounded. This is always `None` for
+    /// cursor-based pages. The offset saturates at `u64::MAX`, which can't happen for a page
+    /// returned by `resolve`.
+    pub fn offset(&self) -> Option<u64> {
+        match *self {
+            Page::Number { number, size } => {
+                size.map(|size| number.saturating_sub(1).saturating_mul(size))
+            }
+            Page::Offset { offset, .. } => Some(offset),
+            Page::Cursor { .. } => None
+        }
+    }
+
+    /// Creates the `first`, `prev`, `next` and `last` links relative to this page.
+    ///
+    /// * `url` - The URL of the request. Any `page[*]` query parameters are replaced, all other
+    /// query parameters are kept as is.
+    /// * `count` - The number of items on this page. If `total` is unknown, then a `next` link is
+    /// only added if this page is full.
+    /// * `total` - The total number of items in the collection. The `last` link is only added if
+    /// this is known.
+    ///
+    /// Returns `None` if the page is unbounded, if `url` isn't a valid URL or if this is a
+    /// cursor-based page (see `cursor_links`).
+    pub fn links(&self, url: &str, count: usize, total: Option<u64>) -> Option<JsonApiLinks> {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => return None
+        };
+
+        let size = match self.size() {
+            Some(size) if size > 0 => size,
+            _ => return None
+        };
+
+        let (first, prev, next, last) = match *self {
+            Page::Number { number, .. } => {
+                let last = total.map(|total| total.saturating_sub(1) / size + 1);
+                let next = number.checked_add(1).filter(|_| match last {
+                    Some(last) => number < last,
+                    None => count as u64 >= size
+                });
+
+                (
+                    self.with_position(1),
+                    if number > 1 { Some(self.with_position(number - 1)) } else { None },
+                    next.map(|next| self.with_position(next)),
+                    last.map(|last| self.with_position(last))
+                )
+            }
+            Page::Offset { offset, .. } => {
+                let last = total.map(|total| total.saturating_sub(1) / size * size);
+                let next = offset.checked_add(size).filter(|&next| match total {
+                    Some(total) => next < total,
+                    None => count as u64 >= size
+                });
+
+                (
+                    self.with_position(0),
+                    if offset > 0 {
+                        Some(self.with_position(offset.saturating_sub(size)))
+                    } else {
+                        None
+                    },
+                    next.map(|next| self.with_position(next)),
+                    last.map(|last| self.with_position(last))
+                )
+            }
+            Page::Cursor { .. } => return None
+        };
+
+        Some(JsonApiLinks {
+            self_link: None,
+            first: Some(first.to_url(&url)),
+            prev: prev.map(|p| p.to_url(&url)),
+            next: next.map(|p| p.to_url(&url)),
+            last: last.map(|p| p.to_url(&url))
+        })
+    }
+
+    /// Creates the `first`, `prev` and `next` links relative to a cursor-based page. There is no
+    /// `last` link, since the position of the last page isn't known.
+    ///
+    /// * `url` - The URL of the request. Any `page[*]` query parameters are replaced, all other
+    /// query parameters are kept as is.
+    /// * `count` - The number of items on this page.
+    /// * `first` and `last` - The cursors of the first and the last item on this page.
+    ///
+    /// Returns `None` if this isn't a bounded cursor-based page, or if `url` isn't a valid URL.
+    pub fn cursor_links(
+        &self,
+        url: &str,
+        count: usize,
+        first: Option<&Cursor>,
+        last: Option<&Cursor>
+    ) -> Option<JsonApiLinks> {
+        let url = match Url::parse(url) {
+            Ok(url) => url,
+            Err(_) => return None
+        };
+
+        let (after, before, size) = match *self {
+            Page::Cursor {
+                ref after,
+                ref before,
+                size: Some(size)
+            } => (after, before, size),
+            _ => return None
+        };
+
+        let is_full = count as u64 >= size;
+        // When paging backwards there is always a next page (the one we came from), and when
+        // paging forwards there is always a previous page.
+        let has_next = before.is_some() || is_full;
+        let has_prev = after.is_some() || (before.is_some() && is_full);
+
+        let page = |after: Option<&Cursor>, before: Option<&Cursor>| Page::Cursor {
+            after: after.cloned(),
+            before: before.cloned(),
+            size: Some(size)
+        };
+
+        let prev = if has_prev { first } else { None };
+        let next = if has_next { last } else { None };
+
+        Some(JsonApiLinks {
+            self_link: None,
+            first: Some(page(None, None).to_url(&url)),
+            prev: prev.map(|cursor| page(None, Some(cursor)).to_url(&url)),
+            next: next.map(|cursor| page(Some(cursor), None).to_url(&url)),
+            last: None
+        })
+    }
+
+    /// A page of the same size as this one, at page number or offset `position`.
+    fn with_position(&self, position: u64) -> Page {
+        match *self {
+            Page::Number { size, .. } => Page::Number {
+                number: position,
+                size: size
+            },
+            Page::Offset { limit, .. } => Page::Offset {
+                offset: position,
+                limit: limit
+            },
+            Page::Cursor { .. } => self.clone()
+        }
+    }
+
+    fn query_pairs(&self) -> Vec<(&'static str, String)> {
+        let (position, size) = match *self {
+            Page::Number { number, size } => {
+                (("page[number]", Some(number.to_string())), ("page[size]", size))
+            }
+            Page::Offset { offset, limit } => {
+                (("page[offset]", Some(offset.to_string())), ("page[limit]", limit))
+            }
+            Page::Cursor {
+                ref after,
+                ref before,
+                size
+            } => {
+                let position = match (after, before) {
+                    (&Some(ref cursor), _) => ("page[after]", Some(cursor.encode())),
+                    (_, &Some(ref cursor)) => ("page[before]", Some(cursor.encode())),
+                    _ => ("page[after]", None)
+                };
+                (position, ("page[size]", size))
+            }
+        };
+
+        let mut pairs = vec![];
+        if let Some(value) = position.1 {
+            pairs.push((position.0, value));
+        }
+        if let Some(value) = size.1 {
+            pairs.push((size.0, value.to_string()));
+        }
+
+        pairs
+    }
+
+    fn to_url(&self, url: &Url) -> String {
+        let mut url = url.clone();
+        let pairs: Vec<(String, String)> = url.query_pairs()
+            .into_owned()
+            .filter(|&(ref key, _)| !key.starts_with("page["))
+            .collect();
+
+        {
+            let mut serializer = url.query_pairs_mut();
+            serializer.clear();
+            for (key, value) in pairs {
+                serializer.append_pair(&key, &value);
+            }
+            for (key, value) in self.query_pairs() {
+                serializer.append_pair(key, &value);
+            }
+        }
+
+        url.to_string()
+    }
+}
+
+/// Gives access to the `page[*]` query parameters of a params type, so that the default and
+/// maximum page size of a resource can be applied before the params are passed on to
+/// `JsonIndex::find_all`.
+pub trait PageParams {
+    fn page(&self) -> Option<&Page>;
+
+    fn page_mut(&mut self) -> &mut Option<Page>;
+}
+
+impl<F, S, I, Fl> PageParams for JsonApiParams<F, S, I, Fl> {
+    fn page(&self) -> Option<&Page> {
+        self.page.as_ref()
+    }
+
+    fn page_mut(&mut self) -> &mut Option<Page> {
+        &mut self.page
+    }
+}
+
+/// A page of resources returned from `JsonIndex::find_all`.
+pub struct JsonApiPage<T: ToJson> {
+    pub data: Vec<JsonApiData<T>>,
+    /// The total number of resources in the collection, across all pages. If this is set, then
+    /// it is sent as `meta.total` in the response, and used to create the `last` page link.
+    pub total: Option<u64>,
+    /// The cursors of the first and the last resource on this page. These are used to create the
+    /// `prev` and `next` links of a cursor-based page.
+    pub cursors: Option<(Cursor, Cursor)>,
+    /// Top-level meta information of the response.
+    pub meta: JsonApiMeta,
+    /// Resources that are related to the resources on this page, which are sent in the top-level
+    /// `included` member of the response.
+    pub included: Vec<JsonApiIncluded>
+}
+
+impl<T: ToJson> JsonApiPage<T> {
+    pub fn new(data: Vec<JsonApiData<T>>) -> Self {
+        JsonApiPage {
+            data: data,
+            total: None,
+            cursors: None,
+            meta: JsonApiMeta::new(),
+            included: vec![]
+        }
+    }
+
+    pub fn with_total(mut self, total: u64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Sets the cursors of the first and the last resource on this page, typically created with
+    /// `Cursor::bounds`.
+    pub fn with_cursors(mut self, cursors: Option<(Cursor, Cursor)>) -> Self {
+        self.cursors = cursors;
+        self
+    }
+
+    /// Adds top-level meta information to the response. Any existing members with the same names
+    /// are replaced, except for `total` if the total has been set with `with_total`.
+    pub fn with_meta(mut self, meta: JsonApiMeta) -> Self {
+        self.meta.extend(meta);
+        self
+    }
+
+    /// Side-loads a related resource, see `JsonApiContainer::include`. Duplicates are removed
+    /// when the page is converted to a document.
+    pub fn include<U: ToJson>(mut self, resource: JsonApiData<U>) -> Self {
+        self.included.push(JsonApiIncluded::from_data(&resource));
+        self
+    }
+
+    /// Converts this page to a compound document, with pagination links relative to `url` if
+    /// `page` is set.
+    pub fn into_container(
+        self,
+        url: &str,
+        page: Option<&Page>
+    ) -> JsonApiContainer<Vec<JsonApiData<T>>> {
+        let count = self.data.len();
+        let total = self.total;
+        let cursors = self.cursors;
+        let mut container = JsonApiContainer::from(self.data)
+            .with_included(self.included)
+            .with_meta(self.meta);
+
+        container.links = page.and_then(|page| match *page {
+            Page::Cursor { .. } => {
+                let first = cursors.as_ref().map(|c| &c.0);
+                let last = cursors.as_ref().map(|c| &c.1);
+                page.cursor_links(url, count, first, last)
+            }
+            _ => page.links(url, count, total)
+        });
+
+        if let Some(total) = total {
+            container.meta.insert("total".to_string(), Value::from(total));
+        }
+
+        container
+    }
+}
+
+impl<T: ToJson> Debug for JsonApiPage<T>
+where
+    JsonApiData<T>: Debug
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        f.debug_struct("JsonApiPage")
+            .field("data", &self.data)
+            .field("total", &self.total)
+            .field("cursors", &self.cursors)
+            .field("meta", &self.meta)
+            .field("included", &self.included)
+            .finish()
+    }
+}
+
+impl<T: ToJson> From<Vec<JsonApiData<T>>> for JsonApiPage<T> {
+    fn from(data: Vec<JsonApiData<T>>) -> Self {
+        JsonApiPage::new(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &'static str = "http://localhost/foos?sort=bar";
+
+    #[test]
+    fn resolve_default_page_size() {
+        let expected = Some(Page::Number {
+            number: 1,
+            size: Some(10)
+        });
+        assert_eq!(Ok(expected), Page::resolve(None, Some(10), Some(100)));
+    }
+
+    #[test]
+    fn resolve_limits_page_size() {
+        let page = Page::Offset {
+            offset: 5,
+            limit: Some(500)
+        };
+        let expected = Some(Page::Offset {
+            offset: 5,
+            limit: Some(100)
+        });
+        assert_eq!(Ok(expected), Page::resolve(Some(page), None, Some(100)));
+    }
+
+    #[test]
+    fn resolve_without_page_sizes() {
+        assert_eq!(Ok(None), Page::resolve(None, None, None));
+    }
+
+    #[test]
+    fn resolve_rejects_page_number_out_of_range() {
+        let error = Err(QueryStringParseError::InvalidPageValue("page[number]".to_string()));
+
+        let page = Page::Number {
+            number: u64::max_value(),
+            size: None
+        };
+        assert_eq!(error, Page::resolve(Some(page), None, None));
+
+        let page = Page::Number {
+            number: u64::max_value() / 10 + 2,
+            size: Some(10)
+        };
+        assert_eq!(error, Page::resolve(Some(page), None, None));
+    }
+
+    #[test]
+    fn resolve_rejects_page_offset_out_of_range() {
+        let page = Page::Offset {
+            offset: u64::max_value(),
+            limit: None
+        };
+        let error = Err(QueryStringParseError::InvalidPageValue("page[offset]".to_string()));
+        assert_eq!(error, Page::resolve(Some(page), Some(10), None));
+    }
+
+    #[test]
+    fn resolve_accepts_last_page_in_range() {
+        let page = Page::Number {
+            number: u64::max_value() / 10 + 1,
+            size: Some(10)
+        };
+        assert_eq!(Ok(Some(page.clone())), Page::resolve(Some(page.clone()), None, None));
+        assert_eq!(Some(u64::max_value() / 10 * 10), page.offset());
+    }
+
+    #[test]
+    fn links_at_end_of_range() {
+        let page = Page::Number {
+            number: u64::max_value(),
+            size: Some(1)
+        };
+        let links = page.links(URL, 1, None).expect("expected links");
+        assert_eq!(None, links.next);
+        assert_eq!(Some(u64::max_value() - 1), page.offset());
+
+        let page = Page::Offset {
+            offset: u64::max_value() - 5,
+            limit: Some(10)
+        };
+        let links = page.links(URL, 10, Some(u64::max_value())).expect("expected links");
+        assert_eq!(None, links.next);
+        assert!(links.last.is_some());
+    }
+
+    #[test]
+    fn number_links_with_total() {
+        let page = Page::Number {
+            number: 2,
+            size: Some(10)
+        };
+        let links = page.links(URL, 10, Some(25)).expect("expected links");
+
+        let url = |n| format!("{}&page%5Bnumber%5D={}&page%5Bsize%5D=10", URL, n);
+        assert_eq!(Some(url(1)), links.first);
+        assert_eq!(Some(url(1)), links.prev);
+        assert_eq!(Some(url(3)), links.next);
+        assert_eq!(Some(url(3)), links.last);
+    }
+
+    #[test]
+    fn offset_links_without_total() {
+        let page = Page::Offset {
+            offset: 0,
+            limit: Some(10)
+        };
+        let links = page.links(URL, 4, None).expect("expected links");
+
+        let url = |n| format!("{}&page%5Boffset%5D={}&page%5Blimit%5D=10", URL, n);
+        assert_eq!(Some(url(0)), links.first);
+        assert_eq!(None, links.prev);
+        assert_eq!(None, links.next);
+        assert_eq!(None, links.last);
+    }
+
+    #[test]
+    fn no_links_for_unbounded_page() {
+        let page = Page::Number {
+            number: 1,
+            size: None
+        };
+        assert_eq!(None, page.links(URL, 4, None));
+    }
+}
@@ -2,6 +2,9 @@ extern crate url;
 
 use self::url::form_urlencoded;
 use errors::QueryStringParseError;
+use cursor::Cursor;
+use page::Page;
+use resource::JsonApiResource;
 use std::collections::HashMap;
 use std::collections::hash_map::Entry::Occupied;
 use std::collections::hash_map::Entry::Vacant;
@@ -32,17 +35,38 @@ use std::convert::TryFrom;
 ///     bar,
 /// }
 ///
+/// // This enum will typically be generated by rustiful-derive
+/// #[derive(Debug, PartialEq, Eq, Clone)]
+/// enum GeneratedIncludeParam {
+///     baz(Option<Box<GeneratedIncludeParam>>)
+/// }
+///
+/// // This enum will typically be generated by rustiful-derive
+/// #[derive(Debug, PartialEq, Eq, Clone)]
+/// enum GeneratedFilterParam {
+///     foo(rustiful::FilterValue<bool>),
+///     bar(rustiful::FilterValue<String>)
+/// }
+///
 /// let fields = vec![GeneratedFieldSetParam::foo, GeneratedFieldSetParam::bar];
 /// let sort = vec![GeneratedSortParam::foo(rustiful::SortOrder::Asc)];
+/// let include = vec![GeneratedIncludeParam::baz(None)];
+/// let filter = vec![GeneratedFilterParam::foo(rustiful::FilterValue::Eq(true))];
 /// let query_params = HashMap::new();
 ///
-/// let params = rustiful::JsonApiParams::new(fields.clone(), sort.clone(), query_params.clone());
+/// let params = rustiful::JsonApiParams::new(fields.clone(),
+///                                           sort.clone(),
+///                                           include.clone(),
+///                                           filter.clone(),
+///                                           query_params.clone());
 ///
 /// assert_eq!(fields, params.fieldset.fields);
 /// assert_eq!(sort, params.sort.fields);
+/// assert_eq!(include, params.include.fields);
+/// assert_eq!(filter, params.filter.fields);
 /// assert_eq!(query_params, params.query_params);
 /// ```
-pub struct JsonApiParams<F, S> {
+pub struct JsonApiParams<F, S, I, Fl> {
     /// A type-safe container for the "sort" query parameter in JSONAPI.
     ///
     /// The type parameter `<S>` will usually be an enum type that is generated using the `JsonApi`
@@ -53,21 +77,47 @@ pub struct JsonApiParams<F, S> {
     /// The type parameter `<F>` will usually be an enum type that is generated using the `JsonApi`
     /// attribute in rustiful-derive.
     pub fieldset: FieldSet<F>,
-    /// A hashmap representing all other query parameters that are not `sort` or `fields[*]`.
+    /// A type-safe container for the "include" query parameter in JSONAPI.
+    ///
+    /// The type parameter `<I>` will usually be an enum type that is generated using the `JsonApi`
+    /// attribute in rustiful-derive.
+    pub include: Include<I>,
+    /// A type-safe container for the "filter" query parameters in JSONAPI.
+    ///
+    /// The type parameter `<Fl>` will usually be an enum type that is generated using the
+    /// `JsonApi` attribute in rustiful-derive.
+    pub filter: Filter<Fl>,
+    /// The "page" query parameters in JSONAPI, if any. `JsonIndex` resources get the default and
+    /// maximum page size of the resource applied to this before it is passed to `find_all`.
+    pub page: Option<Page>,
+    /// A hashmap representing all other query parameters that are not `sort`, `include`,
+    /// `fields[*]`, `filter[*]` or `page[*]`.
     pub query_params: HashMap<String, Vec<String>>
 }
 
-impl<F, S> JsonApiParams<F, S> {
+impl<F, S, I, Fl> JsonApiParams<F, S, I, Fl> {
     pub fn new(
         fieldset: Vec<F>,
         sort_params: Vec<S>,
+        include_params: Vec<I>,
+        filter_params: Vec<Fl>,
         query_params: HashMap<String, Vec<String>>
-    ) -> JsonApiParams<F, S> {
+    ) -> JsonApiParams<F, S, I, Fl> {
         JsonApiParams {
             sort: Sort {
                 fields: sort_params
             },
-            fieldset: FieldSet { fields: fieldset },
+            fieldset: FieldSet {
+                fields: fieldset,
+                related: HashMap::new()
+            },
+            include: Include {
+                fields: include_params
+            },
+            filter: Filter {
+                fields: filter_params
+            },
+            page: None,
             query_params: query_params
         }
     }
@@ -75,19 +125,33 @@ impl<F, S> JsonApiParams<F, S> {
 
 /// Converts a query string to a type-safe representation.
 ///
-/// This function converts a query string to `JsonApiParams<F, S>` for any type `F` that has a
-/// `TryFrom<(&'b str, Vec<&'b str>)` implementation and for any type `S` that has a
-/// `TryFrom<(&'b str, SortOrder)` implementation. All of these types and implementations will
-/// typically be automatically generated when deriving `JsonApi` on your resource type.
+/// This function converts a query string to `JsonApiParams<F, S, I, Fl>` for any type `F` that
+/// implements `JsonApiFieldSetField`, for any type `S` that has a
+/// `TryFrom<(&'b str, SortOrder)` implementation, for any type `I` that has a `TryFrom<&'b str>`
+/// implementation and for any type `Fl` that has a `TryFrom<(&'b str, &'b str, &'b str)>`
+/// implementation.
+/// All of these types and implementations will typically be automatically generated when deriving
+/// `JsonApi` on your resource type.
 ///
 /// # Errors
 ///
-/// * If any field name in the `fields` query parameter doesn't match with any of the field
-/// names in the type deriving this trait (or rather if the string doesn't match with a string
-/// present in the `TryFrom` impl of the generated `field` enum.)
+/// * If any field name in the `fields[TYPE]` query parameter of the type deriving this trait
+/// doesn't match with any of its field names (or rather if the string doesn't match with a string
+/// present in the `JsonApiFieldSetField` impl of the generated `field` enum.)
+/// * If the `TYPE` in a `fields[TYPE]` query parameter is neither the type deriving this trait nor
+/// the type of a resource that can be included along with it.
 /// * If any field name in the `sort` query parameter doesn't match with any of the field
 /// names in the type deriving this trait (or rather if the string doesn't match with a string
 /// present in the `TryFrom` impl of the generated `sort` enum.)
+/// * If any relationship path in the `include` query parameter doesn't match with a relationship
+/// of the type deriving this trait, or with a relationship of the related type for each part of a
+/// dotted path such as `author.company`.
+/// * If any `page[*]` key isn't one of `number`, `size`, `offset`, `limit`, `after` or `before`,
+/// if more than one of the `number`/`size`, `offset`/`limit` and `after`/`before`/`size`
+/// pagination strategies are used, or if a page value isn't a valid number or cursor.
+/// * If any `filter[*]` key doesn't match with any of the attribute names in the type deriving
+/// this trait, if the operator in a `filter[*][*]` key isn't supported by the type of that
+/// attribute, or if the value of the key cannot be parsed to the type of that attribute.
 ///
 /// # Example
 ///
@@ -112,7 +176,8 @@ impl<F, S> JsonApiParams<F, S> {
 /// ```
 ///
 /// Then you can call `from_str` with a query string to create a new instance of
-/// `JsonApiParams<MyResource::FilterField, MyResource::SortField>`.
+/// `JsonApiParams<MyResource::FieldSetField, MyResource::SortField, MyResource::IncludeField,
+/// MyResource::FilterField>`.
 ///
 /// ```
 /// # extern crate rustiful;
@@ -138,20 +203,29 @@ impl<F, S> JsonApiParams<F, S> {
 /// # }
 /// #
 /// # fn main() {
-/// let query_string = "sort=-foo&fields[my-resources]=bar&other=test&other=abc";
+/// let query_string = "sort=-foo&fields[my-resources]=bar&filter[foo]=true&other=test&other=abc";
 /// let params = <MyResource as JsonApiResource>::Params::from_str(query_string);
 /// # }
 /// ```
-impl<F, S> FromStr for JsonApiParams<F, S>
+impl<F, S, I, Fl> FromStr for JsonApiParams<F, S, I, Fl>
 where
     S: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
-    F: for<'b> TryFrom<(&'b str, Vec<&'b str>), Error = QueryStringParseError>
+    F: JsonApiFieldSetField,
+    I: for<'b> TryFrom<&'b str, Error = QueryStringParseError>,
+    Fl: for<'b> TryFrom<(&'b str, &'b str, &'b str), Error = QueryStringParseError>
 {
     type Err = QueryStringParseError;
 
     fn from_str<'a>(query_string: &'a str) -> Result<Self, Self::Err> {
         let mut sort_params = Vec::new();
         let mut field_params = Vec::new();
+        let mut related_field_params: HashMap<String, Vec<String>> = HashMap::new();
+        let mut included_types: Vec<IncludedType> = Vec::new();
+        let mut include_params = Vec::new();
+        let mut filter_params = Vec::new();
+        let mut has_include_key = false;
+        let mut page_params: HashMap<String, u64> = HashMap::new();
+        let mut page_cursors: HashMap<String, Cursor> = HashMap::new();
         let mut query_params: HashMap<String, Vec<String>> = HashMap::new();
 
         let decoded = form_urlencoded::parse(query_string.as_bytes()).into_owned();
@@ -174,16 +248,30 @@ where
                         Err(err) => return Err(err)
                     }
                 }
-            } else if key.starts_with("fields") {
-                let mut model = key.trim_left_matches("fields");
+            } else if &key == "include" {
+                if has_include_key {
+                    return Err(QueryStringParseError::DuplicateIncludeKey(value));
+                }
 
-                if !model.starts_with('[') || !model.ends_with(']') {
-                    return Err(QueryStringParseError::InvalidFieldsetKey(model.to_string()));
+                has_include_key = true;
+
+                for path in value.split(',').filter(|&p| !p.is_empty()) {
+                    match I::try_from(path) {
+                        Ok(result) => include_params.push(result),
+                        Err(err) => return Err(err)
+                    }
                 }
+            } else if is_family(&key, "fields") {
+                // Either `fields[TYPE]`, or a key that is invalid such as `fields` or `fields[TYPE`
+                let model = &key["fields".len()..];
 
-                model = model.trim_left_matches('[').trim_right_matches(']');
+                if !model.starts_with('[') || !model.ends_with(']') || model.len() < 3 {
+                    return Err(QueryStringParseError::InvalidFieldsetKey(key.to_string()));
+                }
 
-                if model.is_empty() {
+                let model = &model[1..model.len() - 1];
+
+                if model.contains('[') || model.contains(']') {
                     return Err(QueryStringParseError::InvalidFieldsetKey(key.to_string()));
                 }
 
@@ -196,8 +284,84 @@ where
                     return Err(QueryStringParseError::EmptyFieldsetValue(model.to_string()));
                 }
 
-                match F::try_from((model, fields)) {
-                    Ok(result) => field_params.push(result),
+                if model == F::type_name() {
+                    for field in fields {
+                        match F::from_name(field) {
+                            Some(result) => field_params.push(result),
+                            None => {
+                                let e = QueryStringParseError::InvalidFieldValue(field.to_string());
+                                return Err(e);
+                            }
+                        }
+                    }
+
+                    continue;
+                }
+
+                if included_types.is_empty() {
+                    included_types.push((F::type_name(), |name| F::from_name(name).is_some()));
+                    F::included_types(&mut included_types);
+                }
+
+                let is_field = match included_types.iter().find(|&&(t, _)| t == model) {
+                    Some(&(_, is_field)) => is_field,
+                    None => {
+                        let e = QueryStringParseError::InvalidFieldsetType(model.to_string());
+                        return Err(e);
+                    }
+                };
+
+                if let Some(field) = fields.iter().find(|&&f| !is_field(f)) {
+                    return Err(QueryStringParseError::InvalidFieldValue(field.to_string()));
+                }
+
+                related_field_params
+                    .entry(model.to_string())
+                    .or_insert_with(Vec::new)
+                    .extend(fields.into_iter().map(|f| f.to_string()));
+            } else if is_family(&key, "page") {
+                let name = key.trim_left_matches("page");
+
+                match name {
+                    "[number]" | "[size]" | "[offset]" | "[limit]" => {
+                        match u64::from_str(&value) {
+                            Ok(result) => page_params.insert(key.to_string(), result),
+                            Err(_) => {
+                                let e = QueryStringParseError::InvalidPageValue(key.to_string());
+                                return Err(e);
+                            }
+                        };
+                    }
+                    "[after]" | "[before]" => {
+                        match Cursor::from_str(&value) {
+                            Ok(result) => page_cursors.insert(key.to_string(), result),
+                            Err(_) => {
+                                let e = QueryStringParseError::InvalidPageValue(key.to_string());
+                                return Err(e);
+                            }
+                        };
+                    }
+                    _ => return Err(QueryStringParseError::InvalidPageKey(key.to_string()))
+                }
+            } else if is_family(&key, "filter") {
+                let filter = key.trim_left_matches("filter");
+
+                if !filter.starts_with('[') || !filter.ends_with(']') {
+                    return Err(QueryStringParseError::InvalidFilterKey(key.to_string()));
+                }
+
+                // Either `[field]` or `[field][operator]`
+                let mut parts = filter[1..filter.len() - 1].splitn(2, "][");
+                let field = parts.next().unwrap_or("");
+                let operator = parts.next().unwrap_or("");
+
+                let is_valid = |s: &str| !s.contains('[') && !s.contains(']');
+                if field.is_empty() || !is_valid(field) || !is_valid(operator) {
+                    return Err(QueryStringParseError::InvalidFilterKey(key.to_string()));
+                }
+
+                match Fl::try_from((field, operator, value.as_str())) {
+                    Ok(result) => filter_params.push(result),
                     Err(err) => return Err(err)
                 }
             } else {
@@ -214,14 +378,111 @@ where
             }
         }
 
-        Ok(JsonApiParams::new(field_params, sort_params, query_params))
+        let mut params = JsonApiParams::new(
+            field_params,
+            sort_params,
+            include_params,
+            filter_params,
+            query_params
+        );
+        params.fieldset.related = related_field_params;
+        params.page = match to_page(&page_params, page_cursors) {
+            Ok(page) => page,
+            Err(err) => return Err(err)
+        };
+
+        Ok(params)
     }
 }
 
-impl<F, S> Default for JsonApiParams<F, S> {
+/// Whether `key` is one of the query parameters of the family `name`, such as `fields[articles]`
+/// for `fields`. A key that is only the family name is reserved for it as well, so that it's
+/// rejected instead of being treated as an implementation specific query parameter.
+fn is_family(key: &str, name: &str) -> bool {
+    key.starts_with(name) && (key.len() == name.len() || key[name.len()..].starts_with('['))
+}
+
+/// Converts the `page[*]` query parameters to a `Page`, if there are any.
+fn to_page(
+    params: &HashMap<String, u64>,
+    mut cursors: HashMap<String, Cursor>
+) -> Result<Option<Page>, QueryStringParseError> {
+    let get = |key: &str| params.get(key).cloned();
+    let (number, size) = (get("page[number]"), get("page[size]"));
+    let (offset, limit) = (get("page[offset]"), get("page[limit]"));
+    let (after, before) = (cursors.remove("page[after]"), cursors.remove("page[before]"));
+
+    for &(key, value) in &[("page[number]", number), ("page[size]", size), ("page[limit]", limit)] {
+        if value == Some(0) {
+            return Err(QueryStringParseError::InvalidPageValue(key.to_string()));
+        }
+    }
+
+    let is_cursor = after.is_some() || before.is_some();
+    let is_number = number.is_some() || (size.is_some() && !is_cursor);
+    let is_offset = offset.is_some() || limit.is_some();
+
+    if after.is_some() && before.is_some() {
+        return Err(QueryStringParseError::InvalidPageKey("page[before]".to_string()));
+    }
+
+    if is_cursor && (number.is_some() || is_offset) {
+        let key = if after.is_some() { "page[after]" } else { "page[before]" };
+        return Err(QueryStringParseError::InvalidPageKey(key.to_string()));
+    }
+
+    match (is_number, is_offset) {
+        (true, true) => {
+            let key = if offset.is_some() { "page[offset]" } else { "page[limit]" };
+            Err(QueryStringParseError::InvalidPageKey(key.to_string()))
+        }
+        (true, false) => Ok(Some(Page::Number {
+            number: number.unwrap_or(1),
+            size: size
+        })),
+        (false, true) => Ok(Some(Page::Offset {
+            offset: offset.unwrap_or(0),
+            limit: limit
+        })),
+        (false, false) if is_cursor => Ok(Some(Page::Cursor {
+            after: after,
+            before: before,
+            size: size
+        })),
+        (false, false) => Ok(None)
+    }
+}
+
+impl<F, S, I, Fl> JsonApiParams<F, S, I, Fl> {
+    /// The params to serialize an included resource of type `T` with, which contain the fieldset
+    /// that was requested for `T` with `fields[TYPE]`, if any.
+    ///
+    /// # Errors
+    ///
+    /// If any of the field names that were requested for `T` isn't a field of `T`.
+    pub fn included_params<T>(&self) -> Result<T::Params, QueryStringParseError>
+    where
+        T: JsonApiResource,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        let type_name = <T::FieldSetField as JsonApiFieldSetField>::type_name();
+        let query_string = match self.fieldset.related_fields(type_name) {
+            Some(fields) => {
+                form_urlencoded::Serializer::new(String::new())
+                    .append_pair(&format!("fields[{}]", type_name), &fields.join(","))
+                    .finish()
+            }
+            None => String::new()
+        };
+
+        T::Params::from_str(&query_string)
+    }
+}
+
+impl<F, S, I, Fl> Default for JsonApiParams<F, S, I, Fl> {
     fn default() -> Self {
         let query_params: HashMap<String, Vec<String>> = Default::default();
-        JsonApiParams::new(vec![], vec![], query_params)
+        JsonApiParams::new(vec![], vec![], vec![], vec![], query_params)
     }
 }
 
@@ -240,7 +501,61 @@ pub struct Sort<S> {
 /// The type parameter `<F>` will usually be an enum type that is generated using the `JsonApi`
 /// attribute in rustiful-derive.
 pub struct FieldSet<F> {
-    pub fields: Vec<F>
+    /// The fields in `fields[TYPE]` for the type of the resource itself.
+    pub fields: Vec<F>,
+    /// The field names in `fields[TYPE]` for the types of the resources that can be included along
+    /// with the resource, by type name. Use `JsonApiParams::included_params` to get the params to
+    /// serialize an included resource with.
+    pub related: HashMap<String, Vec<String>>
+}
+
+impl<F> FieldSet<F> {
+    /// The field names that were requested for the included resources of type `type_name`, if
+    /// any.
+    pub fn related_fields(&self, type_name: &str) -> Option<&[String]> {
+        self.related.get(type_name).map(|fields| fields.as_slice())
+    }
+}
+
+/// A field in the `fields[TYPE]` query parameter, i.e the `field` enum that is generated when
+/// deriving `JsonApi`.
+pub trait JsonApiFieldSetField: Sized {
+    /// The name of the resource type in `fields[TYPE]`.
+    fn type_name() -> &'static str;
+
+    /// The field named `name`, or `None` if the resource has no such attribute or relationship.
+    fn from_name(name: &str) -> Option<Self>;
+
+    /// Adds the resources that can be included along with this resource, either directly or
+    /// through a dotted path such as `author.company`, to `types`. Types that are already in
+    /// `types` are skipped along with their relationships.
+    fn included_types(types: &mut Vec<IncludedType>);
+}
+
+/// The type name of a resource that can be included, and whether a name is one of its fields,
+/// i.e `JsonApiFieldSetField::type_name` and `JsonApiFieldSetField::from_name` of the resource.
+/// The field names in `fields[TYPE]` are checked with this when the query string is parsed.
+pub type IncludedType = (&'static str, fn(&str) -> bool);
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A type-safe container for the "include" query parameter in JSONAPI.
+///
+/// The type parameter `<I>` will usually be an enum type that is generated using the `JsonApi`
+/// attribute in rustiful-derive. Each relationship path in the query parameter, such as `author`
+/// or `author.company`, is represented as one value in `fields`.
+pub struct Include<I> {
+    pub fields: Vec<I>
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+/// A type-safe container for the "filter" query parameters in JSONAPI.
+///
+/// The type parameter `<Fl>` will usually be an enum type that is generated using the `JsonApi`
+/// attribute in rustiful-derive. Each `filter[field]=value` or `filter[field][operator]=value`
+/// pair in the query string is represented as one value in `fields`, holding a `FilterValue` with
+/// the operator and the value parsed to the type of the field.
+pub struct Filter<Fl> {
+    pub fields: Vec<Fl>
 }
 
 #[derive(Debug, PartialEq, Eq, Copy, Clone)]
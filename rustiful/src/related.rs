@@ -0,0 +1,66 @@
+use container::JsonApiContainer;
+use data::JsonApiData;
+use page::JsonApiPage;
+use std::fmt::Debug;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use to_json::ToJson;
+
+/// The related resources of a relationship, which are the primary data of a related resource
+/// endpoint, e.g `GET /articles/1/author` or `GET /articles/1/comments`.
+pub enum JsonApiRelated<T: ToJson> {
+    /// The document with the related resource of a to-one relationship, or `None` if the
+    /// relationship is empty. Resources that are requested with `include` are side-loaded with
+    /// `JsonApiContainer::include`.
+    ToOne(Option<JsonApiContainer<JsonApiData<T>>>),
+    /// A page of the related resources of a to-many relationship.
+    ToMany(JsonApiPage<T>)
+}
+
+impl<T: ToJson> Debug for JsonApiRelated<T>
+where
+    JsonApiData<T>: Debug
+{
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            JsonApiRelated::ToOne(ref data) => f.debug_tuple("ToOne").field(data).finish(),
+            JsonApiRelated::ToMany(ref page) => f.debug_tuple("ToMany").field(page).finish()
+        }
+    }
+}
+
+impl<T: ToJson> From<JsonApiData<T>> for JsonApiRelated<T> {
+    fn from(data: JsonApiData<T>) -> Self {
+        JsonApiRelated::ToOne(Some(data.into()))
+    }
+}
+
+impl<T: ToJson> From<Option<JsonApiData<T>>> for JsonApiRelated<T> {
+    fn from(data: Option<JsonApiData<T>>) -> Self {
+        JsonApiRelated::ToOne(data.map(JsonApiContainer::from))
+    }
+}
+
+impl<T: ToJson> From<JsonApiContainer<JsonApiData<T>>> for JsonApiRelated<T> {
+    fn from(container: JsonApiContainer<JsonApiData<T>>) -> Self {
+        JsonApiRelated::ToOne(Some(container))
+    }
+}
+
+impl<T: ToJson> From<Option<JsonApiContainer<JsonApiData<T>>>> for JsonApiRelated<T> {
+    fn from(container: Option<JsonApiContainer<JsonApiData<T>>>) -> Self {
+        JsonApiRelated::ToOne(container)
+    }
+}
+
+impl<T: ToJson> From<Vec<JsonApiData<T>>> for JsonApiRelated<T> {
+    fn from(data: Vec<JsonApiData<T>>) -> Self {
+        JsonApiRelated::ToMany(data.into())
+    }
+}
+
+impl<T: ToJson> From<JsonApiPage<T>> for JsonApiRelated<T> {
+    fn from(page: JsonApiPage<T>) -> Self {
+        JsonApiRelated::ToMany(page)
+    }
+}
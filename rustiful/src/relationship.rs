@@ -0,0 +1,209 @@
+use std::fmt::Display;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// A JSONAPI resource identifier object, i.e `{"type": "people", "id": "1"}`.
+pub struct JsonApiResourceIdentifier {
+    /// The id of the linked resource.
+    pub id: String,
+    #[serde(rename = "type")]
+    /// The type name of the linked resource, equivalent to its resource name.
+    pub type_name: String
+}
+
+impl JsonApiResourceIdentifier {
+    pub fn new<T: Into<String>, Id: Into<String>>(type_name: T, id: Id) -> Self {
+        JsonApiResourceIdentifier {
+            id: id.into(),
+            type_name: type_name.into()
+        }
+    }
+
+    /// Converts the id of this identifier to `T`.
+    ///
+    /// # Errors
+    ///
+    /// * If the type name of this identifier isn't `type_name`.
+    /// * If the id cannot be converted to `T`.
+    pub fn parse_id<T>(&self, type_name: &str) -> Result<T, String>
+    where
+        T: FromStr,
+        T::Err: Display
+    {
+        if self.type_name != type_name {
+            return Err(format!(
+                "Invalid type name '{}', expected '{}'",
+                self.type_name,
+                type_name
+            ));
+        }
+
+        T::from_str(&self.id).map_err(|e| format!("Failed to parse id value {}: {}", &self.id, e))
+    }
+}
+
+/// A relationship of a resource. This is implemented for the `relationship` enum that is generated
+/// when deriving `JsonApi`, which has one variant per field with the `JsonApiRelationship`
+/// attribute.
+pub trait JsonApiRelationshipField: FromStr {
+    /// The name of the relationship, as used in the `relationships` object and in the URL of the
+    /// relationship endpoints.
+    fn name(&self) -> &'static str;
+
+    /// The resource name of the related resource.
+    fn type_name(&self) -> &'static str;
+
+    /// Whether this is a to-many relationship.
+    fn is_to_many(&self) -> bool;
+
+    /// All relationships of the resource.
+    fn all() -> Vec<Self>;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+/// The resource linkage of a relationship, which is the primary data of a relationship endpoint,
+/// e.g `GET /articles/1/relationships/tags`.
+pub enum JsonApiLinkage {
+    /// The linkage of a to-many relationship, serialized as an array.
+    ToMany(Vec<JsonApiResourceIdentifier>),
+    /// The linkage of a to-one relationship, serialized as `null` when the relationship is empty.
+    ToOne(Option<JsonApiResourceIdentifier>)
+}
+
+impl JsonApiLinkage {
+    pub fn is_to_many(&self) -> bool {
+        match *self {
+            JsonApiLinkage::ToMany(_) => true,
+            JsonApiLinkage::ToOne(_) => false
+        }
+    }
+
+    /// The resource identifiers of this linkage.
+    pub fn identifiers(&self) -> Vec<&JsonApiResourceIdentifier> {
+        match *self {
+            JsonApiLinkage::ToMany(ref identifiers) => identifiers.iter().collect(),
+            JsonApiLinkage::ToOne(ref identifier) => identifier.iter().collect()
+        }
+    }
+
+    /// Converts the ids of this linkage to `Vec<T>`. See `JsonApiResourceIdentifier::parse_id` for
+    /// possible errors.
+    pub fn parse_ids<T>(&self, type_name: &str) -> Result<Vec<T>, String>
+    where
+        T: FromStr,
+        T::Err: Display
+    {
+        self.identifiers().iter().map(|i| i.parse_id(type_name)).collect()
+    }
+}
+
+impl From<ToOneRelationship> for JsonApiLinkage {
+    fn from(relationship: ToOneRelationship) -> Self {
+        JsonApiLinkage::ToOne(relationship.data)
+    }
+}
+
+impl From<ToManyRelationship> for JsonApiLinkage {
+    fn from(relationship: ToManyRelationship) -> Self {
+        JsonApiLinkage::ToMany(relationship.data)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A to-one relationship object. A `None` in `data` means that the relationship is empty, which is
+/// serialized as `{"data": null}`.
+pub struct ToOneRelationship {
+    pub data: Option<JsonApiResourceIdentifier>
+}
+
+impl ToOneRelationship {
+    pub fn new(data: Option<JsonApiResourceIdentifier>) -> Self {
+        ToOneRelationship { data: data }
+    }
+
+    /// Converts the resource linkage of this relationship to `Option<T>`. See
+    /// `JsonApiResourceIdentifier::parse_id` for possible errors.
+    pub fn parse_id<T>(&self, type_name: &str) -> Result<Option<T>, String>
+    where
+        T: FromStr,
+        T::Err: Display
+    {
+        match self.data {
+            Some(ref identifier) => identifier.parse_id(type_name).map(Some),
+            None => Ok(None)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A to-many relationship object, serialized as `{"data": [...]}`.
+pub struct ToManyRelationship {
+    pub data: Vec<JsonApiResourceIdentifier>
+}
+
+impl ToManyRelationship {
+    pub fn new(data: Vec<JsonApiResourceIdentifier>) -> Self {
+        ToManyRelationship { data: data }
+    }
+
+    /// Converts the resource linkage of this relationship to `Vec<T>`. See
+    /// `JsonApiResourceIdentifier::parse_id` for possible errors.
+    pub fn parse_ids<T>(&self, type_name: &str) -> Result<Vec<T>, String>
+    where
+        T: FromStr,
+        T::Err: Display
+    {
+        self.data.iter().map(|i| i.parse_id(type_name)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate serde_json;
+
+    use super::*;
+
+    #[test]
+    fn parse_to_one_id() {
+        let rel = ToOneRelationship::new(Some(JsonApiResourceIdentifier::new("people", "1")));
+        assert_eq!(Ok(Some(1)), rel.parse_id::<u8>("people"));
+    }
+
+    #[test]
+    fn parse_empty_to_one_id() {
+        let rel = ToOneRelationship::new(None);
+        assert_eq!(Ok(None), rel.parse_id::<u8>("people"));
+    }
+
+    #[test]
+    fn parse_to_one_id_fails_on_type_mismatch() {
+        let rel = ToOneRelationship::new(Some(JsonApiResourceIdentifier::new("tags", "1")));
+        assert_eq!(
+            Err("Invalid type name 'tags', expected 'people'".to_string()),
+            rel.parse_id::<u8>("people")
+        );
+    }
+
+    #[test]
+    fn parse_to_many_ids() {
+        let rel = ToManyRelationship::new(vec![
+            JsonApiResourceIdentifier::new("tags", "1"),
+            JsonApiResourceIdentifier::new("tags", "2")
+        ]);
+        assert_eq!(Ok(vec![1, 2]), rel.parse_ids::<u8>("tags"));
+    }
+
+    #[test]
+    fn deserialize_linkage() {
+        let to_many: JsonApiLinkage = serde_json::from_str(r#"[{"type":"tags","id":"1"}]"#)
+            .unwrap();
+        let to_one: JsonApiLinkage = serde_json::from_str("null").unwrap();
+
+        assert_eq!(
+            JsonApiLinkage::ToMany(vec![JsonApiResourceIdentifier::new("tags", "1")]),
+            to_many
+        );
+        assert_eq!(JsonApiLinkage::ToOne(None), to_one);
+    }
+}
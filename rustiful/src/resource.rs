@@ -1,4 +1,6 @@
 use errors::QueryStringParseError;
+use page::PageParams;
+use relationship::JsonApiRelationshipField;
 use std::fmt::Debug;
 use std::str::FromStr;
 
@@ -6,12 +8,21 @@ use std::str::FromStr;
 ///
 /// This trait is automatically implemented for any type that derives the `JsonApi` attribute.
 pub trait JsonApiResource: Sized {
-    /// An alias for `JsonApiParams<Self::SortField, Self::FilterField>`
-    type Params: FromStr<Err = QueryStringParseError>;
+    /// An alias for
+    /// `JsonApiParams<Self::FieldSetField, Self::SortField, Self::IncludeField, Self::FilterField>`
+    type Params: FromStr<Err = QueryStringParseError> + PageParams;
     /// This type is typically generated in rustiful-derive.
     type SortField;
     /// This type is typically generated in rustiful-derive.
+    type FieldSetField;
+    /// This type is typically generated in rustiful-derive, with one variant per filterable
+    /// attribute.
     type FilterField;
+    /// This type is typically generated in rustiful-derive, with one variant per relationship.
+    type IncludeField;
+    /// This type is typically generated in rustiful-derive, with one variant per relationship. It
+    /// is used to route requests to the relationship endpoints of a resource.
+    type RelationshipField: JsonApiRelationshipField;
     /// The type of a field named `id` or the type of a field that has the `#[JsonApiId]` attribute
     /// on the type deriving `JsonApi`.
     type JsonApiIdType: FromStr + Debug;
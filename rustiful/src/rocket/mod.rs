@@ -0,0 +1,94 @@
+//! A [Rocket](https://rocket.rs) 0.5 adapter, as an alternative to the Iron adapter in
+//! `rustiful::iron`.
+//!
+//! `JsonApiRoutesBuilder` registers the endpoints of resources in the same way as
+//! `JsonApiRouterBuilder`, and builds the Rocket routes of these endpoints. The context of the
+//! service traits is a Rocket request guard, i.e it implements `rocket::request::FromRequest`.
+//! The content of a request is negotiated before its guard runs.
+
+mod routes_builder;
+pub use self::routes_builder::*;
+
+extern crate rocket;
+
+use self::rocket::Request;
+use self::rocket::Response;
+use self::rocket::http::Status;
+use self::rocket::outcome::Outcome;
+use self::rocket::route;
+use dispatch::JsonApiRequest;
+use dispatch::JsonApiResponse;
+use service::HttpStatus;
+use std::io::Cursor;
+
+impl HttpStatus for Status {
+    fn status_code(&self) -> u16 {
+        self.code
+    }
+
+    fn from_status_code(code: u16) -> Self {
+        Status::new(code)
+    }
+}
+
+/// Converts a Rocket request to a `JsonApiRequest`, with the `id` and `relationship` parameters of
+/// the route. `params` are the dynamic segments of the route by their index, see
+/// `Request::param`.
+fn json_api_request(
+    req: &Request,
+    params: &[(usize, String)],
+    body: Vec<u8>,
+    base_url: String
+) -> JsonApiRequest {
+    let uri = req.uri();
+    let mut request = JsonApiRequest::new(req.method().as_str(), uri.path().as_str())
+        .with_query(uri.query().map_or("", |query| query.as_str()))
+        .with_body(body)
+        .with_base_url(base_url);
+
+    for &(index, ref name) in params {
+        if let Some(Ok(value)) = req.param::<&str>(index) {
+            request = request.with_param(name.as_str(), value);
+        }
+    }
+
+    for header in req.headers().iter() {
+        request = request.with_header(header.name().as_str(), header.value());
+    }
+
+    request
+}
+
+fn rocket_response<'r>(response: JsonApiResponse) -> route::Outcome<'r> {
+    let mut builder = Response::build();
+    builder.status(Status::new(response.status));
+
+    for (name, value) in response.headers {
+        builder.raw_header(name, value);
+    }
+
+    if let Some(body) = response.body {
+        builder.sized_body(body.len(), Cursor::new(body));
+    }
+
+    Outcome::Success(builder.finalize())
+}
+
+/// The base URL of any links in a response. This is the base URL set with
+/// `JsonApiRoutesBuilder::set_base_url` if there is one, otherwise it is `http://` and the host of
+/// the request. Rocket only knows the host of requests that it has parsed itself, so the `Host`
+/// header is used for any other requests, e.g those of a local `Client`.
+fn base_url(req: &Request, base_url: &Option<String>) -> String {
+    if let Some(ref base_url) = *base_url {
+        return base_url.clone();
+    }
+
+    let host = req.host()
+        .map(|host| host.to_string())
+        .or_else(|| req.headers().get_one("Host").map(|host| host.to_string()));
+
+    match host {
+        Some(host) => format!("http://{}", host),
+        None => "http://localhost".to_string()
+    }
+}
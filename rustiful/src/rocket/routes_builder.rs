@@ -0,0 +1,576 @@
+extern crate rocket;
+
+use self::rocket::Data;
+use self::rocket::Request;
+use self::rocket::Route;
+use self::rocket::data::Capped;
+use self::rocket::data::ToByteUnit;
+use self::rocket::http::Method;
+use self::rocket::http::Status;
+use self::rocket::outcome::Outcome;
+use self::rocket::request;
+use self::rocket::request::FromRequest;
+use self::rocket::route;
+use self::rocket::route::BoxFuture;
+use super::base_url;
+use super::json_api_request;
+use super::rocket_response;
+use dispatch::*;
+use errors::ContextError;
+use errors::QueryStringParseError;
+use errors::RequestError;
+use operations::ATOMIC_EXTENSION;
+use operations::JsonApiTransaction;
+use params::JsonApiFieldSetField;
+use params::SortOrder;
+use relationship::JsonApiRelationshipField;
+use resource::JsonApiResource;
+use service::JsonGetRelated;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::future::Future;
+use std::io;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use to_json::ToJson;
+
+/// Dispatches a request with the context of its endpoint.
+type Dispatch<C> = Arc<Fn(&JsonApiRequest, C) -> JsonApiResponseFuture + Send + Sync>;
+
+/// Creates a route once the configuration of the builder is known.
+type RouteFactory = Box<Fn(&Arc<Config>) -> Route>;
+
+/// The configuration of `JsonApiRoutesBuilder` that is shared by all of its routes.
+#[derive(Debug)]
+struct Config {
+    max_body_length: usize,
+    base_url: Option<String>,
+    negotiation: ContentNegotiation
+}
+
+/// Constructs the Rocket routes of resources implementing any of the `JsonGet`, `JsonPost`,
+/// `JsonIndex`, `JsonPatch` or `JsonDelete` traits, or their async variants such as
+/// `AsyncJsonGet`.
+///
+/// This has the same registration methods as `JsonApiRouterBuilder`, and registers the same
+/// routes. The context of the service traits is a Rocket request guard, so it must implement
+/// `rocket::request::FromRequest` and its error must implement `std::error::Error`. If the guard
+/// fails, the response is a JSONAPI error document with the status of the guard. If it forwards,
+/// the request is forwarded to the next matching route.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use rustiful::rocket::JsonApiRoutesBuilder;
+///
+/// let mut builder = JsonApiRoutesBuilder::default();
+/// builder.jsonapi_get::<MyResource>();
+/// builder.jsonapi_index::<MyResource>();
+///
+/// rocket::build().mount("/", builder.build())
+/// ```
+#[allow(missing_debug_implementations)] // The route factories don't implement Debug...
+pub struct JsonApiRoutesBuilder {
+    routes: Vec<RouteFactory>,
+    max_body_length: usize,
+    base_url: Option<String>,
+    negotiation: ContentNegotiation
+}
+
+/// This `Default` implementation sets the maximum body length to 10MB.
+impl Default for JsonApiRoutesBuilder {
+    fn default() -> Self {
+        Self::new(10 * 1024 * 1024)
+    }
+}
+
+impl JsonApiRoutesBuilder {
+    /// Constructs a new `JsonApiRoutesBuilder` with a maximum body length in bytes.
+    pub fn new(max_body_length: usize) -> Self {
+        JsonApiRoutesBuilder {
+            routes: Vec::new(),
+            max_body_length: max_body_length,
+            base_url: None,
+            negotiation: ContentNegotiation::new()
+        }
+    }
+
+    /// Sets the max body length for any incoming JSON document. This is specified in bytes.
+    /// Requests with a larger body get a `413 Payload Too Large` response.
+    pub fn set_max_body_length(&mut self, max_body_length: usize) {
+        self.max_body_length = max_body_length;
+    }
+
+    /// Sets the base URL of the `self` links in any responses, such as `https://example.com/api`.
+    ///
+    /// If no base URL is set, `http://` and the host of each request are used instead. Set this
+    /// when the server uses TLS or is behind a proxy.
+    pub fn set_base_url<S: Into<String>>(&mut self, base_url: S) {
+        self.base_url = Some(base_url.into());
+    }
+
+    /// Declares support for the JSONAPI extension with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_extension`.
+    pub fn add_extension<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_extension(uri);
+    }
+
+    /// Declares support for the JSONAPI profile with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_profile`.
+    pub fn add_profile<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_profile(uri);
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `JsonIndex`.
+    pub fn jsonapi_index<T>(&mut self)
+    where
+        T: 'static,
+        T: IndexDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::Get, format!("/{}", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `JsonGet`.
+    pub fn jsonapi_get<T>(&mut self)
+    where
+        T: 'static,
+        T: GetDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::Get, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `JsonDelete`.
+    pub fn jsonapi_delete<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::Delete, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `JsonPost`.
+    pub fn jsonapi_post<T>(&mut self)
+    where
+        T: 'static,
+        T: PostDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::Post, format!("/{}", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `JsonPatch`.
+    pub fn jsonapi_patch<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::Patch, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonGetRelationship`.
+    pub fn jsonapi_get_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: GetRelationshipDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/<id>/relationships/<relationship>", T::RESOURCE_NAME);
+        self.route(Method::Get, path, |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPatchRelationship`.
+    pub fn jsonapi_patch_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchRelationshipDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/<id>/relationships/<relationship>", T::RESOURCE_NAME);
+        self.route(Method::Patch, path, |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `POST /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPostRelationship`.
+    pub fn jsonapi_post_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PostRelationshipDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/<id>/relationships/<relationship>", T::RESOURCE_NAME);
+        self.route(Method::Post, path, |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonDeleteRelationship`.
+    pub fn jsonapi_delete_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteRelationshipDispatcher,
+        T::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/<id>/relationships/<relationship>", T::RESOURCE_NAME);
+        self.route(Method::Delete, path, |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the routes `GET /my-resources/{id}/{relationship}` for a struct that implements
+    /// `JsonGetRelated<R>`, for each relationship that refers to `R`. Call this once for each
+    /// type of related resource.
+    pub fn jsonapi_get_related<T, R>(&mut self)
+    where
+        T: 'static,
+        T: GetRelatedDispatcher<R>,
+        T::RelationshipField: Copy + Send + Sync + 'static,
+        <T as JsonGetRelated<R>>::Context: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <<T as JsonGetRelated<R>>::Context as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error,
+        R: JsonApiResource + ToJson + 'static
+    {
+        let relationships = T::RelationshipField::all()
+            .into_iter()
+            .filter(|r| r.type_name() == R::RESOURCE_NAME);
+
+        for relationship in relationships {
+            let path = format!("/{}/<id>/{}", T::RESOURCE_NAME, relationship.name());
+            self.route(Method::Get, path, move |req, ctx| {
+                let context = move || Ok::<_, (RequestError, Status)>(ctx);
+                <T as GetRelatedDispatcher<R>>::dispatch(req, relationship, context)
+            });
+        }
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `AsyncJsonIndex`.
+    pub fn jsonapi_index_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncIndexDispatcher,
+        T::AsyncContext: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::AsyncContext as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::Get, format!("/{}", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `AsyncJsonGet`.
+    pub fn jsonapi_get_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncGetDispatcher,
+        T::AsyncContext: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::AsyncContext as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::Get, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `AsyncJsonDelete`.
+    pub fn jsonapi_delete_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncDeleteDispatcher,
+        T::AsyncContext: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::AsyncContext as FromRequest<'r>>::Error: Error,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::Delete, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `AsyncJsonPost`.
+    pub fn jsonapi_post_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPostDispatcher,
+        T::AsyncContext: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::AsyncContext as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::Post, format!("/{}", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `AsyncJsonPatch`.
+    pub fn jsonapi_patch_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPatchDispatcher,
+        T::AsyncContext: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <T::AsyncContext as FromRequest<'r>>::Error: Error,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::Patch, format!("/{}/<id>", T::RESOURCE_NAME), |req, ctx| {
+            T::dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Setup the route `POST /operations` for the Atomic Operations extension, see
+    /// `JsonApiRouterBuilder::jsonapi_operations`.
+    pub fn jsonapi_operations<C>(&mut self, operations: OperationsHandler<C>)
+    where
+        C: for<'r> FromRequest<'r> + JsonApiTransaction + Clone + Send + 'static,
+        for<'r> <C as FromRequest<'r>>::Error: Error
+    {
+        self.add_extension(ATOMIC_EXTENSION);
+        self.route(Method::Post, "/operations".to_string(), move |req, ctx: C| {
+            operations.dispatch(req, move || Ok::<_, (RequestError, Status)>(ctx))
+        });
+    }
+
+    /// Constructs the Rocket routes that were previously specified in `jsonapi_get`,
+    /// `jsonapi_post` et cetera, which can then be mounted with `Rocket::mount`.
+    pub fn build(self) -> Vec<Route> {
+        let config = Arc::new(Config {
+            max_body_length: self.max_body_length,
+            base_url: self.base_url,
+            negotiation: self.negotiation
+        });
+
+        self.routes.iter().map(|route| route(&config)).collect()
+    }
+
+    fn route<C, F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        C: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <C as FromRequest<'r>>::Error: Error,
+        F: Fn(&JsonApiRequest, C) -> JsonApiResponse + Send + Sync + 'static
+    {
+        self.route_async(method, path, move |req, ctx| dispatch(req, ctx).into());
+    }
+
+    fn route_async<C, F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        C: for<'r> FromRequest<'r> + Send + 'static,
+        for<'r> <C as FromRequest<'r>>::Error: Error,
+        F: Fn(&JsonApiRequest, C) -> JsonApiResponseFuture + Send + Sync + 'static
+    {
+        let dispatch: Dispatch<C> = Arc::new(dispatch);
+        let params = Arc::new(path_params(&path));
+
+        self.routes.push(Box::new(move |config: &Arc<Config>| {
+            let config = config.clone();
+            let params = params.clone();
+            let dispatch = dispatch.clone();
+            let handler = handler(move |req, data| {
+                let params = params.clone();
+                Box::pin(Respond::new(req, data, config.clone(), params, dispatch.clone()))
+            });
+
+            Route::new(method, &path, handler)
+        }));
+    }
+}
+
+/// The dynamic segments of `path` by their index, e.g `(1, "id")` for `/foos/<id>`.
+fn path_params(path: &str) -> Vec<(usize, String)> {
+    path.trim_matches('/')
+        .split('/')
+        .enumerate()
+        .filter(|&(_, segment)| segment.starts_with('<') && segment.ends_with('>'))
+        .map(|(index, segment)| (index, segment[1..segment.len() - 1].to_string()))
+        .collect()
+}
+
+/// Pins down the signature of a closure, so that it implements `rocket::route::Handler`.
+fn handler<F>(handler: F) -> F
+where
+    F: for<'r, 'c> Fn(&'r Request<'c>, Data<'r>) -> BoxFuture<'r> + Clone + Send + Sync + 'static
+{
+    handler
+}
+
+/// Negotiates the content of the request, runs the request guard of the context, reads the body,
+/// dispatches the request, and then waits for the response of the dispatcher.
+struct Respond<'r, 'c: 'r, C: FromRequest<'r>> {
+    req: &'r Request<'c>,
+    data: Option<Data<'r>>,
+    config: Arc<Config>,
+    params: Arc<Vec<(usize, String)>>,
+    dispatch: Dispatch<C>,
+    media_type: JsonApiMediaType,
+    guard: Option<BoxFuture<'r, request::Outcome<C, C::Error>>>,
+    context: Option<C>,
+    body: Option<BoxFuture<'r, io::Result<Capped<Vec<u8>>>>>,
+    response: Option<JsonApiResponseFuture>
+}
+
+/// None of the fields are pinned, the futures are boxed instead.
+impl<'r, 'c: 'r, C: FromRequest<'r>> Unpin for Respond<'r, 'c, C> {}
+
+impl<'r, 'c: 'r, C> Respond<'r, 'c, C>
+where
+    C: FromRequest<'r> + Send + 'static,
+    C::Error: Error
+{
+    fn new(
+        req: &'r Request<'c>,
+        data: Data<'r>,
+        config: Arc<Config>,
+        params: Arc<Vec<(usize, String)>>,
+        dispatch: Dispatch<C>
+    ) -> Self {
+        // The content is negotiated before the guard runs, since the body isn't needed for it.
+        let request = json_api_request(req, &params, Vec::new(), String::new());
+        let (media_type, guard, response) = match config.negotiation.negotiate(&request) {
+            Ok(media_type) => (media_type, Some(req.guard::<C>()), None),
+            Err((e, status)) => {
+                let response = JsonApiResponse::error(&e, status).into();
+                (JsonApiMediaType::default(), None, Some(response))
+            }
+        };
+
+        Respond {
+            req: req,
+            data: Some(data),
+            config: config,
+            params: params,
+            dispatch: dispatch,
+            media_type: media_type,
+            guard: guard,
+            context: None,
+            body: None,
+            response: response
+        }
+    }
+
+    /// Polls the request guard, and starts reading the body once the guard succeeds. This is
+    /// `Some` if the request is done, i.e if the guard failed or forwarded the request.
+    fn poll_guard(&mut self, cx: &mut Context) -> Poll<Option<route::Outcome<'r>>> {
+        let outcome = match self.guard.as_mut() {
+            Some(guard) => match guard.as_mut().poll(cx) {
+                Poll::Ready(outcome) => outcome,
+                Poll::Pending => return Poll::Pending
+            },
+            None => return Poll::Ready(None)
+        };
+
+        self.guard = None;
+        let data = self.data.take().expect("No data found in request that expects one!");
+
+        match outcome {
+            Outcome::Success(context) => {
+                let limit = self.config.max_body_length.bytes();
+                self.context = Some(context);
+                self.body = Some(Box::pin(data.open(limit).into_bytes()));
+                Poll::Ready(None)
+            }
+            Outcome::Error((status, e)) => {
+                let response = JsonApiResponse::error(&ContextError(e), status.code);
+                Poll::Ready(Some(rocket_response(response)))
+            }
+            Outcome::Forward(status) => Poll::Ready(Some(Outcome::Forward((data, status))))
+        }
+    }
+}
+
+impl<'r, 'c: 'r, C> Future for Respond<'r, 'c, C>
+where
+    C: FromRequest<'r> + Send + 'static,
+    C::Error: Error
+{
+    type Output = route::Outcome<'r>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        match this.poll_guard(cx) {
+            Poll::Ready(Some(outcome)) => return Poll::Ready(outcome),
+            Poll::Ready(None) => {}
+            Poll::Pending => return Poll::Pending
+        }
+
+        if this.response.is_none() {
+            let body = this.body.as_mut().expect("Respond polled after completion!");
+            let body = match body.as_mut().poll(cx) {
+                Poll::Ready(body) => body,
+                Poll::Pending => return Poll::Pending
+            };
+
+            this.body = None;
+
+            this.response = Some(match body {
+                Ok(ref body) if !body.is_complete() => {
+                    JsonApiResponse::error(&RequestError::BodyTooLarge, 413).into()
+                }
+                Ok(body) => {
+                    let context = this.context.take().expect("No context found for request!");
+                    let base_url = base_url(this.req, &this.config.base_url);
+                    let body = body.into_inner();
+                    let request = json_api_request(this.req, &this.params, body, base_url)
+                        .with_media_type(this.media_type.clone());
+                    (this.dispatch)(&request, context)
+                }
+                Err(_) => JsonApiResponse::error(&RequestError::UnreadableBody, 400).into()
+            });
+        }
+
+        match this.response.as_mut().map(|response| response.as_mut().poll(cx)) {
+            Some(Poll::Ready(response)) => {
+                Poll::Ready(rocket_response(response.with_media_type(&this.media_type)))
+            }
+            _ => Poll::Pending
+        }
+    }
+}
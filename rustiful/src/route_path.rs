@@ -0,0 +1,88 @@
+extern crate url;
+
+use self::url::percent_encoding::percent_decode;
+use dispatch::JsonApiResponse;
+use errors::RequestError;
+use std::collections::HashMap;
+
+/// A segment of the path of a route, such as `foos` or `:id`.
+enum Segment {
+    Static(String),
+    Param(String)
+}
+
+/// The path of a route, such as `/foos/:id`, with a `:name` segment for each path parameter.
+pub struct RoutePath {
+    segments: Vec<Segment>
+}
+
+impl RoutePath {
+    pub fn new(path: &str) -> Self {
+        let segments = path.trim_matches('/')
+            .split('/')
+            .map(|segment| if segment.starts_with(':') {
+                Segment::Param(segment[1..].to_string())
+            } else {
+                Segment::Static(segment.to_string())
+            })
+            .collect();
+
+        RoutePath { segments: segments }
+    }
+
+    /// The percent-decoded parameters in `path` if it matches this route path, otherwise `None`.
+    pub fn matches(&self, path: &str) -> Option<HashMap<String, String>> {
+        let parts: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+        if parts.len() != self.segments.len() {
+            return None;
+        }
+
+        let mut params = HashMap::new();
+
+        for (segment, part) in self.segments.iter().zip(parts) {
+            match *segment {
+                Segment::Static(ref name) if name == part => {}
+                Segment::Param(ref name) if !part.is_empty() => {
+                    params.insert(name.clone(), decode_param(part));
+                }
+                _ => return None
+            }
+        }
+
+        Some(params)
+    }
+}
+
+/// A path parameter with its percent-encoded octets decoded, e.g `a%20b` is `a b`. Octets that
+/// aren't valid UTF-8 are replaced with the replacement character.
+pub fn decode_param(param: &str) -> String {
+    percent_decode(param.as_bytes()).decode_utf8_lossy().into_owned()
+}
+
+/// The `405 Method Not Allowed` response to a request with a path that only matches routes with
+/// other methods. `allowed` are the methods of these routes, which are sent in the `Allow` header.
+pub fn method_not_allowed(allowed: &[&str]) -> JsonApiResponse {
+    JsonApiResponse::error(&RequestError::MethodNotAllowed, 405)
+        .with_header("Allow", &allowed.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_with_params() {
+        let path = RoutePath::new("/foos/:id/relationships/:relationship");
+        let params = path.matches("/foos/1/relationships/bar").unwrap();
+
+        assert_eq!(Some(&"1".to_string()), params.get("id"));
+        assert_eq!(Some(&"bar".to_string()), params.get("relationship"));
+    }
+
+    #[test]
+    fn matches_with_percent_encoded_params() {
+        let params = RoutePath::new("/foos/:id").matches("/foos/a%20b%2Fc").unwrap();
+        assert_eq!(Some(&"a b/c".to_string()), params.get("id"));
+    }
+}
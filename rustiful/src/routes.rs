@@ -0,0 +1,180 @@
+use dispatch::JsonApiRequest;
+use dispatch::JsonApiResponse;
+use errors::RequestError;
+use route_path::RoutePath;
+use route_path::method_not_allowed;
+
+/// Calls a dispatcher with the `JsonApiRequest` and the request type `R` of a framework, which is
+/// used to create the context of the service trait. `O` is the response of the dispatcher, i.e
+/// `JsonApiResponse` or `JsonApiResponseFuture`.
+pub type Dispatch<R, O> = Box<Fn(&JsonApiRequest, &R) -> O + Send + Sync>;
+
+/// A route to a dispatcher, such as `GET /foos/:id`.
+struct Route<R, O> {
+    method: String,
+    path: RoutePath,
+    dispatch: Dispatch<R, O>
+}
+
+/// The head of a request of an adapter that is built on the `http` crate. hyper and tower use
+/// different versions of `http`, so each of them implements this for its own request type.
+pub trait RequestHead {
+    fn method(&self) -> &str;
+
+    fn path(&self) -> &str;
+
+    fn query(&self) -> Option<&str>;
+
+    /// The scheme, host and port of the request URL, e.g `https://example.com:8080`. This is
+    /// `None` for a request in origin form, i.e with only a path and a query in its request line.
+    fn origin(&self) -> Option<String>;
+
+    /// The names and values of the headers. Values that aren't visible ASCII are left out.
+    fn headers(&self) -> Vec<(&str, &str)>;
+}
+
+/// Converts the head of a request and its body to a `JsonApiRequest`. The `id` and
+/// `relationship` parameters are set when the request is routed.
+pub fn json_api_request<H: RequestHead>(
+    head: &H,
+    body: Vec<u8>,
+    base_url: String
+) -> JsonApiRequest {
+    let mut request = JsonApiRequest::new(head.method(), head.path())
+        .with_query(head.query().unwrap_or(""))
+        .with_body(body)
+        .with_base_url(base_url);
+
+    for (name, value) in head.headers() {
+        request = request.with_header(name, value);
+    }
+
+    request
+}
+
+/// The base URL of any links in a response. This is the base URL set with the `set_base_url`
+/// method of the builder if there is one, otherwise it is the origin of the request URL, or
+/// `http://` and the `Host` header for requests in origin form.
+pub fn base_url<H: RequestHead>(head: &H, base_url: &Option<String>) -> String {
+    if let Some(ref base_url) = *base_url {
+        return base_url.clone();
+    }
+
+    if let Some(origin) = head.origin() {
+        return origin;
+    }
+
+    let host = head.headers()
+        .into_iter()
+        .find(|&(name, _)| name.eq_ignore_ascii_case("Host"))
+        .map_or("localhost", |(_, host)| host);
+
+    format!("http://{}", host)
+}
+
+/// The routes of an adapter without a router of its own, such as `rustiful::hyper`. `R` is the
+/// request type of the framework.
+pub struct Routes<R, O = JsonApiResponse> {
+    routes: Vec<Route<R, O>>
+}
+
+impl<R, O> Default for Routes<R, O> {
+    fn default() -> Self {
+        Routes { routes: Vec::new() }
+    }
+}
+
+impl<R, O: From<JsonApiResponse>> Routes<R, O> {
+    /// Adds a route for `method` and `path`, where `path` has a `:name` segment for each path
+    /// parameter, e.g `/foos/:id`.
+    pub fn add<F>(&mut self, method: &str, path: &str, dispatch: F)
+    where
+        F: Fn(&JsonApiRequest, &R) -> O + Send + Sync + 'static
+    {
+        self.routes.push(Route {
+            method: method.to_string(),
+            path: RoutePath::new(path),
+            dispatch: Box::new(dispatch)
+        });
+    }
+
+    /// Dispatches `request` to the first route that matches its method and path, with the path
+    /// parameters of the route. This is a `404 Not Found` response if no route matches the path,
+    /// and a `405 Method Not Allowed` response with an `Allow` header if routes match the path
+    /// but not the method.
+    pub fn dispatch(&self, mut request: JsonApiRequest, req: &R) -> O {
+        let mut allowed: Vec<&str> = Vec::new();
+
+        for route in &self.routes {
+            if let Some(params) = route.path.matches(&request.path) {
+                if route.method == request.method {
+                    request.params = params;
+                    return (route.dispatch)(&request, req);
+                }
+
+                if !allowed.contains(&route.method.as_str()) {
+                    allowed.push(&route.method);
+                }
+            }
+        }
+
+        if allowed.is_empty() {
+            return JsonApiResponse::error(&RequestError::NotFound, 404).into();
+        }
+
+        method_not_allowed(&allowed).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn routes() -> Routes<()> {
+        let mut routes = Routes::default();
+        routes.add("GET", "/foos/:id", |req, _| {
+            JsonApiResponse::new(200).with_header("id", req.param("id").unwrap())
+        });
+        routes.add("GET", "/foos/:id/relationships/:relationship", |req, _| {
+            let relationship = req.param("relationship").unwrap();
+            JsonApiResponse::new(200).with_header("relationship", relationship)
+        });
+        routes
+    }
+
+    #[test]
+    fn dispatch_with_params() {
+        let response = routes().dispatch(JsonApiRequest::new("GET", "/foos/1"), &());
+        assert_eq!(200, response.status);
+        assert_eq!(Some("1"), response.header("id"));
+
+        let request = JsonApiRequest::new("GET", "/foos/1/relationships/bar");
+        let response = routes().dispatch(request, &());
+        assert_eq!(Some("bar"), response.header("relationship"));
+    }
+
+    #[test]
+    fn dispatch_without_matching_route() {
+        let routes = routes();
+        assert_eq!(404, routes.dispatch(JsonApiRequest::new("GET", "/foos"), &()).status);
+        assert_eq!(404, routes.dispatch(JsonApiRequest::new("GET", "/foos//"), &()).status);
+        assert_eq!(404, routes.dispatch(JsonApiRequest::new("GET", "/bars/1"), &()).status);
+    }
+
+    #[test]
+    fn dispatch_with_method_not_allowed() {
+        let mut routes = routes();
+        routes.add("DELETE", "/foos/:id", |_, _| JsonApiResponse::new(204));
+
+        let response = routes.dispatch(JsonApiRequest::new("POST", "/foos/1"), &());
+        assert_eq!(405, response.status);
+        assert_eq!(Some("GET, DELETE"), response.header("Allow"));
+    }
+
+    #[test]
+    fn dispatch_with_percent_encoded_params() {
+        let request = JsonApiRequest::new("GET", "/foos/a%20b%2Fc");
+        let response = routes().dispatch(request, &());
+        assert_eq!(Some("a b/c"), response.header("id"));
+    }
+}
@@ -1,6 +1,12 @@
+use container::JsonApiContainer;
 use data::JsonApiData;
+use error::JsonApiErrorDetails;
+use errors::ConversionError;
+use page::JsonApiPage;
+use relationship::JsonApiLinkage;
+use relationship::JsonApiResourceIdentifier;
+use related::JsonApiRelated;
 use resource::JsonApiResource;
-use std;
 use to_json::ToJson;
 use std::convert::TryFrom;
 use std::convert::TryInto;
@@ -8,10 +14,40 @@ use std::convert::TryInto;
 /// A trait containing framework-specific types.
 ///
 /// This is currently used to set the `Status` type for `JsonGet`, `JsonPost`, `JsonIndex`,
-/// `JsonPatch` and `JsonDelete`. There is a blanket impl in `iron/mod.rs` for all `T` implementing
-/// `JsonApiResource`, which sets the `Status` to the Hyper `Status` type.
+/// `JsonPatch` and `JsonDelete`. `#[derive(JsonApi)]` implements it for the resource, with the
+/// status type given by `#[jsonapi(status = "...")]` on the struct, or `DefaultStatus` if there is
+/// no such attribute.
 pub trait Handler {
-    type Status: Send;
+    type Status: HttpStatus + Send;
+}
+
+/// The `Handler::Status` of a resource without a `#[jsonapi(status = "...")]` attribute. This is
+/// a plain `u16` regardless of the enabled adapters, since every adapter converts the status with
+/// `HttpStatus`.
+pub type DefaultStatus = u16;
+
+/// A framework-specific status type, which can be converted to and from a numeric HTTP status
+/// code. This is used by the dispatchers (such as `GetDispatcher`) to render the errors of the
+/// service traits, regardless of the framework that is used.
+pub trait HttpStatus {
+    /// The numeric status code, e.g `404`.
+    fn status_code(&self) -> u16;
+
+    /// The status with the numeric status code `code`, or `500 Internal Server Error` if the
+    /// framework doesn't support `code`.
+    fn from_status_code(code: u16) -> Self
+    where
+        Self: Sized;
+}
+
+impl HttpStatus for u16 {
+    fn status_code(&self) -> u16 {
+        *self
+    }
+
+    fn from_status_code(code: u16) -> Self {
+        code
+    }
 }
 
 /// A trait for implementing GET `/{resource-name}/{id}` on a resource type.
@@ -32,6 +68,7 @@ pub trait Handler {
 /// use rustiful::JsonGet;
 /// use rustiful::ToJson;
 /// use rustiful::IntoJson;
+/// use rustiful::JsonApiContainer;
 /// use rustiful::JsonApiData;
 /// use rustiful::JsonApiParams;
 ///
@@ -57,6 +94,8 @@ pub trait Handler {
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # struct MyCtx {
 /// # }
 /// #
@@ -67,7 +106,7 @@ pub trait Handler {
 ///     fn find(id: Self::JsonApiIdType,
 ///         params: &Self::Params,
 ///         ctx: Self::Context)
-///         -> Result<Option<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+///         -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
 ///         let resource = MyResource {
 ///             id: "magic_id".to_string(),
 ///             foo: true,
@@ -75,7 +114,7 @@ pub trait Handler {
 ///         };
 ///
 ///         if id == resource.id {
-///             Ok(Some(resource.into_json(params)))
+///             Ok(Some(resource.into_json(params).into()))
 ///         } else {
 ///             Ok(None)
 ///         }
@@ -87,7 +126,7 @@ pub trait Handler {
 ///     let attrs = <<MyResource as ToJson>::Attrs>::new(Some(true), Some("hello".to_string()));
 ///     let resource = MyResource::find(id.clone(), &Default::default(), MyCtx {});
 ///     let expected = JsonApiData::new(Some(id), attrs);
-///     assert_eq!(expected, resource.unwrap().unwrap());
+///     assert_eq!(expected, resource.unwrap().unwrap().data);
 ///
 ///     assert_eq!(Ok(None), MyResource::find("foo".to_string(), &Default::default(), MyCtx {}));
 /// }
@@ -96,8 +135,8 @@ pub trait JsonGet: Handler
 where
     Self: JsonApiResource + ToJson
 {
-    /// A user-defined error type
-    type Error: std::error::Error + Send;
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
     /// A user-defined type
     type Context;
 
@@ -109,15 +148,19 @@ where
     /// `Self::JsonApiIdType` will be `Uuid`. The only requirement is that `Self::JsonApiIdType` is
     /// convertible to a String (i.e the type implements `FromStr`).
     /// * `params` - A type-safe reference of the params
-    /// passed in the request. `Self::FilterField` and `Self::SortField` are automatically
+    /// passed in the request. `Self::FieldSetField` and `Self::SortField` are automatically
     /// implemented when `JsonApi` is derived. See `JsonApiParams` for more info.
     /// * `ctx` - A user defined context type. This is used to instantiate the given
     /// type on each request. This type can be used for whatever you like, such as an auth token
+    ///
+    /// The resource is returned in a document, so that any related resources that were requested
+    /// with the `include` query parameter can be side-loaded with `JsonApiContainer::include`. A
+    /// `JsonApiData` is converted to a document with `into()`.
     fn find(
         id: Self::JsonApiIdType,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Option<JsonApiData<Self>>, (Self::Error, Self::Status)>;
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)>;
 }
 
 /// A trait for implementing POST `/{resource-name}` on a resource type.
@@ -136,12 +179,13 @@ where
 /// use std::error::Error;
 /// use std::fmt::Display;
 /// use std::convert::TryInto;
+/// use rustiful::ConversionError;
 /// use rustiful::JsonPost;
 /// use rustiful::ToJson;
 /// use rustiful::IntoJson;
+/// use rustiful::JsonApiContainer;
 /// use rustiful::JsonApiData;
 /// use rustiful::JsonApiParams;
-/// use rustiful::iron::status::Status;
 ///
 /// #[derive(Debug, PartialEq, Eq, JsonApi, Default)]
 /// struct MyResource {
@@ -165,6 +209,8 @@ where
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # struct MyCtx {
 /// # }
 /// #
@@ -175,13 +221,15 @@ where
 ///     fn create(json: JsonApiData<Self>,
 ///               params: &Self::Params,
 ///               ctx: Self::Context)
-///             -> Result<JsonApiData<Self>, (Self::Error, Status)> {
+///         -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)> {
 ///         if let Some(_) = json.id {
-///             Err((MyError("invalid id!".to_string()), Status::BadRequest))
+///             Err((MyError("invalid id!".to_string()), 400))
 ///         } else {
-///             let mut resource: Self = json.try_into().map_err(|e| (MyError(e), Status::BadRequest))?;
+///             let mut resource: Self = json
+///                 .try_into()
+///                 .map_err(|e: ConversionError| (MyError(e.to_string()), 400))?;
 ///             resource.id = "created!".to_string();
-///             Ok(resource.into_json(params))
+///             Ok(Some(resource.into_json(params).into()))
 ///         }
 ///     }
 /// }
@@ -191,7 +239,7 @@ where
 ///     let attrs = <<MyResource as ToJson>::Attrs>::new(Some(true), Some("hello".to_string()));
 ///     let mut resource = JsonApiData::new(Some(id), attrs);
 ///
-///     let err = Err((MyError("invalid id!".to_string()), Status::BadRequest));
+///     let err = Err((MyError("invalid id!".to_string()), 400));
 ///     assert_eq!(err, MyResource::create(resource.clone(), &Default::default(), MyCtx {}));
 ///
 ///     let mut expected = resource.clone();
@@ -199,15 +247,16 @@ where
 ///
 ///     resource.id = None;
 ///
-///     assert_eq!(Ok(expected), MyResource::create(resource, &Default::default(), MyCtx {}));
+///     let created = MyResource::create(resource, &Default::default(), MyCtx {});
+///     assert_eq!(expected, created.unwrap().unwrap().data);
 /// }
 /// ```
 pub trait JsonPost: Handler
 where
     Self: JsonApiResource + ToJson
 {
-    /// A user-defined error type
-    type Error: std::error::Error + Send;
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
 
     /// A user-defined type
     type Context;
@@ -215,23 +264,34 @@ where
     /// Creates a resource.
     ///
     /// * `json` - The JSON document to create your record with. Convert it to
-    /// `Result<Self, String>` using `json::try_into()` (a `TryFrom<JsonApiData<Self>>`
-    /// impl is automatically generated when `JsonApi` is derived).
+    /// `Result<Self, ConversionError>` using `json::try_into()` (a `TryFrom<JsonApiData<Self>>`
+    /// impl is automatically generated when `JsonApi` is derived, which also runs the
+    /// validations declared with `#[jsonapi(validate(...))]`).
     /// It is also possible to use a client-generated id when creating a new resource; the id will
     /// be present in `json.id` if the client decides to generate an id. If the client doesn't
     /// generate an id `json.id` will be `None`, and it will be up to the implementation to generate
     /// a suitable id. If client-generated ids are not supported, an Error should be returned.
     /// * `params` - A type-safe reference of the params
-    /// passed in the request. `Self::FilterField` and `Self::SortField` are automatically
+    /// passed in the request. `Self::FieldSetField` and `Self::SortField` are automatically
     /// implemented when `JsonApi` is derived. See `JsonApiParams` for more info.
     /// * `ctx` - A user defined context type. This is used to instantiate the given
     /// type on each request. This type can be used for whatever you like, such as an auth token
     /// or a database connection.
+    ///
+    /// Return the created resource, which is sent with a `201 Created` status and a `Location`
+    /// header pointing at the new resource. Return `Ok(None)` if the resource had a
+    /// client-generated id and was created exactly as sent, in which case `204 No Content` is
+    /// sent instead. Returning `Ok(None)` for a resource without a client-generated id is a
+    /// `500 Internal Server Error`, since the client wouldn't learn the id of the resource.
+    ///
+    /// The resource is returned in a document like in `JsonGet::find`, so that top-level meta
+    /// information can be added with `JsonApiContainer::with_meta`. A `JsonApiData` is converted
+    /// to a document with `into()`.
     fn create(
         json: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)>;
+    ) -> Result<Option<JsonApiContainer<JsonApiData<Self>>>, (Self::Error, Self::Status)>;
 }
 
 /// A trait for implementing PATCH `/{resource-name}/{id}` on a resource type.
@@ -252,9 +312,9 @@ where
 /// use rustiful::JsonPatch;
 /// use rustiful::ToJson;
 /// use rustiful::IntoJson;
+/// use rustiful::JsonApiContainer;
 /// use rustiful::JsonApiData;
 /// use rustiful::JsonApiParams;
-/// use rustiful::iron::status::Status;
 ///
 /// #[derive(Debug, PartialEq, Eq, JsonApi, Default)]
 /// struct MyResource {
@@ -278,6 +338,8 @@ where
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # struct MyCtx {
 /// # }
 /// #
@@ -289,7 +351,7 @@ where
 ///               json: JsonApiData<Self>,
 ///               params: &Self::Params,
 ///               ctx: Self::Context)
-///               -> Result<JsonApiData<Self>, (Self::Error, Status)> {
+///               -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Self::Status)> {
 ///         let mut resource = MyResource {
 ///             id: "magic_id".to_string(),
 ///             foo: true,
@@ -300,10 +362,12 @@ where
 ///             // The `patch` method will only overwrite fields that are explicitly sent in the
 ///             // JSON patch, i.e if the field has a value or is explicitly set to `null`. Fields
 ///             // that are omitted will not be updated.
-///             let updated: MyResource = resource.patch(json).map_err(|e| (MyError(e), Status::BadRequest))?;
-///             Ok(updated.into_json(params))
+///             let updated: MyResource = resource
+///                 .patch(json)
+///                 .map_err(|e| (MyError(e.to_string()), 400))?;
+///             Ok(updated.into_json(params).into())
 ///         } else {
-///             Err((MyError("Cannot patch resource!".to_string()), Status::BadRequest))
+///             Err((MyError("Cannot patch resource!".to_string()), 400))
 ///         }
 ///     }
 /// }
@@ -320,12 +384,12 @@ where
 ///     };
 ///
 ///     let result = MyResource::update(id.clone(), json.clone(), &Default::default(), MyCtx {});
-///     assert_eq!(expected.into_json(&Default::default()), result.unwrap());
+///     assert_eq!(expected.into_json(&Default::default()), result.unwrap().data);
 ///
 ///
 ///     let id = "some_other_id".to_string();
 ///     let result = MyResource::update(id, json, &Default::default(), MyCtx {});
-///     let err = Err((MyError("Cannot patch resource!".to_string()), Status::BadRequest));
+///     let err = Err((MyError("Cannot patch resource!".to_string()), 400));
 ///     assert_eq!(err, result);
 /// }
 /// ```
@@ -333,8 +397,8 @@ pub trait JsonPatch: Handler
 where
     Self: JsonApiResource + ToJson
 {
-    /// A user-defined error type
-    type Error: std::error::Error + Send;
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
 
     /// A user-defined type
     type Context;
@@ -357,9 +421,9 @@ where
     /// # use rustiful::JsonPatch;
     /// # use rustiful::ToJson;
     /// # use rustiful::IntoJson;
+    /// # use rustiful::JsonApiContainer;
     /// # use rustiful::JsonApiData;
     /// # use rustiful::JsonApiParams;
-    /// # use rustiful::iron::status::Status;
     ///
     /// #[derive(Debug, PartialEq, Eq, JsonApi, Default, Clone)]
     /// struct MyResource {
@@ -383,6 +447,8 @@ where
     /// #    }
     /// # }
     /// #
+    /// # impl rustiful::JsonApiErrorDetails for MyError {}
+    /// #
     /// # struct MyCtx {
     /// # }
     /// #
@@ -394,8 +460,8 @@ where
     /// #              json: JsonApiData<Self>,
     /// #              params: &Self::Params,
     /// #              ctx: Self::Context)
-    /// #              -> Result<JsonApiData<Self>, (Self::Error, Status)> {
-    /// #        Err((MyError("This is just here to demonstrate patch".to_string()), Status::BadRequest))
+    /// #              -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+    /// #        Err((MyError("This is just here to demonstrate patch".to_string()), 400))
     /// #    }
     /// }
     ///
@@ -429,9 +495,9 @@ where
     /// }
     ///
     /// ```
-    fn patch(self, json: JsonApiData<Self>) -> Result<Self, String>
+    fn patch(self, json: JsonApiData<Self>) -> Result<Self, ConversionError>
     where
-        Self: TryFrom<(Self, JsonApiData<Self>), Error = String>
+        Self: TryFrom<(Self, JsonApiData<Self>), Error = ConversionError>
     {
         (self, json).try_into()
     }
@@ -445,17 +511,19 @@ where
     /// convertible to a String (i.e the type implements `FromStr`).
     /// * `json` - The JSON patch with the attributes to update.
     /// * `params` - A type-safe reference of the params
-    /// passed in the request. `Self::FilterField` and `Self::SortField` are automatically
+    /// passed in the request. `Self::FieldSetField` and `Self::SortField` are automatically
     /// implemented when `JsonApi` is derived. See `JsonApiParams` for more info.
     /// * `ctx` - A user defined context type. This is used to instantiate the given
     /// type on each request. This type can be used for whatever you like, such as an auth token
     /// or a database connection.
+    ///
+    /// The updated resource is returned in a document, see `JsonPost::create`.
     fn update(
         id: Self::JsonApiIdType,
         json: JsonApiData<Self>,
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<JsonApiData<Self>, (Self::Error, Self::Status)>;
+    ) -> Result<JsonApiContainer<JsonApiData<Self>>, (Self::Error, Self::Status)>;
 }
 
 /// A trait for implementing GET `/{resource-name}` on a resource type.
@@ -477,6 +545,7 @@ where
 /// use rustiful::ToJson;
 /// use rustiful::IntoJson;
 /// use rustiful::JsonApiData;
+/// use rustiful::JsonApiPage;
 /// use rustiful::JsonApiParams;
 ///
 /// #[derive(Debug, PartialEq, Eq, JsonApi, Default)]
@@ -501,56 +570,73 @@ where
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # struct MyCtx {
 /// # }
 /// #
 /// impl JsonIndex for MyResource {
 ///     type Error = MyError;
 ///     type Context = MyCtx;
+///     const DEFAULT_PAGE_SIZE: Option<u64> = Some(20);
 ///
 ///     fn find_all(params: &Self::Params,
 ///                 ctx: Self::Context)
-///                 -> Result<Vec<JsonApiData<Self>>, (Self::Error, Self::Status)> {
+///                 -> Result<JsonApiPage<Self>, (Self::Error, Self::Status)> {
 ///         let resource = MyResource {
 ///             id: "magic_id".to_string(),
 ///             foo: true,
 ///             bar: "hello".to_string()
 ///         };
 ///
-///         Ok(vec![resource].into_json(params))
+///         Ok(JsonApiPage::new(vec![resource].into_json(params)).with_total(1))
 ///     }
 /// }
 ///
 /// fn main() {
 ///     let id = "magic_id".to_string();
 ///     let attrs = <<MyResource as ToJson>::Attrs>::new(Some(true), Some("hello".to_string()));
-///     let resource = MyResource::find_all(&Default::default(), MyCtx {});
+///     let resource = MyResource::find_all(&Default::default(), MyCtx {}).unwrap();
 ///     let expected = JsonApiData::new(Some(id), attrs);
-///     assert_eq!(vec![expected], resource.unwrap());
+///     assert_eq!(vec![expected], resource.data);
+///     assert_eq!(Some(1), resource.total);
 /// }
 /// ```
 pub trait JsonIndex: Handler
 where
     Self: JsonApiResource + ToJson
 {
-    /// A user-defined error type
-    type Error: std::error::Error + Send;
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
 
     /// A user-defined type
     type Context;
 
-    /// Gets a collection of resources.
+    /// The page size used when the client doesn't specify one. If both this and `MAX_PAGE_SIZE`
+    /// are `None`, then collections are unbounded unless the client requests a page.
+    const DEFAULT_PAGE_SIZE: Option<u64> = None;
+
+    /// The largest page size that a client can request. Larger page sizes are reduced to this
+    /// size.
+    const MAX_PAGE_SIZE: Option<u64> = None;
+
+    /// Gets a page of resources.
     ///
     /// * `params` - A type-safe reference of the params
-    /// passed in the request. `Self::FilterField` and `Self::SortField` are automatically
-    /// implemented when `JsonApi` is derived. See `JsonApiParams`
+    /// passed in the request. `Self::FieldSetField` and `Self::SortField` are automatically
+    /// implemented when `JsonApi` is derived. See `JsonApiParams`. The requested page is in
+    /// `params.page`, with `DEFAULT_PAGE_SIZE` and `MAX_PAGE_SIZE` already applied.
     /// * `ctx` - A user defined context type. This is used to instantiate the given
     /// type on each request. This type can be used for whatever you like, such as an auth token
     /// or a database connection.
+    ///
+    /// A `Vec<JsonApiData<Self>>` can be converted to a `JsonApiPage<Self>` with `into()`. Set
+    /// the total number of resources with `JsonApiPage::with_total` to include `meta.total` and a
+    /// `last` link in the response.
     fn find_all(
         params: &Self::Params,
         ctx: Self::Context
-    ) -> Result<Vec<JsonApiData<Self>>, (Self::Error, Self::Status)>;
+    ) -> Result<JsonApiPage<Self>, (Self::Error, Self::Status)>;
 }
 
 /// A trait for implementing DELETE `/{resource-name}/{id}` on a resource type.
@@ -573,7 +659,6 @@ where
 /// use rustiful::IntoJson;
 /// use rustiful::JsonApiData;
 /// use rustiful::JsonApiParams;
-/// use rustiful::iron::status::Status;
 ///
 /// #[derive(Debug, PartialEq, Eq, JsonApi, Default)]
 /// struct MyResource {
@@ -597,6 +682,8 @@ where
 /// #    }
 /// # }
 /// #
+/// # impl rustiful::JsonApiErrorDetails for MyError {}
+/// #
 /// # struct MyCtx {
 /// # }
 /// #
@@ -604,7 +691,9 @@ where
 ///     type Error = MyError;
 ///     type Context = MyCtx;
 ///
-///     fn delete(id: Self::JsonApiIdType, ctx: Self::Context) -> Result<(), (Self::Error, Status)> {
+///     fn delete(id: Self::JsonApiIdType,
+///               ctx: Self::Context)
+///               -> Result<(), (Self::Error, Self::Status)> {
 ///         let resource = MyResource {
 ///             id: "magic_id".to_string(),
 ///             foo: true,
@@ -614,14 +703,14 @@ where
 ///         if id == resource.id {
 ///             Ok(())
 ///         } else {
-///             Err((MyError("Invalid id!".to_string()), Status::BadRequest))
+///             Err((MyError("Invalid id!".to_string()), 400))
 ///         }
 ///     }
 /// }
 ///
 /// fn main() {
 ///     let id = "magic_id".to_string();
-///     let err = Err((MyError("Invalid id!".to_string()), Status::BadRequest));
+///     let err = Err((MyError("Invalid id!".to_string()), 400));
 ///     assert_eq!(Ok(()), MyResource::delete("magic_id".to_string(), MyCtx {}));
 ///     assert_eq!(err, MyResource::delete("other_id".to_string(), MyCtx {}));
 /// }
@@ -630,8 +719,8 @@ pub trait JsonDelete: Handler
 where
     Self: JsonApiResource
 {
-    /// A user-defined error type
-    type Error: std::error::Error + Send;
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
 
     /// A user-defined type
     type Context;
@@ -651,3 +740,175 @@ where
         ctx: Self::Context
     ) -> Result<(), (Self::Error, Self::Status)>;
 }
+
+/// A trait for implementing GET `/{resource-name}/{id}/relationships/{relationship}` on a resource
+/// type, i.e fetching the resource linkage of a relationship.
+///
+/// `relationship` is one of the variants of the `relationship` enum that is generated when
+/// deriving `JsonApi`, with one variant per field with the `JsonApiRelationship` attribute.
+/// Requests for relationships that don't exist are answered with a 404 before this is called.
+pub trait JsonGetRelationship: Handler
+where
+    Self: JsonApiResource
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
+
+    /// A user-defined type
+    type Context;
+
+    /// Finds the resource linkage of `relationship` on the resource with the id `id`. Returns
+    /// `Ok(None)` if the resource cannot be found.
+    ///
+    /// * `id` - The id of the resource. See `JsonGet::find`.
+    /// * `relationship` - The relationship to fetch.
+    /// * `ctx` - A user defined context type. See `JsonGet::find`.
+    ///
+    /// A `ToOneRelationship` or a `ToManyRelationship` can be converted to a `JsonApiLinkage` with
+    /// `into()`.
+    fn find_relationship(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        ctx: Self::Context
+    ) -> Result<Option<JsonApiLinkage>, (Self::Error, Self::Status)>;
+}
+
+/// A trait for implementing PATCH `/{resource-name}/{id}/relationships/{relationship}` on a
+/// resource type, i.e replacing the resource linkage of a relationship.
+///
+/// Before this is called, rustiful checks that the linkage in the request body matches the
+/// cardinality of the relationship (an array for a to-many relationship, an object or `null`
+/// for a to-one relationship) and that all resource identifiers have the type of the related
+/// resource.
+pub trait JsonPatchRelationship: Handler
+where
+    Self: JsonApiResource
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
+
+    /// A user-defined type
+    type Context;
+
+    /// Replaces the resource linkage of `relationship` on the resource with the id `id`, and
+    /// returns the updated linkage.
+    ///
+    /// * `id` - The id of the resource. See `JsonGet::find`.
+    /// * `relationship` - The relationship to update.
+    /// * `linkage` - The new linkage of the relationship. Use `JsonApiLinkage::parse_ids` to
+    /// convert it to the ids of the related resource.
+    /// * `ctx` - A user defined context type. See `JsonGet::find`.
+    fn update_relationship(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        linkage: JsonApiLinkage,
+        ctx: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)>;
+}
+
+/// A trait for implementing POST `/{resource-name}/{id}/relationships/{relationship}` on a
+/// resource type, i.e adding members to a to-many relationship.
+///
+/// Requests for to-one relationships are answered with a 403 before this is called. The resource
+/// identifiers are checked in the same way as for `JsonPatchRelationship`.
+pub trait JsonPostRelationship: Handler
+where
+    Self: JsonApiResource
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
+
+    /// A user-defined type
+    type Context;
+
+    /// Adds the resources in `members` to `relationship` on the resource with the id `id`, and
+    /// returns the updated linkage. Members that are already part of the relationship should not
+    /// be added again.
+    ///
+    /// * `id` - The id of the resource. See `JsonGet::find`.
+    /// * `relationship` - The to-many relationship to update.
+    /// * `members` - The resources to add to the relationship.
+    /// * `ctx` - A user defined context type. See `JsonGet::find`.
+    fn add_to_relationship(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        members: Vec<JsonApiResourceIdentifier>,
+        ctx: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)>;
+}
+
+/// A trait for implementing DELETE `/{resource-name}/{id}/relationships/{relationship}` on a
+/// resource type, i.e removing members from a to-many relationship.
+///
+/// Requests for to-one relationships are answered with a 403 before this is called. The resource
+/// identifiers are checked in the same way as for `JsonPatchRelationship`.
+pub trait JsonDeleteRelationship: Handler
+where
+    Self: JsonApiResource
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
+
+    /// A user-defined type
+    type Context;
+
+    /// Removes the resources in `members` from `relationship` on the resource with the id `id`,
+    /// and returns the updated linkage. Members that aren't part of the relationship should be
+    /// ignored.
+    ///
+    /// * `id` - The id of the resource. See `JsonGet::find`.
+    /// * `relationship` - The to-many relationship to update.
+    /// * `members` - The resources to remove from the relationship.
+    /// * `ctx` - A user defined context type. See `JsonGet::find`.
+    fn remove_from_relationship(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        members: Vec<JsonApiResourceIdentifier>,
+        ctx: Self::Context
+    ) -> Result<JsonApiLinkage, (Self::Error, Self::Status)>;
+}
+
+/// A trait for implementing GET `/{resource-name}/{id}/{relationship}` on a resource type, i.e
+/// fetching the related resources of a relationship as a document of the related resource type
+/// `R`.
+///
+/// Implement this once for each type of related resource, e.g `JsonGetRelated<Person>` for the
+/// `author` and `editor` relationships of an article and `JsonGetRelated<Comment>` for its
+/// `comments`. The `fields`, `sort`, `include`, `filter` and `page` query parameters are parsed
+/// as the parameters of `R`.
+pub trait JsonGetRelated<R>: Handler
+where
+    Self: JsonApiResource,
+    R: JsonApiResource + ToJson
+{
+    /// A user-defined error type, see `JsonApiErrorDetails`
+    type Error: JsonApiErrorDetails + Send;
+
+    /// A user-defined type
+    type Context;
+
+    /// The page size of a to-many relationship when the client doesn't specify one. See
+    /// `JsonIndex::DEFAULT_PAGE_SIZE`.
+    const DEFAULT_PAGE_SIZE: Option<u64> = None;
+
+    /// The maximum page size of a to-many relationship. See `JsonIndex::MAX_PAGE_SIZE`.
+    const MAX_PAGE_SIZE: Option<u64> = None;
+
+    /// Finds the resources that are related to the resource with the id `id` through
+    /// `relationship`. Returns `Ok(None)` if the resource cannot be found.
+    ///
+    /// * `id` - The id of the resource. See `JsonGet::find`.
+    /// * `relationship` - The relationship to follow. This is always a relationship to `R`.
+    /// * `params` - The query parameters of the related resource type.
+    /// * `ctx` - A user defined context type. See `JsonGet::find`.
+    ///
+    /// A `JsonApiData<R>`, a `JsonApiContainer<JsonApiData<R>>` with included resources, or an
+    /// `Option` of either can be converted to a to-one `JsonApiRelated<R>` with `into()`, and a
+    /// `Vec<JsonApiData<R>>` or a `JsonApiPage<R>` to a to-many `JsonApiRelated<R>`.
+    fn find_related(
+        id: Self::JsonApiIdType,
+        relationship: Self::RelationshipField,
+        params: &R::Params,
+        ctx: Self::Context
+    ) -> Result<Option<JsonApiRelated<R>>, (Self::Error, Self::Status)>;
+}
@@ -4,7 +4,13 @@ use serde::ser::Serialize;
 pub trait ToJson {
     type Attrs: Clone + Serialize + DeserializeOwned;
 
+    type Relationships: Clone + Serialize + DeserializeOwned;
+
     fn id(&self) -> String;
 
     const TYPE_NAME: &'static str;
+
+    /// The names of all fields that have the `JsonApiRelationship` attribute, as they appear in the
+    /// `relationships` object.
+    const RELATIONSHIP_NAMES: &'static [&'static str];
 }
@@ -0,0 +1,63 @@
+extern crate http;
+
+use self::http::StatusCode;
+use self::http::request::Parts;
+use std;
+
+/// A trait used to initialize a type from the head of a request, like the `FromRequestParts`
+/// extractor of axum. The body has already been read by `JsonApiService` at this point.
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// # extern crate rustiful;
+/// #
+/// # use std::error::Error;
+/// # use std::fmt::Display;
+/// # use std::fmt::Formatter;
+/// # use http::StatusCode;
+/// # use http::request::Parts;
+/// # use rustiful::tower::FromRequest;
+/// #
+/// struct Foo {
+///     magic_header: String
+/// }
+///
+/// #[derive(Debug)]
+/// struct FooError(String);
+/// #
+/// # impl Error for FooError {
+/// #     fn description(&self) -> &str {
+/// #        &self.0
+/// #   }
+/// #
+/// #   fn cause(&self) -> Option<&Error> {
+/// #       None
+/// #   }
+/// # }
+/// #
+/// # impl Display for FooError {
+/// #     fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+/// #        self.0.fmt(f)
+/// #    }
+/// # }
+///
+/// impl FromRequest for Foo {
+///     type Error = FooError;
+///
+///     fn from_request(parts: &Parts) -> Result<Self, (Self::Error, StatusCode)> {
+///         match parts.headers.get("my-magic-header").and_then(|h| h.to_str().ok()) {
+///             Some(value) => Ok(Foo { magic_header: value.to_string() }),
+///             None => Err((FooError("header not present!".to_string()), StatusCode::BAD_REQUEST))
+///         }
+///     }
+/// }
+/// #
+/// # fn main() {}
+/// ```
+pub trait FromRequest: Sized {
+    type Error: std::error::Error + Send;
+
+    fn from_request(parts: &Parts) -> Result<Self, (Self::Error, StatusCode)>;
+}
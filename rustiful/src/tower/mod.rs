@@ -0,0 +1,77 @@
+//! A [tower](https://github.com/tower-rs/tower) adapter, which can be mounted in an axum `Router`.
+//!
+//! `JsonApiServiceBuilder` registers the endpoints of resources in the same way as
+//! `JsonApiRouterBuilder`, and builds a `JsonApiService` which implements
+//! `tower_service::Service` for `http` 0.2 requests with any `http_body::Body`.
+
+mod from_request;
+pub use self::from_request::*;
+
+mod service;
+pub use self::service::*;
+
+extern crate http;
+
+use self::http::Response;
+use self::http::StatusCode;
+use self::http::request::Parts;
+use dispatch::JsonApiResponse;
+use routes::RequestHead;
+use service::HttpStatus;
+
+impl HttpStatus for StatusCode {
+    fn status_code(&self) -> u16 {
+        self.as_u16()
+    }
+
+    fn from_status_code(code: u16) -> Self {
+        StatusCode::from_u16(code).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR)
+    }
+}
+
+/// The head of an `http` 0.2 request, which is split off from its body before the body is
+/// collected.
+impl RequestHead for Parts {
+    fn method(&self) -> &str {
+        self.method.as_str()
+    }
+
+    fn path(&self) -> &str {
+        self.uri.path()
+    }
+
+    fn query(&self) -> Option<&str> {
+        self.uri.query()
+    }
+
+    fn origin(&self) -> Option<String> {
+        match (self.uri.scheme(), self.uri.authority()) {
+            (Some(scheme), Some(authority)) => Some(format!("{}://{}", scheme, authority)),
+            _ => None
+        }
+    }
+
+    fn headers(&self) -> Vec<(&str, &str)> {
+        self.headers
+            .iter()
+            .filter_map(|(name, value)| value.to_str().ok().map(|value| (name.as_str(), value)))
+            .collect()
+    }
+}
+
+/// Converts a `JsonApiResponse` to a response with a `String` body, which axum and hyper can
+/// send as is.
+fn tower_response(response: JsonApiResponse) -> Response<String> {
+    let status = StatusCode::from_u16(response.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut builder = Response::builder().status(status);
+
+    for &(ref name, ref value) in &response.headers {
+        builder = builder.header(name.as_str(), value.as_str());
+    }
+
+    builder.body(response.body.unwrap_or_default()).unwrap_or_else(|_| {
+        let mut result = Response::new(String::new());
+        *result.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+        result
+    })
+}
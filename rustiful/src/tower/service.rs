@@ -0,0 +1,502 @@
+extern crate bytes;
+extern crate http;
+extern crate http_body;
+extern crate tower_service;
+
+use self::bytes::Buf;
+use self::http::Method;
+use self::http::Request;
+use self::http::Response;
+use self::http::request::Parts;
+use self::http_body::Body;
+use self::tower_service::Service;
+use super::from_request::FromRequest;
+use super::tower_response;
+use dispatch::*;
+use errors::QueryStringParseError;
+use errors::RequestError;
+use operations::ATOMIC_EXTENSION;
+use operations::JsonApiTransaction;
+use params::JsonApiFieldSetField;
+use params::SortOrder;
+use relationship::JsonApiRelationshipField;
+use resource::JsonApiResource;
+use routes::Routes;
+use routes::base_url;
+use routes::json_api_request;
+use service::JsonGetRelated;
+use std::convert::Infallible;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::task::Context;
+use std::task::Poll;
+use to_json::ToJson;
+
+/// Constructs a tower `Service` for resources implementing any of the `JsonGet`, `JsonPost`,
+/// `JsonIndex`, `JsonPatch` or `JsonDelete` traits, or their async variants such as
+/// `AsyncJsonGet`.
+///
+/// This has the same registration methods as `JsonApiRouterBuilder`, and registers the same
+/// routes. The context of the service traits is created with `rustiful::tower::FromRequest`
+/// instead of `rustiful::iron::FromRequest`.
+///
+/// # Example
+///
+/// The service can be mounted in an axum `Router`. Since `nest_service` strips the prefix from
+/// the path, the base URL should include it.
+///
+/// ```rust,ignore
+/// use axum::Router;
+/// use rustiful::tower::JsonApiServiceBuilder;
+///
+/// let mut builder = JsonApiServiceBuilder::default();
+/// builder.jsonapi_get::<MyResource>();
+/// builder.jsonapi_index::<MyResource>();
+/// builder.set_base_url("https://example.com/api");
+///
+/// let app = Router::new().nest_service("/api", builder.build());
+/// ```
+#[allow(missing_debug_implementations)] // The dispatch closures don't implement Debug...
+pub struct JsonApiServiceBuilder {
+    routes: Routes<Parts, JsonApiResponseFuture>,
+    max_body_length: usize,
+    base_url: Option<String>,
+    negotiation: ContentNegotiation
+}
+
+/// This `Default` implementation sets the maximum body length to 10MB.
+impl Default for JsonApiServiceBuilder {
+    fn default() -> Self {
+        Self::new(10 * 1024 * 1024)
+    }
+}
+
+impl JsonApiServiceBuilder {
+    /// Constructs a new `JsonApiServiceBuilder` with a maximum body length in bytes.
+    pub fn new(max_body_length: usize) -> Self {
+        JsonApiServiceBuilder {
+            routes: Routes::default(),
+            max_body_length: max_body_length,
+            base_url: None,
+            negotiation: ContentNegotiation::new()
+        }
+    }
+
+    /// Sets the max body length for any incoming JSON document. This is specified in bytes.
+    /// Requests with a larger body get a `413 Payload Too Large` response.
+    pub fn set_max_body_length(&mut self, max_body_length: usize) {
+        self.max_body_length = max_body_length;
+    }
+
+    /// Sets the base URL of the `self` links in any responses, such as `https://example.com/api`.
+    ///
+    /// If no base URL is set, the scheme, host and port of each request are used instead. Set
+    /// this when the server is behind a proxy, or when the service is nested under a prefix.
+    pub fn set_base_url<S: Into<String>>(&mut self, base_url: S) {
+        self.base_url = Some(base_url.into());
+    }
+
+    /// Declares support for the JSONAPI extension with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_extension`.
+    pub fn add_extension<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_extension(uri);
+    }
+
+    /// Declares support for the JSONAPI profile with the URI `uri`, see
+    /// `JsonApiRouterBuilder::add_profile`.
+    pub fn add_profile<S: Into<String>>(&mut self, uri: S) {
+        self.negotiation.add_profile(uri);
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `JsonIndex`.
+    pub fn jsonapi_index<T>(&mut self)
+    where
+        T: 'static,
+        T: IndexDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::GET, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `JsonGet`.
+    pub fn jsonapi_get<T>(&mut self)
+    where
+        T: 'static,
+        T: GetDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::GET, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `JsonDelete`.
+    pub fn jsonapi_delete<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::DELETE, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `JsonPost`.
+    pub fn jsonapi_post<T>(&mut self)
+    where
+        T: 'static,
+        T: PostDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route(Method::POST, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `JsonPatch`.
+    pub fn jsonapi_patch<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchDispatcher,
+        T::Context: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route(Method::PATCH, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonGetRelationship`.
+    pub fn jsonapi_get_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: GetRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::GET, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPatchRelationship`.
+    pub fn jsonapi_patch_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PatchRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::PATCH, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonPostRelationship`.
+    pub fn jsonapi_post_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: PostRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::POST, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}/relationships/{relationship}` for a struct that
+    /// implements `JsonDeleteRelationship`.
+    pub fn jsonapi_delete_relationship<T>(&mut self)
+    where
+        T: 'static,
+        T: DeleteRelationshipDispatcher,
+        T::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        let path = format!("/{}/:id/relationships/:relationship", T::RESOURCE_NAME);
+        self.route(Method::DELETE, path, |req, r| {
+            T::dispatch(req, || <T::Context as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the routes `GET /my-resources/{id}/{relationship}` for a struct that implements
+    /// `JsonGetRelated<R>`, for each relationship that refers to `R`. Call this once for each
+    /// type of related resource.
+    pub fn jsonapi_get_related<T, R>(&mut self)
+    where
+        T: 'static,
+        T: GetRelatedDispatcher<R>,
+        T::RelationshipField: Copy + Send + Sync + 'static,
+        <T as JsonGetRelated<R>>::Context: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error,
+        R: JsonApiResource + ToJson + 'static
+    {
+        let relationships = T::RelationshipField::all()
+            .into_iter()
+            .filter(|r| r.type_name() == R::RESOURCE_NAME);
+
+        for relationship in relationships {
+            let path = format!("/{}/:id/{}", T::RESOURCE_NAME, relationship.name());
+            self.route(Method::GET, path, move |req, r| {
+                let context =
+                    || <<T as JsonGetRelated<R>>::Context as FromRequest>::from_request(r);
+                <T as GetRelatedDispatcher<R>>::dispatch(req, relationship, context)
+            });
+        }
+    }
+
+    /// Setup the route `GET /my-resources` for a struct that implements `AsyncJsonIndex`.
+    pub fn jsonapi_index_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncIndexDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::GET, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `GET /my-resources/{id}` for a struct that implements `AsyncJsonGet`.
+    pub fn jsonapi_get_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncGetDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::GET, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `DELETE /my-resources/{id}` for a struct that implements `AsyncJsonDelete`.
+    pub fn jsonapi_delete_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncDeleteDispatcher,
+        T::AsyncContext: FromRequest,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::DELETE, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /my-resources` for a struct that implements `AsyncJsonPost`.
+    pub fn jsonapi_post_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPostDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField
+    {
+        self.route_async(Method::POST, format!("/{}", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `PATCH /my-resources/{id}` for a struct that implements `AsyncJsonPatch`.
+    pub fn jsonapi_patch_async<T>(&mut self)
+    where
+        T: 'static,
+        T: AsyncPatchDispatcher,
+        T::AsyncContext: FromRequest,
+        T::SortField: for<'b> TryFrom<(&'b str, SortOrder), Error = QueryStringParseError>,
+        T::FieldSetField: JsonApiFieldSetField,
+        <T::JsonApiIdType as FromStr>::Err: Error
+    {
+        self.route_async(Method::PATCH, format!("/{}/:id", T::RESOURCE_NAME), |req, r| {
+            T::dispatch(req, || <T::AsyncContext as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Setup the route `POST /operations` for the Atomic Operations extension, see
+    /// `JsonApiRouterBuilder::jsonapi_operations`.
+    pub fn jsonapi_operations<C>(&mut self, operations: OperationsHandler<C>)
+    where
+        C: FromRequest + JsonApiTransaction + Clone + 'static
+    {
+        self.add_extension(ATOMIC_EXTENSION);
+        self.route(Method::POST, "/operations".to_string(), move |req, r| {
+            operations.dispatch(req, || <C as FromRequest>::from_request(r))
+        });
+    }
+
+    /// Constructs a `JsonApiService` with the routes that were previously specified in
+    /// `jsonapi_get`, `jsonapi_post` et cetera.
+    pub fn build(self) -> JsonApiService {
+        JsonApiService {
+            inner: Arc::new(self)
+        }
+    }
+
+    fn route<F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        F: Fn(&JsonApiRequest, &Parts) -> JsonApiResponse + Send + Sync + 'static
+    {
+        self.route_async(method, path, move |req, r| dispatch(req, r).into());
+    }
+
+    fn route_async<F>(&mut self, method: Method, path: String, dispatch: F)
+    where
+        F: Fn(&JsonApiRequest, &Parts) -> JsonApiResponseFuture + Send + Sync + 'static
+    {
+        self.routes.add(method.as_str(), &path, dispatch);
+    }
+
+    /// Dispatches the request, along with the extensions and profiles that apply to it. These
+    /// are sent in the `Content-Type` once the response is ready.
+    fn respond(
+        &self,
+        parts: &Parts,
+        body: Result<Vec<u8>, RequestError>
+    ) -> (JsonApiResponseFuture, JsonApiMediaType) {
+        let body = match body {
+            Ok(body) => body,
+            Err(e @ RequestError::BodyTooLarge) => {
+                return (JsonApiResponse::error(&e, 413).into(), JsonApiMediaType::default());
+            }
+            Err(e) => return (JsonApiResponse::error(&e, 400).into(), JsonApiMediaType::default())
+        };
+
+        let request = json_api_request(parts, body, base_url(parts, &self.base_url));
+        match self.negotiation.negotiate(&request) {
+            Ok(media_type) => {
+                let request = request.with_media_type(media_type.clone());
+                (self.routes.dispatch(request, parts), media_type)
+            }
+            Err((e, status)) => {
+                (JsonApiResponse::error(&e, status).into(), JsonApiMediaType::default())
+            }
+        }
+    }
+}
+
+/// A tower `Service` that routes requests to the endpoints of the registered resources. This is
+/// constructed with `JsonApiServiceBuilder`, and is cheap to clone. The service never fails, any
+/// errors are rendered as a JSONAPI error document instead.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)] // The dispatch closures don't implement Debug...
+pub struct JsonApiService {
+    inner: Arc<JsonApiServiceBuilder>
+}
+
+impl<B> Service<Request<B>> for JsonApiService
+where
+    B: Body + Send + 'static
+{
+    type Response = Response<String>;
+    type Error = Infallible;
+    type Future = Pin<Box<Future<Output = Result<Response<String>, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<B>) -> Self::Future {
+        let (parts, body) = req.into_parts();
+
+        Box::pin(Respond {
+            inner: self.inner.clone(),
+            parts: Some(parts),
+            body: Box::pin(body),
+            buffer: Vec::new(),
+            response: None,
+            media_type: JsonApiMediaType::default()
+        })
+    }
+}
+
+/// Reads the body of a request, dispatches the request, and then waits for the response of the
+/// dispatcher.
+struct Respond<B> {
+    inner: Arc<JsonApiServiceBuilder>,
+    parts: Option<Parts>,
+    body: Pin<Box<B>>,
+    buffer: Vec<u8>,
+    response: Option<JsonApiResponseFuture>,
+    media_type: JsonApiMediaType
+}
+
+impl<B: Body> Respond<B> {
+    /// Reads the rest of the body, unless it's larger than the max body length.
+    fn poll_body(&mut self, cx: &mut Context) -> Poll<Result<Vec<u8>, RequestError>> {
+        loop {
+            match self.body.as_mut().poll_data(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => return Poll::Ready(Ok(self.buffer.split_off(0))),
+                Poll::Ready(Some(Err(_))) => {
+                    return Poll::Ready(Err(RequestError::UnreadableBody));
+                }
+                Poll::Ready(Some(Ok(mut data))) => {
+                    if self.buffer.len() + data.remaining() > self.inner.max_body_length {
+                        return Poll::Ready(Err(RequestError::BodyTooLarge));
+                    }
+
+                    while data.has_remaining() {
+                        let len = data.chunk().len();
+                        self.buffer.extend_from_slice(data.chunk());
+                        data.advance(len);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<B: Body> Future for Respond<B> {
+    type Output = Result<Response<String>, Infallible>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if this.response.is_none() {
+            let body = match this.poll_body(cx) {
+                Poll::Ready(body) => body,
+                Poll::Pending => return Poll::Pending
+            };
+
+            let parts = this.parts.take().expect("Respond polled after completion!");
+            let (response, media_type) = this.inner.respond(&parts, body);
+            this.response = Some(response);
+            this.media_type = media_type;
+        }
+
+        match this.response.as_mut().map(|response| response.as_mut().poll(cx)) {
+            Some(Poll::Ready(response)) => {
+                Poll::Ready(Ok(tower_response(response.with_media_type(&this.media_type))))
+            }
+            _ => Poll::Pending
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! The validators that are used by `#[jsonapi(validate(...))]` field attributes.
+//!
+//! Each validator is run on the value of a field after a resource is converted from a
+//! `JsonApiData` (with `try_into()` or `patch()`). A field of type `Option<T>` is only validated
+//! if it's `Some`. The failed validations are returned as a `ConversionError::Validation`, and
+//! each `ValidationError` is rendered with the status code `422` and a `source.pointer` to
+//! `/data/attributes/<field>`.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! #[derive(Debug, Default, JsonApi)]
+//! struct Article {
+//!     id: String,
+//!     #[jsonapi(validate(length(min = 1, max = 200)))]
+//!     title: String,
+//!     #[jsonapi(validate(range(min = 0, max = 5)))]
+//!     rating: i32,
+//!     #[jsonapi(validate(regex = "^[a-z-]+$"))]
+//!     slug: String,
+//!     #[jsonapi(validate(email))]
+//!     contact: Option<String>,
+//!     #[jsonapi(validate(custom = "not_draft"))]
+//!     status: String
+//! }
+//!
+//! fn not_draft(status: &str) -> Result<(), String> {
+//!     if status == "draft" {
+//!         Err("status cannot be draft".to_string())
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//! ```
+//!
+//! A custom validator is a function that takes a reference to the value of the field (or anything
+//! it derefs to, such as `&str` for a `String`), and returns `Err` with a message if the value is
+//! invalid. Its path is resolved relative to the module where `JsonApi` is derived.
+
+extern crate regex;
+
+use error::JsonApiErrorDetails;
+use error::JsonApiErrorSource;
+use self::regex::Regex;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::sync::OnceLock;
+
+static INVALID_ATTRIBUTE: &'static str = "Invalid attribute";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A failed validation of the attribute `field`.
+pub struct ValidationError {
+    /// The name of the attribute that failed validation.
+    pub field: String,
+    /// The validator that failed, e.g `length` or `email`. This is the `code` of the error object.
+    pub code: String,
+    pub message: String
+}
+
+impl ValidationError {
+    pub fn new<F, C, M>(field: F, code: C, message: M) -> Self
+    where
+        F: Into<String>,
+        C: Into<String>,
+        M: Into<String>
+    {
+        ValidationError {
+            field: field.into(),
+            code: code.into(),
+            message: message.into()
+        }
+    }
+}
+
+impl Error for ValidationError {
+    fn description(&self) -> &str {
+        INVALID_ATTRIBUTE
+    }
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter) -> ::std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl JsonApiErrorDetails for ValidationError {
+    fn code(&self) -> Option<String> {
+        Some(self.code.clone())
+    }
+
+    fn error_source(&self) -> Option<JsonApiErrorSource> {
+        Some(JsonApiErrorSource::pointer(format!("/data/attributes/{}", self.field)))
+    }
+}
+
+/// A value with a length that can be validated with `length`.
+pub trait HasLength {
+    /// The number of characters of a string, or the number of elements of a collection.
+    fn length(&self) -> usize;
+}
+
+impl HasLength for str {
+    fn length(&self) -> usize {
+        self.chars().count()
+    }
+}
+
+impl HasLength for String {
+    fn length(&self) -> usize {
+        self.as_str().length()
+    }
+}
+
+impl<T> HasLength for [T] {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<T> HasLength for Vec<T> {
+    fn length(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Checks that the length of `value` is at least `min` and at most `max`.
+pub fn length<T: HasLength + ?Sized>(
+    field: &str,
+    value: &T,
+    min: Option<usize>,
+    max: Option<usize>
+) -> Result<(), ValidationError> {
+    let length = value.length();
+    if min.map_or(true, |min| length >= min) && max.map_or(true, |max| length <= max) {
+        return Ok(());
+    }
+
+    let expected = match (min, max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => unreachable!()
+    };
+
+    Err(ValidationError::new(
+        field,
+        "length",
+        format!("{} must have a length of {}, but was {}", field, expected, length)
+    ))
+}
+
+/// Checks that `value` is at least `min` and at most `max`.
+pub fn range<T: PartialOrd + Display>(
+    field: &str,
+    value: &T,
+    min: Option<T>,
+    max: Option<T>
+) -> Result<(), ValidationError> {
+    let above_min = min.as_ref().map_or(true, |min| value >= min);
+    let below_max = max.as_ref().map_or(true, |max| value <= max);
+    if above_min && below_max {
+        return Ok(());
+    }
+
+    let expected = match (min, max) {
+        (Some(min), Some(max)) => format!("between {} and {}", min, max),
+        (Some(min), None) => format!("at least {}", min),
+        (None, Some(max)) => format!("at most {}", max),
+        (None, None) => unreachable!()
+    };
+
+    Err(ValidationError::new(
+        field,
+        "range",
+        format!("{} must be {}, but was {}", field, expected, value)
+    ))
+}
+
+/// A regular expression of a `regex` validation, which is compiled the first time it's used. The
+/// derive declares one as a `static` for each `regex` validation, so that the pattern is only
+/// compiled once.
+#[derive(Debug)]
+pub struct Pattern {
+    pattern: &'static str,
+    regex: OnceLock<Regex>
+}
+
+impl Pattern {
+    pub const fn new(pattern: &'static str) -> Self {
+        Pattern {
+            pattern: pattern,
+            regex: OnceLock::new()
+        }
+    }
+
+    /// The compiled regular expression.
+    ///
+    /// # Panics
+    ///
+    /// If the pattern isn't a valid regular expression. `#[jsonapi(validate(regex = "..."))]`
+    /// rejects invalid patterns at compile time, so this can only happen if the `Pattern` is
+    /// created by hand.
+    pub fn regex(&self) -> &Regex {
+        self.regex.get_or_init(|| {
+            Regex::new(self.pattern)
+                .unwrap_or_else(|e| panic!("Invalid regex {}: {}", self.pattern, e))
+        })
+    }
+}
+
+/// Checks that `value` matches the regular expression `pattern`.
+pub fn regex<T: AsRef<str> + ?Sized>(
+    field: &str,
+    value: &T,
+    pattern: &Pattern
+) -> Result<(), ValidationError> {
+    if pattern.regex().is_match(value.as_ref()) {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            field,
+            "regex",
+            format!("{} must match {}", field, pattern.pattern)
+        ))
+    }
+}
+
+/// Checks that `value` looks like an email address, i.e that it has a non-empty local part and a
+/// domain with at least one dot, separated by a single `@`, and no whitespace.
+pub fn email<T: AsRef<str> + ?Sized>(field: &str, value: &T) -> Result<(), ValidationError> {
+    let value = value.as_ref();
+    let mut parts = value.split('@');
+    let valid = match (parts.next(), parts.next(), parts.next()) {
+        (Some(local), Some(domain), None) => {
+            !local.is_empty() && !value.contains(char::is_whitespace) &&
+                domain.split('.').count() > 1 && domain.split('.').all(|part| !part.is_empty())
+        }
+        _ => false
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(ValidationError::new(
+            field,
+            "email",
+            format!("{} must be a valid email address", field)
+        ))
+    }
+}
+
+/// Runs the custom validator `validator` on `value`.
+pub fn custom<T: ?Sized, F>(field: &str, value: &T, validator: F) -> Result<(), ValidationError>
+where
+    F: FnOnce(&T) -> Result<(), String>
+{
+    validator(value).map_err(|message| ValidationError::new(field, "custom", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validate_length() {
+        assert!(length("title", "abc", Some(1), Some(3)).is_ok());
+        assert!(length("title", "åäö", None, Some(3)).is_ok());
+        assert!(length("tags", &vec![1, 2], Some(3), None).is_err());
+
+        let error = length("title", "", Some(1), Some(200)).unwrap_err();
+        assert_eq!("length", error.code);
+        assert_eq!("title must have a length of between 1 and 200, but was 0", error.message);
+    }
+
+    #[test]
+    fn validate_range() {
+        assert!(range("rating", &3, Some(0), Some(5)).is_ok());
+        assert!(range("rating", &0.5, Some(1.0), None).is_err());
+
+        let error = range("rating", &6, None, Some(5)).unwrap_err();
+        assert_eq!("rating must be at most 5, but was 6", error.message);
+    }
+
+    #[test]
+    fn validate_regex() {
+        static SLUG: Pattern = Pattern::new("^[a-z-]+$");
+        assert!(regex("slug", "a-slug", &SLUG).is_ok());
+        assert!(regex("slug", "Not a slug", &SLUG).is_err());
+    }
+
+    #[test]
+    fn validate_email() {
+        assert!(email("contact", "foo@example.com").is_ok());
+        assert!(email("contact", "foo@example").is_err());
+        assert!(email("contact", "@example.com").is_err());
+        assert!(email("contact", "foo@bar@example.com").is_err());
+        assert!(email("contact", "foo bar@example.com").is_err());
+    }
+
+    #[test]
+    fn validate_custom() {
+        let error = custom("status", "draft", |_| Err("no drafts".to_string())).unwrap_err();
+        assert_eq!(ValidationError::new("status", "custom", "no drafts"), error);
+    }
+
+    #[test]
+    fn validation_error_to_error_object() {
+        let error = ValidationError::new("title", "length", "title is too long");
+        let json = ::error::JsonApiError::from_error(&error, 422);
+
+        assert_eq!("Invalid attribute", json.title);
+        assert_eq!("422", json.status);
+        assert_eq!(Some("length".to_string()), json.code);
+        assert_eq!(
+            Some("/data/attributes/title".to_string()),
+            json.source.and_then(|s| s.pointer)
+        );
+    }
+}